@@ -15,7 +15,7 @@ fn main() -> Result<()> {
         w: 1280,
         h: 720,
         use_high_dpi: false,
-        settings: Default::default(),
+        ..Default::default()
     }
     .init(|window_builder| {
         window_builder.position_centered();
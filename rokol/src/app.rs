@@ -4,15 +4,20 @@ Application ([`FFI`])
 [`FFI`]: rokol_ffi::app
 
 [`Rokol::run`](crate::glue::sapp::Rokol::run) runs an implementation of [`RApp`].
+[`record`] records the events into a file and replays them without a window.
 */
 
 use std::{
     ffi::{c_void, CStr, CString},
+    fmt,
     os::raw::c_char,
+    path::PathBuf,
 };
 
 use {bitflags::bitflags, rokol_ffi::app as ffi};
 
+pub mod record;
+
 // --------------------------------------------------------------------------------
 // Hidden items
 
@@ -48,9 +53,7 @@ pub trait RApp {
     /// The cleanup-callback isn't guaranteed to be called on the web and mobile platforms.
     #[cfg(feature = "impl-gfx")]
     fn cleanup(&mut self) {
-        unsafe {
-            rokol_ffi::gfx::sg_shutdown();
-        }
+        crate::gfx::shutdown();
     }
     #[cfg(not(feature = "impl-gfx"))]
     fn cleanup(&mut self);
@@ -64,6 +67,22 @@ pub trait RApp {
     /// sokol-app versions).
     fn event(&mut self, _ev: &Event) {}
 
+    /// Typed event handling, called right after [`RApp::event`] with the same event
+    ///
+    /// The same restrictions as [`RApp::event`] apply.
+    fn app_event(&mut self, _ev: &AppEvent) {}
+
+    /// Audio stream set up right after [`RApp::init`] (`impl-audio` feature)
+    ///
+    /// The stream runs on the audio thread, so it's a separate object from the app; share states
+    /// with channels or atomics. Audio is shut down after [`RApp::cleanup`].
+    #[cfg(feature = "impl-audio")]
+    fn audio_stream(
+        &mut self,
+    ) -> Option<(crate::audio::AudioDesc, Box<dyn crate::audio::AudioStream>)> {
+        None
+    }
+
     /// Called when a fatal error is encountered during start which doesn't allow the program to
     /// continue
     ///
@@ -88,18 +107,19 @@ pub trait RAppFfiCallback {
     extern "C" fn cleanup_userdata_cb(user_data: *mut c_void);
     extern "C" fn event_userdata_cb(event: *const ffi::sapp_event, user_data: *mut c_void);
     extern "C" fn fail_userdata_cb(message: *const c_char, user_data: *mut c_void);
-    // extern "C" fn stream_userdata_cb(
-    //     buffer: *mut f32,
-    //     num_frames: c_uint,
-    //     num_channels: c_uint,
-    //     user_data: *mut c_void,
-    // );
 }
 
 impl<T: RApp> RAppFfiCallback for T {
     extern "C" fn init_userdata_cb(user_data: *mut c_void) {
         let me: &mut Self = unsafe { &mut *(user_data as *mut Self) };
         me.init();
+
+        #[cfg(feature = "impl-audio")]
+        if let Some((desc, stream)) = me.audio_stream() {
+            if let Err(err) = crate::audio::setup_boxed_stream(&desc, stream) {
+                log::warn!("rokol: {}", err);
+            }
+        }
     }
 
     extern "C" fn frame_userdata_cb(user_data: *mut c_void) {
@@ -110,6 +130,9 @@ impl<T: RApp> RAppFfiCallback for T {
     extern "C" fn cleanup_userdata_cb(user_data: *mut c_void) {
         let me: &mut Self = unsafe { &mut *(user_data as *mut Self) };
         me.cleanup();
+
+        #[cfg(feature = "impl-audio")]
+        crate::audio::shutdown();
     }
 
     extern "C" fn event_userdata_cb(event: *const ffi::sapp_event, user_data: *mut c_void) {
@@ -118,6 +141,10 @@ impl<T: RApp> RAppFfiCallback for T {
         let ev: &Event = unsafe { &*(event as *const _) };
 
         me.event(ev);
+
+        if let Some(ev) = AppEvent::from_ffi(ev) {
+            me.app_event(&ev);
+        }
     }
 
     extern "C" fn fail_userdata_cb(message: *const c_char, user_data: *mut c_void) {
@@ -134,21 +161,6 @@ impl<T: RApp> RAppFfiCallback for T {
         let me: &mut Self = unsafe { &mut *(user_data as *mut Self) };
         me.fail(msg);
     }
-
-    // extern "C" fn stream_userdata_cb(
-    //     buffer: *mut f32,
-    //     num_frames: c_uint,
-    //     num_channels: c_uint,
-    //     user_data: *mut c_void,
-    // ) {
-    //     let arr = unsafe {
-    //         let n_bytes = num_frames * num_channels;
-    //         std::slice::from_raw_parts_mut(buffer, n_bytes as usize)
-    //     };
-    //
-    //     let me: &mut Self = unsafe { &mut *(user_data as *mut Self) };
-    //     me.audio_stream(arr, num_frames, num_channels);
-    // }
 }
 
 // --------------------------------------------------------------------------------
@@ -341,6 +353,7 @@ ffi_enum! {
 
 bitflags! {
     /// Rokol modifier keys as bitflags
+    #[derive(Default)]
     pub struct Mod: u32 {
         const SHIFT = ffi::SAPP_MODIFIER_SHIFT as u32;
         const CONTROL = ffi::SAPP_MODIFIER_CTRL as u32;
@@ -349,6 +362,59 @@ bitflags! {
     }
 }
 
+/// Error when parsing [`Key`] or [`Mouse`] from a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNameError {
+    pub name: String,
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown name `{}`", self.name)
+    }
+}
+
+impl std::error::Error for ParseNameError {}
+
+/// Display, parsing and serde via the variant names (e.g. `Space`, `LeftControl`, `Kbd1`)
+macro_rules! impl_names {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(self.name())
+                }
+            }
+
+            impl std::str::FromStr for $ty {
+                type Err = ParseNameError;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    Self::from_name(s).ok_or_else(|| ParseNameError {
+                        name: s.to_string(),
+                    })
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for $ty {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(self.name())
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for $ty {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let name = String::deserialize(deserializer)?;
+                    name.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_names!(Key, Mouse);
+
 // --------------------------------------------------------------------------------
 // Re-exports
 //
@@ -367,6 +433,157 @@ pub type Event = ffi::sapp_event;
 
 pub type Range = ffi::sapp_range;
 
+// --------------------------------------------------------------------------------
+// Typed events
+
+/// Phase of [`AppEvent::Touches`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    Began,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+/// [`Event`] as a Rust enum. Positions and sizes are in framebuffer pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppEvent {
+    KeyDown {
+        key: Key,
+        repeat: bool,
+        mods: Mod,
+    },
+    KeyUp {
+        key: Key,
+        mods: Mod,
+    },
+    /// Text input (including control characters such as backspace)
+    Char(char),
+    MouseDown {
+        button: Mouse,
+        pos: [f32; 2],
+        mods: Mod,
+    },
+    MouseUp {
+        button: Mouse,
+        pos: [f32; 2],
+        mods: Mod,
+    },
+    MouseScroll {
+        delta: [f32; 2],
+        mods: Mod,
+    },
+    /// `delta` is the movement since the last event (also while the mouse is locked)
+    MouseMove {
+        pos: [f32; 2],
+        delta: [f32; 2],
+    },
+    MouseEnter,
+    MouseLeave,
+    /// (Multi touch) All the touches; [`TouchPoint::changed`] is set to the ones that changed
+    Touches(TouchPhase, Vec<TouchPoint>),
+    /// New framebuffer size
+    Resized {
+        w: u32,
+        h: u32,
+    },
+    Iconified,
+    Restored,
+    Focused,
+    Unfocused,
+    /// (Mobile)
+    Suspended,
+    /// (Mobile)
+    Resumed,
+    UpdateCursor,
+    /// Call [`cancel_quit`] here to keep running
+    QuitRequested,
+    /// (Clipboard) The pasted text (lossy UTF-8)
+    ClipboardPasted(String),
+    /// (Drag) Paths of the dropped files
+    FilesDropped(Vec<PathBuf>),
+}
+
+impl AppEvent {
+    /// Converts an FFI event. Returns `None` for invalid events.
+    ///
+    /// Clipboard and dropped file events read Sokol's buffers, so call this inside the event
+    /// callback.
+    pub fn from_ffi(ev: &Event) -> Option<Self> {
+        let key = || Key::from_ffi(ev.key_code);
+        let mods = Mod::from_bits_truncate(ev.modifiers);
+        let pos = [ev.mouse_x, ev.mouse_y];
+        let touches = |phase| {
+            let n = ev.num_touches.clamp(0, ev.touches.len() as i32) as usize;
+            Self::Touches(phase, ev.touches[0..n].to_vec())
+        };
+
+        Some(match EventType::from_ffi(ev.type_) {
+            EventType::KeyDown => Self::KeyDown {
+                key: key(),
+                repeat: ev.key_repeat,
+                mods,
+            },
+            EventType::KeyUp => Self::KeyUp { key: key(), mods },
+            EventType::Char => Self::Char(char::from_u32(ev.char_code)?),
+            EventType::MouseDown => Self::MouseDown {
+                button: Mouse::from_ffi(ev.mouse_button),
+                pos,
+                mods,
+            },
+            EventType::MouseUp => Self::MouseUp {
+                button: Mouse::from_ffi(ev.mouse_button),
+                pos,
+                mods,
+            },
+            EventType::MouseScroll => Self::MouseScroll {
+                delta: [ev.scroll_x, ev.scroll_y],
+                mods,
+            },
+            EventType::MouseMove => Self::MouseMove {
+                pos,
+                delta: [ev.mouse_dx, ev.mouse_dy],
+            },
+            EventType::MouseEnter => Self::MouseEnter,
+            EventType::MouseLeave => Self::MouseLeave,
+            EventType::TouchesBegin => touches(TouchPhase::Began),
+            EventType::TouchesMoved => touches(TouchPhase::Moved),
+            EventType::TouchesEnded => touches(TouchPhase::Ended),
+            EventType::TouchesCancelled => touches(TouchPhase::Cancelled),
+            EventType::Resized => Self::Resized {
+                w: ev.framebuffer_width.max(0) as u32,
+                h: ev.framebuffer_height.max(0) as u32,
+            },
+            EventType::Iconified => Self::Iconified,
+            EventType::Restored => Self::Restored,
+            EventType::Focused => Self::Focused,
+            EventType::Unocused => Self::Unfocused,
+            EventType::Suspended => Self::Suspended,
+            EventType::Resumed => Self::Resumed,
+            EventType::UpdateCursor => Self::UpdateCursor,
+            EventType::QuitRequested => Self::QuitRequested,
+            EventType::ClipboardPasted => {
+                let ptr = unsafe { ffi::sapp_get_clipboard_string() };
+                let text = if ptr.is_null() {
+                    String::new()
+                } else {
+                    unsafe { CStr::from_ptr(ptr) }
+                        .to_string_lossy()
+                        .into_owned()
+                };
+                Self::ClipboardPasted(text)
+            }
+            EventType::FilesDropped => Self::FilesDropped(
+                (0..self::n_dropped_files())
+                    .filter_map(|ix| self::dropped_file_path(ix).ok())
+                    .map(PathBuf::from)
+                    .collect(),
+            ),
+            EventType::InvalidEvent | EventType::_Num | EventType::_ForceU32 => return None,
+        })
+    }
+}
+
 pub type IconDesc = ffi::sapp_icon_desc;
 
 pub fn set_icon(icon: &IconDesc) {
@@ -386,6 +603,9 @@ pub fn is_valid() -> bool {
 /// screen size is 1280x720). Divided it with [`dpi_scale`] to get size for e.g.
 /// [`crate::gfx::ImageDesc`].
 pub fn width() -> u32 {
+    if let Some(clock) = record::clock() {
+        return clock.size[0];
+    }
     // it's always bigger than zero, so this is safe
     unsafe { ffi::sapp_width() as u32 }
 }
@@ -395,6 +615,9 @@ pub fn width() -> u32 {
 /// screen size is 1280x720). Divided it with [`dpi_scale`] to get size for e.g.
 /// [`crate::gfx::ImageDesc`].
 pub fn height() -> u32 {
+    if let Some(clock) = record::clock() {
+        return clock.size[1];
+    }
     // it's always bigger than zero, so this is safe
     unsafe { ffi::sapp_height() as u32 }
 }
@@ -407,10 +630,16 @@ pub fn size() -> [u32; 2] {
 }
 
 pub fn width_f() -> f32 {
+    if let Some(clock) = record::clock() {
+        return clock.size[0] as f32;
+    }
     unsafe { ffi::sapp_widthf() }
 }
 
 pub fn height_f() -> f32 {
+    if let Some(clock) = record::clock() {
+        return clock.size[1] as f32;
+    }
     unsafe { ffi::sapp_heightf() }
 }
 
@@ -449,6 +678,9 @@ pub fn is_high_dpi() -> bool {
 
 /// Dpi scaling factor (window pixels to framebuffer pixels)
 pub fn dpi_scale() -> f32 {
+    if let Some(clock) = record::clock() {
+        return clock.dpi_scale;
+    }
     unsafe { ffi::sapp_dpi_scale() }
 }
 
@@ -537,11 +769,17 @@ pub fn consume_event() {
 
 /// Current frame counter (for comparison with sapp_event.frame_count)
 pub fn frame_count() -> u64 {
+    if let Some(clock) = record::clock() {
+        return clock.frame;
+    }
     unsafe { ffi::sapp_frame_count() }
 }
 
 /// Frame duration in seconds averaged over a number of frames to smooth out any jittering spikes
 pub fn frame_duration() -> f64 {
+    if let Some(clock) = record::clock() {
+        return clock.frame_duration;
+    }
     unsafe { ffi::sapp_frame_duration() }
 }
 
@@ -575,9 +813,14 @@ pub fn n_dropped_files() -> u32 {
 }
 
 /// (Drag) The dropped file paths
+///
+/// The path is copied; Sokol owns the original string.
 pub fn dropped_file_path(ix: u32) -> Result<String, std::str::Utf8Error> {
     let ptr = unsafe { ffi::sapp_get_dropped_file_path(ix as i32) };
-    let c_str = unsafe { CString::from_raw(ptr as *mut _) };
+    if ptr.is_null() {
+        return Ok(String::new());
+    }
+    let c_str = unsafe { CStr::from_ptr(ptr) };
     c_str.to_str().map(|s| s.to_string())
 }
 
@@ -628,3 +871,98 @@ pub fn dropped_file_path(ix: u32) -> Result<String, std::str::Utf8Error> {
 
 // // /* Android: get native activity handle */
 // // SOKOL_API_DECL const void* sapp_android_get_native_activity(void);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(ty: EventType, f: impl FnOnce(&mut Event)) -> Event {
+        let mut ev = Event {
+            type_: ty.to_ffi(),
+            ..Default::default()
+        };
+        f(&mut ev);
+        ev
+    }
+
+    fn touch(identifier: usize, changed: bool) -> TouchPoint {
+        TouchPoint {
+            identifier,
+            pos_x: identifier as f32,
+            pos_y: 0.0,
+            changed,
+        }
+    }
+
+    #[test]
+    fn app_event_from_ffi() {
+        let table = [
+            (
+                event(EventType::KeyDown, |ev| {
+                    ev.key_code = Key::A.to_ffi();
+                    ev.key_repeat = true;
+                    ev.modifiers = (Mod::CONTROL | Mod::SHIFT).bits();
+                }),
+                Some(AppEvent::KeyDown {
+                    key: Key::A,
+                    repeat: true,
+                    mods: Mod::CONTROL | Mod::SHIFT,
+                }),
+            ),
+            (
+                event(EventType::Char, |ev| ev.char_code = 'あ' as u32),
+                Some(AppEvent::Char('あ')),
+            ),
+            // surrogate
+            (event(EventType::Char, |ev| ev.char_code = 0xd800), None),
+            (
+                event(EventType::MouseMove, |ev| {
+                    ev.mouse_x = 1.0;
+                    ev.mouse_y = 2.0;
+                    ev.mouse_dx = -3.0;
+                    ev.mouse_dy = 4.0;
+                }),
+                Some(AppEvent::MouseMove {
+                    pos: [1.0, 2.0],
+                    delta: [-3.0, 4.0],
+                }),
+            ),
+            (
+                event(EventType::TouchesMoved, |ev| {
+                    ev.num_touches = 2;
+                    ev.touches[0] = touch(1, false);
+                    ev.touches[1] = touch(2, true);
+                    ev.touches[2] = touch(3, true);
+                }),
+                Some(AppEvent::Touches(
+                    TouchPhase::Moved,
+                    vec![touch(1, false), touch(2, true)],
+                )),
+            ),
+            // `num_touches` is clamped to the array
+            (
+                event(EventType::TouchesBegin, |ev| ev.num_touches = 100),
+                Some(AppEvent::Touches(
+                    TouchPhase::Began,
+                    vec![touch(0, false); 8],
+                )),
+            ),
+            (
+                event(EventType::TouchesEnded, |ev| ev.num_touches = -1),
+                Some(AppEvent::Touches(TouchPhase::Ended, vec![])),
+            ),
+            (
+                event(EventType::Resized, |ev| {
+                    ev.framebuffer_width = 1280;
+                    ev.framebuffer_height = -1;
+                }),
+                Some(AppEvent::Resized { w: 1280, h: 0 }),
+            ),
+            (event(EventType::InvalidEvent, |_| {}), None),
+        ];
+
+        for (ev, expected) in &table {
+            assert_eq!(&AppEvent::from_ffi(ev), expected, "{:?}", ev);
+        }
+    }
+}
@@ -0,0 +1,797 @@
+/*!
+Records [`Event`]s into a file and replays them into an [`RApp`]
+
+# Recording
+
+Wrap the app with a [`Recorder`]. It's an [`RApp`] that forwards every callback and records the
+events with the frame they arrived in:
+
+```no_run
+use rokol::{app::{self, record::Recorder}, glue::sapp::Rokol};
+
+struct Game;
+impl app::RApp for Game {}
+
+let mut recorder = Recorder::new(Game);
+Rokol::default().run(&mut recorder).unwrap();
+recorder.recording().save("input.rkin").unwrap();
+```
+
+# Replaying
+
+[`Replayer`] drives an app without a window. It calls the same [`RApp`] methods as the
+[`RAppFfiCallback`] callbacks (audio is not set up) and advances frames with a fixed duration:
+
+```no_run
+use rokol::app::{self, record::{Recording, Replayer}};
+
+struct Game;
+impl app::RApp for Game {}
+
+let recording = Recording::load("input.rkin").unwrap();
+let mut game = Game;
+Replayer::new(recording).with_frame_duration(1.0 / 60.0).run(&mut game);
+```
+
+While replaying, [`frame_count`], [`frame_duration`], [`width`], [`height`] and [`dpi_scale`]
+return the replayed values. Clipboard and dropped files are recorded with the events and only
+available through [`RApp::app_event`]. Replay with the `dummy` graphics backend (or without
+`rokol::gfx`) when there's no window.
+
+# File format
+
+Events are stored field by field in little endian. Enums and booleans are validated on load, so a
+corrupted file is an error rather than an invalid event.
+
+[`RAppFfiCallback`]: crate::app::RAppFfiCallback
+[`frame_count`]: crate::app::frame_count
+[`frame_duration`]: crate::app::frame_duration
+[`width`]: crate::app::width
+[`height`]: crate::app::height
+[`dpi_scale`]: crate::app::dpi_scale
+*/
+
+use std::{
+    cell::Cell,
+    fmt, fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::app::{self, AppEvent, Event, EventType, Key, Mouse, RApp, TouchPoint};
+
+/// Magic number of recording files
+pub const MAGIC: [u8; 4] = *b"RKIN";
+
+/// Version of the recording file format
+pub const VERSION: u32 = 2;
+
+/// Bytes of an event with an empty payload, used to bound allocations by the file size
+const MIN_ENCODED_EVENT_SIZE: usize = {
+    // frame count, type, key code, char code, key repeat, modifiers and mouse button
+    let header = 8 + 4 + 4 + 4 + 1 + 4 + 4;
+    // mouse position, delta and scroll, then the number of touches
+    let mouse = 6 * 4 + 4;
+    let touches = crate::ffi::app::SAPP_MAX_TOUCHPOINTS as usize * (8 + 4 + 4 + 1);
+    let sizes = 4 * 4;
+    let payload_tag = 1;
+    header + mouse + touches + sizes + payload_tag
+};
+
+/// Bytes of an empty path (the length prefix)
+const MIN_ENCODED_PATH_SIZE: usize = 4;
+
+// --------------------------------------------------------------------------------
+// Replay clock
+
+/// Values returned by the `rokol::app` getters while replaying
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Clock {
+    pub frame: u64,
+    pub frame_duration: f64,
+    pub size: [u32; 2],
+    pub dpi_scale: f32,
+}
+
+thread_local! {
+    static CLOCK: Cell<Option<Clock>> = const { Cell::new(None) };
+}
+
+/// The replay clock if we're inside a [`Replayer`] callback
+pub(crate) fn clock() -> Option<Clock> {
+    CLOCK.with(|c| c.get())
+}
+
+fn with_clock<T>(clock: Clock, f: impl FnOnce() -> T) -> T {
+    let prev = CLOCK.with(|c| c.replace(Some(clock)));
+    let x = f();
+    CLOCK.with(|c| c.set(prev));
+    x
+}
+
+// --------------------------------------------------------------------------------
+// Recording
+
+/// Data read from Sokol's buffers on an event
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payload {
+    None,
+    ClipboardPasted(String),
+    FilesDropped(Vec<PathBuf>),
+}
+
+/// Recorded event. `event.frame_count` is the index of the frame in the recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    pub event: Event,
+    pub payload: Payload,
+}
+
+impl RecordedEvent {
+    /// Records an event in the event callback
+    pub fn new(event: &Event, frame: u64) -> Self {
+        let payload = match EventType::from_ffi(event.type_) {
+            EventType::ClipboardPasted | EventType::FilesDropped => {
+                match AppEvent::from_ffi(event) {
+                    Some(AppEvent::ClipboardPasted(text)) => Payload::ClipboardPasted(text),
+                    Some(AppEvent::FilesDropped(paths)) => Payload::FilesDropped(paths),
+                    _ => Payload::None,
+                }
+            }
+            _ => Payload::None,
+        };
+
+        let mut event = *event;
+        event.frame_count = frame;
+
+        Self { event, payload }
+    }
+
+    /// [`AppEvent`] made from the recorded data (without reading Sokol's buffers)
+    pub fn app_event(&self) -> Option<AppEvent> {
+        match &self.payload {
+            Payload::None => match EventType::from_ffi(self.event.type_) {
+                EventType::ClipboardPasted => Some(AppEvent::ClipboardPasted(String::new())),
+                EventType::FilesDropped => Some(AppEvent::FilesDropped(Vec::new())),
+                _ => AppEvent::from_ffi(&self.event),
+            },
+            Payload::ClipboardPasted(text) => Some(AppEvent::ClipboardPasted(text.clone())),
+            Payload::FilesDropped(paths) => Some(AppEvent::FilesDropped(paths.clone())),
+        }
+    }
+}
+
+/// Error on reading or writing a recording file
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    /// Not a recording file
+    BadMagic,
+    UnsupportedVersion(u32),
+    Corrupted(&'static str),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "IO error on recording file: {}", err),
+            Self::BadMagic => write!(f, "not a rokol recording file"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported recording file version: {}", v),
+            Self::Corrupted(why) => write!(f, "corrupted recording file: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<io::Error> for RecordingError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Recorded events of a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// Framebuffer size at the start of the recording
+    pub size: [u32; 2],
+    pub dpi_scale: f32,
+    pub n_frames: u64,
+    /// Sorted by frame
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            size: [0, 0],
+            dpi_scale: 1.0,
+            n_frames: 0,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Recording {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let mut bytes = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut bytes)?;
+        Self::read(&bytes)
+    }
+
+    pub fn write(&self, mut w: impl Write) -> Result<(), RecordingError> {
+        w.write_all(&MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+
+        w.write_all(&self.size[0].to_le_bytes())?;
+        w.write_all(&self.size[1].to_le_bytes())?;
+        w.write_all(&self.dpi_scale.to_le_bytes())?;
+        w.write_all(&self.n_frames.to_le_bytes())?;
+
+        w.write_all(&(self.events.len() as u32).to_le_bytes())?;
+        for ev in &self.events {
+            self::write_event(&mut w, &ev.event)?;
+
+            match &ev.payload {
+                Payload::None => w.write_all(&[0])?,
+                Payload::ClipboardPasted(text) => {
+                    w.write_all(&[1])?;
+                    self::write_str(&mut w, text)?;
+                }
+                Payload::FilesDropped(paths) => {
+                    w.write_all(&[2])?;
+                    w.write_all(&(paths.len() as u32).to_le_bytes())?;
+                    for path in paths {
+                        self::write_str(&mut w, &path.to_string_lossy())?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read(bytes: &[u8]) -> Result<Self, RecordingError> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        if r.take(4)? != MAGIC {
+            return Err(RecordingError::BadMagic);
+        }
+
+        let version = r.u32()?;
+        if version != VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+
+        let size = [r.u32()?, r.u32()?];
+        let dpi_scale = f32::from_bits(r.u32()?);
+        let n_frames = r.u64()?;
+
+        let len = r.u32()? as usize;
+        let mut events = Vec::with_capacity(len.min(r.remaining() / MIN_ENCODED_EVENT_SIZE));
+        for _ in 0..len {
+            let event = r.event()?;
+
+            let payload = match r.u8()? {
+                0 => Payload::None,
+                1 => Payload::ClipboardPasted(r.string()?),
+                2 => {
+                    let n = r.u32()? as usize;
+                    let mut paths =
+                        Vec::with_capacity(n.min(r.remaining() / MIN_ENCODED_PATH_SIZE));
+                    for _ in 0..n {
+                        paths.push(PathBuf::from(r.string()?));
+                    }
+                    Payload::FilesDropped(paths)
+                }
+                _ => return Err(RecordingError::Corrupted("invalid payload tag")),
+            };
+
+            events.push(RecordedEvent { event, payload });
+        }
+
+        Ok(Self {
+            size,
+            dpi_scale,
+            n_frames,
+            events,
+        })
+    }
+}
+
+fn write_event(w: &mut impl Write, ev: &Event) -> io::Result<()> {
+    w.write_all(&ev.frame_count.to_le_bytes())?;
+    w.write_all(&(ev.type_ as u32).to_le_bytes())?;
+    w.write_all(&(ev.key_code as u32).to_le_bytes())?;
+    w.write_all(&ev.char_code.to_le_bytes())?;
+    w.write_all(&[ev.key_repeat as u8])?;
+    w.write_all(&ev.modifiers.to_le_bytes())?;
+    w.write_all(&(ev.mouse_button as u32).to_le_bytes())?;
+
+    let floats = [
+        ev.mouse_x,
+        ev.mouse_y,
+        ev.mouse_dx,
+        ev.mouse_dy,
+        ev.scroll_x,
+        ev.scroll_y,
+    ];
+    for x in floats {
+        w.write_all(&x.to_le_bytes())?;
+    }
+
+    w.write_all(&ev.num_touches.to_le_bytes())?;
+    for touch in &ev.touches {
+        w.write_all(&(touch.identifier as u64).to_le_bytes())?;
+        w.write_all(&touch.pos_x.to_le_bytes())?;
+        w.write_all(&touch.pos_y.to_le_bytes())?;
+        w.write_all(&[touch.changed as u8])?;
+    }
+
+    let sizes = [
+        ev.window_width,
+        ev.window_height,
+        ev.framebuffer_width,
+        ev.framebuffer_height,
+    ];
+    for x in sizes {
+        w.write_all(&x.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_str(w: &mut impl Write, s: &str) -> io::Result<()> {
+    w.write_all(&(s.len() as u32).to_le_bytes())?;
+    w.write_all(s.as_bytes())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], RecordingError> {
+        if self.bytes.len() - self.pos < n {
+            return Err(RecordingError::Corrupted("unexpected end of file"));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, RecordingError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, RecordingError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn u64(&mut self) -> Result<u64, RecordingError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn i32(&mut self) -> Result<i32, RecordingError> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn f32(&mut self) -> Result<f32, RecordingError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn bool(&mut self) -> Result<bool, RecordingError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(RecordingError::Corrupted("invalid bool")),
+        }
+    }
+
+    fn event(&mut self) -> Result<Event, RecordingError> {
+        let frame_count = self.u64()?;
+        let type_ = EventType::from_u32(self.u32()?)
+            .ok_or(RecordingError::Corrupted("invalid event type"))?;
+        let key_code =
+            Key::from_u32(self.u32()?).ok_or(RecordingError::Corrupted("invalid key code"))?;
+        let char_code = self.u32()?;
+        let key_repeat = self.bool()?;
+        let modifiers = self.u32()?;
+        let mouse_button = Mouse::from_u32(self.u32()?)
+            .ok_or(RecordingError::Corrupted("invalid mouse button"))?;
+
+        let mut ev = Event {
+            frame_count,
+            type_: type_.to_ffi(),
+            key_code: key_code.to_ffi(),
+            char_code,
+            key_repeat,
+            modifiers,
+            mouse_button: mouse_button.to_ffi(),
+            ..Default::default()
+        };
+
+        ev.mouse_x = self.f32()?;
+        ev.mouse_y = self.f32()?;
+        ev.mouse_dx = self.f32()?;
+        ev.mouse_dy = self.f32()?;
+        ev.scroll_x = self.f32()?;
+        ev.scroll_y = self.f32()?;
+
+        ev.num_touches = self.i32()?;
+        for touch in &mut ev.touches {
+            *touch = TouchPoint {
+                identifier: self.u64()? as usize,
+                pos_x: self.f32()?,
+                pos_y: self.f32()?,
+                changed: self.bool()?,
+            };
+        }
+
+        ev.window_width = self.i32()?;
+        ev.window_height = self.i32()?;
+        ev.framebuffer_width = self.i32()?;
+        ev.framebuffer_height = self.i32()?;
+
+        Ok(ev)
+    }
+
+    fn string(&mut self) -> Result<String, RecordingError> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| RecordingError::Corrupted("invalid UTF-8 string"))
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Recorder
+
+/// [`RApp`] wrapper that records the events
+///
+/// Recording starts with the app; use [`Recorder::start`] to restart it.
+#[derive(Debug)]
+pub struct Recorder<A: RApp> {
+    app: A,
+    recording: Recording,
+    is_recording: bool,
+}
+
+impl<A: RApp> Recorder<A> {
+    pub fn new(app: A) -> Self {
+        Self {
+            app,
+            recording: Recording::default(),
+            is_recording: true,
+        }
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    pub fn into_inner(self) -> A {
+        self.app
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.is_recording
+    }
+
+    /// Discards the recorded events and starts a new recording from the next frame
+    pub fn start(&mut self) {
+        self.recording = Recording::default();
+        if app::is_valid() {
+            self.recording.size = app::size();
+            self.recording.dpi_scale = app::dpi_scale();
+        }
+        self.is_recording = true;
+    }
+
+    /// Pauses the recording. Frames are not counted either
+    pub fn stop(&mut self) {
+        self.is_recording = false;
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn take_recording(&mut self) -> Recording {
+        std::mem::take(&mut self.recording)
+    }
+}
+
+impl<A: RApp> RApp for Recorder<A> {
+    fn init(&mut self) {
+        if self.is_recording && self.recording.n_frames == 0 {
+            self.recording.size = app::size();
+            self.recording.dpi_scale = app::dpi_scale();
+        }
+        self.app.init();
+    }
+
+    fn frame(&mut self) {
+        self.app.frame();
+        if self.is_recording {
+            self.recording.n_frames += 1;
+        }
+    }
+
+    fn cleanup(&mut self) {
+        self.app.cleanup();
+    }
+
+    fn event(&mut self, ev: &Event) {
+        if self.is_recording {
+            let ev = RecordedEvent::new(ev, self.recording.n_frames);
+            self.recording.events.push(ev);
+        }
+        self.app.event(ev);
+    }
+
+    fn app_event(&mut self, ev: &AppEvent) {
+        self.app.app_event(ev);
+    }
+
+    #[cfg(feature = "impl-audio")]
+    fn audio_stream(
+        &mut self,
+    ) -> Option<(crate::audio::AudioDesc, Box<dyn crate::audio::AudioStream>)> {
+        self.app.audio_stream()
+    }
+
+    fn fail(&mut self, msg: &str) {
+        self.app.fail(msg);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Replayer
+
+/// Drives an [`RApp`] with a [`Recording`]
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    recording: Recording,
+    clock: Clock,
+    /// Index of the next event
+    cursor: usize,
+}
+
+impl Replayer {
+    /// Replays with the frame duration of 1/60 seconds
+    pub fn new(recording: Recording) -> Self {
+        let clock = Clock {
+            frame: 0,
+            frame_duration: 1.0 / 60.0,
+            size: recording.size,
+            dpi_scale: recording.dpi_scale,
+        };
+
+        Self {
+            recording,
+            clock,
+            cursor: 0,
+        }
+    }
+
+    /// Sets the value of [`app::frame_duration`] while replaying
+    pub fn with_frame_duration(mut self, secs: f64) -> Self {
+        self.clock.frame_duration = secs;
+        self
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Index of the next frame
+    pub fn frame(&self) -> u64 {
+        self.clock.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.clock.frame >= self.recording.n_frames
+    }
+
+    /// Calls [`RApp::init`], replays all the frames and calls [`RApp::cleanup`]
+    pub fn run(&mut self, app: &mut impl RApp) {
+        self.init(app);
+        while self.step(app) {}
+        self.cleanup(app);
+    }
+
+    pub fn init(&mut self, app: &mut impl RApp) {
+        with_clock(self.clock, || app.init());
+    }
+
+    /// Sends the events of the next frame and calls [`RApp::frame`]. Returns `false` if the
+    /// recording is finished
+    pub fn step(&mut self, app: &mut impl RApp) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        while let Some(ev) = self.recording.events.get(self.cursor) {
+            if ev.event.frame_count > self.clock.frame {
+                break;
+            }
+            self.cursor += 1;
+
+            if EventType::from_ffi(ev.event.type_) == EventType::Resized {
+                self.clock.size = [
+                    ev.event.framebuffer_width.max(0) as u32,
+                    ev.event.framebuffer_height.max(0) as u32,
+                ];
+            }
+
+            with_clock(self.clock, || {
+                app.event(&ev.event);
+                if let Some(app_ev) = ev.app_event() {
+                    app.app_event(&app_ev);
+                }
+            });
+        }
+
+        with_clock(self.clock, || app.frame());
+        self.clock.frame += 1;
+
+        true
+    }
+
+    pub fn cleanup(&mut self, app: &mut impl RApp) {
+        with_clock(self.clock, || app.cleanup());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Log {
+        frames: Vec<(u64, f64, Vec<Key>)>,
+        keys: Vec<Key>,
+    }
+
+    impl RApp for Log {
+        fn frame(&mut self) {
+            let keys = std::mem::take(&mut self.keys);
+            self.frames
+                .push((app::frame_count(), app::frame_duration(), keys));
+        }
+
+        fn cleanup(&mut self) {}
+
+        fn app_event(&mut self, ev: &AppEvent) {
+            if let AppEvent::KeyDown { key, .. } = ev {
+                self.keys.push(*key);
+            }
+        }
+    }
+
+    #[test]
+    fn replay_roundtrip() {
+        let key_down = |key: Key, frame| {
+            let ev = Event {
+                type_: EventType::KeyDown.to_ffi(),
+                key_code: key.to_ffi(),
+                ..Default::default()
+            };
+            RecordedEvent::new(&ev, frame)
+        };
+
+        let recording = Recording {
+            size: [640, 360],
+            dpi_scale: 1.0,
+            n_frames: 3,
+            events: vec![key_down(Key::A, 0), key_down(Key::B, 2)],
+        };
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        let recording = Recording::read(&bytes).unwrap();
+
+        let mut log = Log::default();
+        Replayer::new(recording)
+            .with_frame_duration(0.5)
+            .run(&mut log);
+
+        assert_eq!(
+            log.frames,
+            vec![
+                (0, 0.5, vec![Key::A]),
+                (1, 0.5, vec![]),
+                (2, 0.5, vec![Key::B]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_corrupted_event() {
+        let ev = Event {
+            type_: EventType::MouseDown.to_ffi(),
+            mouse_button: Mouse::Right.to_ffi(),
+            ..Default::default()
+        };
+        let recording = Recording {
+            n_frames: 1,
+            events: vec![RecordedEvent::new(&ev, 0)],
+            ..Default::default()
+        };
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        assert_eq!(Recording::read(&bytes).unwrap(), recording);
+
+        // the header is 32 bytes and the event starts with `frame_count: u64`
+        let corrupt = |offset: usize, patch: &[u8]| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + patch.len()].copy_from_slice(patch);
+            match Recording::read(&bytes) {
+                Err(RecordingError::Corrupted(why)) => why,
+                res => panic!("expected a corrupted file: {:?}", res),
+            }
+        };
+
+        assert_eq!(
+            corrupt(40, &0xdeadbeefu32.to_le_bytes()),
+            "invalid event type"
+        );
+        assert_eq!(
+            corrupt(44, &0xdeadbeefu32.to_le_bytes()),
+            "invalid key code"
+        );
+        assert_eq!(corrupt(52, &[2]), "invalid bool");
+        assert_eq!(corrupt(57, &7u32.to_le_bytes()), "invalid mouse button");
+    }
+
+    #[test]
+    fn bounded_allocation() {
+        let recording = Recording {
+            n_frames: 1,
+            events: vec![RecordedEvent::new(&Event::default(), 0)],
+            ..Default::default()
+        };
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 32 + MIN_ENCODED_EVENT_SIZE);
+
+        // huge counts fail on the end of file instead of allocating for them
+        let mut huge = bytes.clone();
+        huge[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Recording::read(&huge),
+            Err(RecordingError::Corrupted("unexpected end of file"))
+        ));
+
+        let mut huge = bytes;
+        let last = huge.len() - 1;
+        huge[last] = 2;
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Recording::read(&huge),
+            Err(RecordingError::Corrupted("unexpected end of file"))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+/*!
+Startup arguments with [`sokol_args.h`] (`impl-args` feature)
+
+Arguments are `key=value` pairs such as `width=1920 fullscreen=true`. Set up once and query
+anywhere:
+
+```no_run
+use rokol::args;
+
+args::setup_env();
+
+let width: u32 = args::value("width")
+    .and_then(|w| w.parse().ok())
+    .unwrap_or(1280);
+let is_verbose = args::boolean("verbose");
+```
+
+`glue::sapp::Rokol::from_args` fills the window settings from the well-known keys.
+
+[`sokol_args.h`]: https://github.com/floooh/sokol/blob/master/sokol_args.h
+*/
+
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
+use rokol_ffi::args as ffi;
+
+/// `sargs_desc`. Zero fields are replaced with Sokol defaults (16 arguments, 16KB of strings)
+pub type ArgsDesc = ffi::sargs_desc;
+
+/// Sets up `sokol_args.h` with the `argc` and `argv` in the `desc`. `sokol_args.h` copies the
+/// strings.
+pub fn setup(desc: &ArgsDesc) {
+    unsafe {
+        ffi::sargs_setup(desc);
+    }
+}
+
+/// Sets up `sokol_args.h` with the command line arguments of the process
+pub fn setup_env() {
+    self::setup_from(std::env::args_os().skip(1).map(|arg| {
+        // invalid UTF-8 is replaced with `U+FFFD`
+        arg.to_string_lossy().into_owned()
+    }));
+}
+
+/// Sets up `sokol_args.h` with arguments (excluding the program name)
+pub fn setup_from<S: AsRef<str>>(args: impl IntoIterator<Item = S>) {
+    // arguments with a nul byte are skipped
+    let args = std::iter::once(CString::default())
+        .chain(
+            args.into_iter()
+                .filter_map(|arg| CString::new(arg.as_ref()).ok()),
+        )
+        .collect::<Vec<_>>();
+    let mut argv = args
+        .iter()
+        .map(|arg| arg.as_ptr() as *mut c_char)
+        .collect::<Vec<_>>();
+
+    self::setup(&ArgsDesc {
+        argc: argv.len() as i32,
+        argv: argv.as_mut_ptr(),
+        // leave enough room for all the arguments
+        max_args: (argv.len() as i32).max(16),
+        ..Default::default()
+    });
+}
+
+pub fn shutdown() {
+    unsafe {
+        ffi::sargs_shutdown();
+    }
+}
+
+pub fn is_valid() -> bool {
+    unsafe { ffi::sargs_isvalid() }
+}
+
+/// Nul bytes in keys can't be found
+fn key(key: &str) -> Option<CString> {
+    CString::new(key).ok()
+}
+
+/// Copies a string owned by `sokol_args.h`
+unsafe fn to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+pub fn exists(key: &str) -> bool {
+    match self::key(key) {
+        Some(key) => unsafe { ffi::sargs_exists(key.as_ptr()) },
+        None => false,
+    }
+}
+
+/// Value of a key, or `None` if the key doesn't exist
+pub fn value(key: &str) -> Option<String> {
+    self::find(key).map(self::value_at)
+}
+
+/// Value of a key, or `default` if the key doesn't exist
+pub fn value_or(key: &str, default: &str) -> String {
+    self::value(key).unwrap_or_else(|| default.to_string())
+}
+
+pub fn equals(key: &str, value: &str) -> bool {
+    self::value(key).as_deref() == Some(value)
+}
+
+/// If the value of the key is `true`, `yes` or `on`
+pub fn boolean(key: &str) -> bool {
+    match self::key(key) {
+        Some(key) => unsafe { ffi::sargs_boolean(key.as_ptr()) },
+        None => false,
+    }
+}
+
+/// Index of a key
+pub fn find(key: &str) -> Option<usize> {
+    let key = self::key(key)?;
+    let ix = unsafe { ffi::sargs_find(key.as_ptr()) };
+    if ix < 0 {
+        None
+    } else {
+        Some(ix as usize)
+    }
+}
+
+pub fn num_args() -> usize {
+    unsafe { ffi::sargs_num_args() as usize }
+}
+
+pub fn key_at(index: usize) -> String {
+    unsafe { self::to_string(ffi::sargs_key_at(index as i32)) }
+}
+
+pub fn value_at(index: usize) -> String {
+    unsafe { self::to_string(ffi::sargs_value_at(index as i32)) }
+}
+
+/// Key/value pairs in order
+pub fn iter() -> impl Iterator<Item = (String, String)> {
+    (0..self::num_args()).map(|i| (self::key_at(i), self::value_at(i)))
+}
+
+/// Parses a boolean value in the same way as [`boolean`]
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,294 @@
+/*!
+Audio output with [`sokol_audio.h`] (`impl-audio` feature)
+
+Samples are interleaved `f32`s in `-1.0..=1.0`. There are two models:
+
+* Push: set up with [`setup`] and [`push`] samples every frame.
+* Pull: set up with [`setup_stream`] and let an [`AudioStream`] fill buffers on the audio thread.
+
+[`NullOutput`] pulls from an [`AudioStream`] without a sound card (for tests and offline
+rendering):
+
+```
+use rokol::audio::NullOutput;
+
+let mut phase = 0.0f32;
+let sine = move |frames: &mut [f32], _n_channels: u32| {
+    for x in frames.iter_mut() {
+        *x = phase.sin();
+        phase += 440.0 * std::f32::consts::TAU / 44100.0;
+    }
+};
+
+let mut out = NullOutput::new(sine, 44100, 1);
+let samples = out.render(512);
+assert_eq!(samples.len(), 512);
+```
+
+[`Mixer`] plays several [`Sound`]s (WAV, or OGG with the `ogg` feature) at once as an
+[`AudioStream`].
+
+[`sokol_audio.h`]: https://github.com/floooh/sokol/blob/master/sokol_audio.h
+*/
+
+mod mixer;
+mod sound;
+
+pub use self::{
+    mixer::{Mixer, PlayParams, VoiceId},
+    sound::{Sound, SoundError},
+};
+
+use std::{
+    error::Error,
+    fmt,
+    os::raw::{c_int, c_void},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use rokol_ffi::audio as ffi;
+
+/// `saudio_desc`. Zero fields are replaced with Sokol defaults (44100Hz, mono)
+pub type AudioDesc = ffi::saudio_desc;
+
+/// Fills audio buffers. Runs on the audio thread.
+pub trait AudioStream: Send + 'static {
+    /// Fills interleaved samples. `frames.len()` is the number of frames times `n_channels`.
+    fn fill(&mut self, frames: &mut [f32], n_channels: u32);
+}
+
+impl<F: FnMut(&mut [f32], u32) + Send + 'static> AudioStream for F {
+    fn fill(&mut self, frames: &mut [f32], n_channels: u32) {
+        (self)(frames, n_channels)
+    }
+}
+
+type BoxedStream = Box<dyn AudioStream>;
+
+/// Stream set up with [`setup_stream`] (double boxed for a thin pointer)
+static STREAM: AtomicPtr<BoxedStream> = AtomicPtr::new(ptr::null_mut());
+
+/// Set when the stream panics. The stream is muted after that.
+static PANICKED: AtomicBool = AtomicBool::new(false);
+
+/// Error on setting up a second stream with [`setup_stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupError;
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`rokol::audio` is already set up with a stream")
+    }
+}
+
+impl Error for SetupError {}
+
+unsafe extern "C" fn stream_userdata_cb(
+    buffer: *mut f32,
+    num_frames: c_int,
+    num_channels: c_int,
+    user_data: *mut c_void,
+) {
+    let stream = &mut *(user_data as *mut BoxedStream);
+    let len = (num_frames * num_channels) as usize;
+    let frames = std::slice::from_raw_parts_mut(buffer, len);
+
+    if PANICKED.load(Ordering::Relaxed) {
+        frames.fill(0.0);
+        return;
+    }
+
+    // unwinding into C is undefined behavior
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        stream.fill(frames, num_channels as u32);
+    }));
+
+    if res.is_err() {
+        log::warn!("rokol: audio stream panicked; muting it");
+        PANICKED.store(true, Ordering::Relaxed);
+        frames.fill(0.0);
+    }
+}
+
+/// Sets up `sokol_audio.h` for the push model
+pub fn setup(desc: &AudioDesc) {
+    unsafe {
+        ffi::saudio_setup(desc);
+    }
+}
+
+/// Sets up `sokol_audio.h` with an [`AudioStream`]. Stream callbacks in the `desc` are overwritten.
+///
+/// Fails if a stream is already set up (call [`shutdown`] first). If the stream panics, the panic
+/// is logged and the stream is muted.
+pub fn setup_stream(desc: &AudioDesc, stream: impl AudioStream) -> Result<(), SetupError> {
+    self::setup_boxed_stream(desc, Box::new(stream))
+}
+
+/// [`setup_stream`] with a boxed [`AudioStream`]
+pub fn setup_boxed_stream(
+    desc: &AudioDesc,
+    stream: Box<dyn AudioStream>,
+) -> Result<(), SetupError> {
+    let stream: *mut BoxedStream = Box::into_raw(Box::new(stream));
+
+    let mut desc = *desc;
+    desc.stream_cb = None;
+    desc.stream_userdata_cb = Some(self::stream_userdata_cb);
+    desc.user_data = stream as *mut c_void;
+
+    if STREAM
+        .compare_exchange(ptr::null_mut(), stream, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        drop(unsafe { Box::from_raw(stream) });
+        return Err(SetupError);
+    }
+
+    PANICKED.store(false, Ordering::Relaxed);
+    unsafe {
+        ffi::saudio_setup(&desc);
+    }
+    Ok(())
+}
+
+/// Shuts down `sokol_audio.h` and drops the [`AudioStream`]
+pub fn shutdown() {
+    unsafe {
+        // joins the audio thread
+        ffi::saudio_shutdown();
+    }
+
+    let stream = STREAM.swap(ptr::null_mut(), Ordering::AcqRel);
+    if !stream.is_null() {
+        drop(unsafe { Box::from_raw(stream) });
+    }
+}
+
+/// If the audio backend is initialized (it's not if there's no audio device)
+pub fn is_valid() -> bool {
+    unsafe { ffi::saudio_isvalid() }
+}
+
+/// Actual sample rate (can be different from the requested one)
+pub fn sample_rate() -> u32 {
+    unsafe { ffi::saudio_sample_rate() as u32 }
+}
+
+/// Actual number of channels
+pub fn channels() -> u32 {
+    unsafe { ffi::saudio_channels() as u32 }
+}
+
+/// Actual size of the backend buffer in frames
+pub fn buffer_frames() -> u32 {
+    unsafe { ffi::saudio_buffer_frames() as u32 }
+}
+
+/// Number of frames to [`push`] to fill the buffer
+pub fn expect() -> u32 {
+    unsafe { ffi::saudio_expect() as u32 }
+}
+
+/// Pushes interleaved samples and returns the number of frames actually pushed
+pub fn push(frames: &[f32]) -> u32 {
+    let n_channels = self::channels().max(1) as usize;
+    let n_frames = frames.len() / n_channels;
+    unsafe { ffi::saudio_push(frames.as_ptr(), n_frames as c_int) as u32 }
+}
+
+/// Output that pulls from an [`AudioStream`] on demand, without a sound card
+#[derive(Debug)]
+pub struct NullOutput<S: AudioStream> {
+    stream: S,
+    sample_rate: u32,
+    n_channels: u32,
+    n_rendered_frames: u64,
+}
+
+impl<S: AudioStream> NullOutput<S> {
+    pub fn new(stream: S, sample_rate: u32, n_channels: u32) -> Self {
+        assert!(n_channels > 0, "audio output requires at least one channel");
+        Self {
+            stream,
+            sample_rate,
+            n_channels,
+            n_rendered_frames: 0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u32 {
+        self.n_channels
+    }
+
+    /// Number of frames rendered so far
+    pub fn rendered_frames(&self) -> u64 {
+        self.n_rendered_frames
+    }
+
+    /// Renders `n_frames` frames
+    pub fn render(&mut self, n_frames: usize) -> Vec<f32> {
+        let mut buf = vec![0.0; n_frames * self.n_channels as usize];
+        self.render_into(&mut buf);
+        buf
+    }
+
+    /// Renders into a buffer of interleaved samples
+    pub fn render_into(&mut self, buf: &mut [f32]) {
+        debug_assert_eq!(buf.len() % self.n_channels as usize, 0, "partial frame");
+        self.stream.fill(buf, self.n_channels);
+        self.n_rendered_frames += (buf.len() / self.n_channels as usize) as u64;
+    }
+
+    pub fn stream(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn stream_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_stream(self) -> S {
+        self.stream
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stream_panic_mutes() {
+        PANICKED.store(false, Ordering::Relaxed);
+
+        let mut n_calls = 0;
+        let mut stream: BoxedStream = Box::new(move |frames: &mut [f32], n_channels: u32| {
+            assert_eq!(n_channels, 2);
+            n_calls += 1;
+            frames.fill(n_calls as f32);
+            if n_calls == 2 {
+                panic!("broken stream");
+            }
+        });
+        let user_data = &mut stream as *mut BoxedStream as *mut c_void;
+
+        let mut buf = [0.5f32; 4];
+        unsafe { stream_userdata_cb(buf.as_mut_ptr(), 2, 2, user_data) };
+        assert_eq!(buf, [1.0; 4]);
+
+        // the panic doesn't unwind into C and the stream is muted
+        for _ in 0..2 {
+            unsafe { stream_userdata_cb(buf.as_mut_ptr(), 2, 2, user_data) };
+            assert_eq!(buf, [0.0; 4]);
+        }
+        assert!(PANICKED.load(Ordering::Relaxed));
+
+        PANICKED.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,375 @@
+/*!
+Software mixer of [`Sound`]s
+*/
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::audio::{AudioStream, Sound};
+
+/// Handle of a playing voice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VoiceId(u64);
+
+/// Parameters of [`Mixer::play`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayParams {
+    pub volume: f32,
+    /// `-1.0` (left) to `1.0` (right). Clamped by the mixer.
+    pub pan: f32,
+    /// Playback rate (`2.0` is an octave higher, `0.0` pauses). Negative values are clamped to
+    /// `0.0`.
+    pub pitch: f32,
+    pub looping: bool,
+    /// Name of the bus, `None` for the master bus only
+    pub bus: Option<String>,
+}
+
+impl Default for PlayParams {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            pan: 0.0,
+            pitch: 1.0,
+            looping: false,
+            bus: None,
+        }
+    }
+}
+
+/// Pan in `-1.0..=1.0` (`NaN` is the center)
+fn clamp_pan(pan: f32) -> f32 {
+    if pan.is_nan() {
+        0.0
+    } else {
+        pan.clamp(-1.0, 1.0)
+    }
+}
+
+/// Non-negative pitch (`NaN` is the default `1.0`)
+fn clamp_pitch(pitch: f32) -> f32 {
+    if pitch.is_nan() {
+        1.0
+    } else {
+        pitch.max(0.0)
+    }
+}
+
+#[derive(Debug)]
+struct Voice {
+    id: VoiceId,
+    sound: Sound,
+    params: PlayParams,
+    /// Position in source frames
+    pos: f64,
+}
+
+#[derive(Debug)]
+struct State {
+    sample_rate: u32,
+    master_volume: f32,
+    buses: BTreeMap<String, f32>,
+    voices: Vec<Voice>,
+    next_id: u64,
+}
+
+/// Plays [`Sound`]s at once. Clone the handle and pass it to [`crate::audio::setup_stream`] (or
+/// [`crate::audio::NullOutput`]) to render.
+///
+/// Sounds are resampled to the output sample rate with linear interpolation. Mono sounds are
+/// panned to stereo outputs; stereo sounds are balanced.
+#[derive(Debug, Clone)]
+pub struct Mixer {
+    state: Arc<Mutex<State>>,
+}
+
+impl Mixer {
+    /// Creates a mixer rendering at `sample_rate` (e.g. [`crate::audio::sample_rate`])
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                sample_rate,
+                master_volume: 1.0,
+                buses: BTreeMap::new(),
+                voices: Vec::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // don't stop the audio on a panic in another thread
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn with_voice(&self, id: VoiceId, f: impl FnOnce(&mut PlayParams)) {
+        let mut state = self.lock();
+        if let Some(voice) = state.voices.iter_mut().find(|v| v.id == id) {
+            f(&mut voice.params);
+        }
+    }
+
+    pub fn play(&self, sound: &Sound, mut params: PlayParams) -> VoiceId {
+        params.pan = self::clamp_pan(params.pan);
+        params.pitch = self::clamp_pitch(params.pitch);
+
+        let mut state = self.lock();
+        let id = VoiceId(state.next_id);
+        state.next_id += 1;
+        state.voices.push(Voice {
+            id,
+            sound: sound.clone(),
+            params,
+            pos: 0.0,
+        });
+        id
+    }
+
+    pub fn stop(&self, id: VoiceId) {
+        self.lock().voices.retain(|v| v.id != id);
+    }
+
+    pub fn stop_all(&self) {
+        self.lock().voices.clear();
+    }
+
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.lock().voices.iter().any(|v| v.id == id)
+    }
+
+    /// Number of playing voices
+    pub fn n_voices(&self) -> usize {
+        self.lock().voices.len()
+    }
+
+    pub fn set_volume(&self, id: VoiceId, volume: f32) {
+        self.with_voice(id, |p| p.volume = volume);
+    }
+
+    pub fn set_pan(&self, id: VoiceId, pan: f32) {
+        self.with_voice(id, |p| p.pan = self::clamp_pan(pan));
+    }
+
+    pub fn set_pitch(&self, id: VoiceId, pitch: f32) {
+        self.with_voice(id, |p| p.pitch = self::clamp_pitch(pitch));
+    }
+
+    pub fn set_looping(&self, id: VoiceId, looping: bool) {
+        self.with_voice(id, |p| p.looping = looping);
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        self.lock().master_volume
+    }
+
+    pub fn set_master_volume(&self, volume: f32) {
+        self.lock().master_volume = volume;
+    }
+
+    /// Volume of a bus (`1.0` if it's not set)
+    pub fn bus_volume(&self, bus: &str) -> f32 {
+        self.lock().buses.get(bus).copied().unwrap_or(1.0)
+    }
+
+    pub fn set_bus_volume(&self, bus: &str, volume: f32) {
+        self.lock().buses.insert(bus.to_string(), volume);
+    }
+
+    /// Mixes the playing voices into interleaved samples (overwriting them)
+    pub fn render(&self, out: &mut [f32], n_channels: u32) {
+        self.lock().render(out, n_channels as usize);
+    }
+}
+
+impl AudioStream for Mixer {
+    fn fill(&mut self, frames: &mut [f32], n_channels: u32) {
+        self.render(frames, n_channels);
+    }
+}
+
+impl State {
+    fn render(&mut self, out: &mut [f32], n_channels: usize) {
+        out.iter_mut().for_each(|x| *x = 0.0);
+        if n_channels == 0 {
+            return;
+        }
+
+        let (sample_rate, master, buses) = (self.sample_rate, self.master_volume, &self.buses);
+        self.voices.retain_mut(|voice| {
+            let bus = match voice.params.bus {
+                Some(ref bus) => buses.get(bus).copied().unwrap_or(1.0),
+                None => 1.0,
+            };
+            voice.mix(out, n_channels, sample_rate, master * bus)
+        });
+
+        out.iter_mut().for_each(|x| *x = x.clamp(-1.0, 1.0));
+    }
+}
+
+impl Voice {
+    /// Adds samples to the output. Returns `false` if the voice is finished.
+    fn mix(&mut self, out: &mut [f32], n_channels: usize, sample_rate: u32, volume: f32) -> bool {
+        let src = self.sound.samples();
+        let src_channels = self.sound.channels() as usize;
+        let n_frames = self.sound.n_frames();
+        if n_frames == 0 {
+            return false;
+        }
+
+        let step = self.params.pitch as f64 * self.sound.sample_rate() as f64 / sample_rate as f64;
+        let volume = volume * self.params.volume;
+        let pan = self.params.pan;
+        let gains = [volume * (1.0 - pan).min(1.0), volume * (1.0 + pan).min(1.0)];
+
+        // linear interpolation between frames (wraps around if looping)
+        let sample = |frame: usize, ch: usize, t: f32, looping: bool| {
+            let next = if frame + 1 < n_frames {
+                frame + 1
+            } else if looping {
+                0
+            } else {
+                frame
+            };
+            let a = src[frame * src_channels + ch];
+            let b = src[next * src_channels + ch];
+            a + (b - a) * t
+        };
+
+        for out_frame in out.chunks_exact_mut(n_channels) {
+            if self.pos >= n_frames as f64 {
+                if self.params.looping && step > 0.0 {
+                    self.pos %= n_frames as f64;
+                } else {
+                    return false;
+                }
+            }
+
+            let frame = self.pos as usize;
+            let t = (self.pos - frame as f64) as f32;
+            let looping = self.params.looping;
+
+            let (l, r) = if src_channels == 1 {
+                let x = sample(frame, 0, t, looping);
+                (x, x)
+            } else {
+                (sample(frame, 0, t, looping), sample(frame, 1, t, looping))
+            };
+
+            if n_channels == 1 {
+                out_frame[0] += (l + r) * 0.5 * volume;
+            } else {
+                out_frame[0] += l * gains[0];
+                out_frame[1] += r * gains[1];
+            }
+
+            self.pos += step;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 16-bit mono WAV
+    fn wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for x in samples {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn mix_offline() {
+        let sound = Sound::decode(&wav(&[16384; 4], 8000)).unwrap();
+        assert_eq!(sound.n_frames(), 4);
+        assert_eq!(sound.samples(), &[0.5; 4]);
+
+        let mixer = Mixer::new(8000);
+        mixer.set_bus_volume("sfx", 0.5);
+
+        let a = mixer.play(&sound, PlayParams::default());
+        mixer.play(
+            &sound,
+            PlayParams {
+                pan: 1.0,
+                bus: Some("sfx".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let mut out = crate::audio::NullOutput::new(mixer.clone(), 8000, 2);
+        let frames = out.render(6);
+
+        // left: 0.5, right: 0.5 + 0.5 * 0.5, then silence
+        assert_eq!(&frames[0..2], &[0.5, 0.75]);
+        assert_eq!(&frames[8..12], &[0.0; 4]);
+        assert!(!mixer.is_playing(a));
+        assert_eq!(mixer.n_voices(), 0);
+
+        // looping voices keep playing
+        let b = mixer.play(
+            &sound,
+            PlayParams {
+                pitch: 0.5,
+                looping: true,
+                ..Default::default()
+            },
+        );
+        let frames = out.render(16);
+        assert!(frames.iter().all(|&x| x == 0.5));
+        assert!(mixer.is_playing(b));
+    }
+
+    #[test]
+    fn sanitize_params() {
+        let sound = Sound::decode(&wav(&[16384; 4], 8000)).unwrap();
+        let mixer = Mixer::new(8000);
+
+        // NaN is replaced with the default, so the voice finishes
+        let a = mixer.play(
+            &sound,
+            PlayParams {
+                pan: f32::NAN,
+                pitch: f32::NAN,
+                ..Default::default()
+            },
+        );
+        let mut out = crate::audio::NullOutput::new(mixer.clone(), 8000, 2);
+        let frames = out.render(6);
+        assert_eq!(&frames[0..2], &[0.5, 0.5]);
+        assert!(!mixer.is_playing(a));
+
+        let b = mixer.play(
+            &sound,
+            PlayParams {
+                pan: -3.0,
+                pitch: -1.0,
+                ..Default::default()
+            },
+        );
+        mixer.with_voice(b, |p| assert_eq!((p.pan, p.pitch), (-1.0, 0.0)));
+        mixer.set_pan(b, 2.0);
+        mixer.set_pitch(b, f32::NAN);
+        mixer.with_voice(b, |p| assert_eq!((p.pan, p.pitch), (1.0, 1.0)));
+    }
+}
@@ -0,0 +1,394 @@
+/*!
+Decoded sample buffers
+*/
+
+use std::{error::Error, fmt, sync::Arc, time::Duration};
+
+/// Error on decoding a [`Sound`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundError {
+    /// Neither WAV nor OGG (or the `ogg` feature is disabled)
+    UnknownFormat,
+    /// The data ended in the middle of a chunk
+    Truncated,
+    /// RIFF file without the `fmt ` or `data` chunk
+    MissingChunk(&'static str),
+    /// WAV encoding other than 8/16/24/32-bit PCM or 32-bit float
+    UnsupportedEncoding { format: u16, bits: u16 },
+    /// Zero channels or zero sample rate
+    InvalidHeader,
+    /// Error from `lewton`
+    Ogg(String),
+}
+
+impl fmt::Display for SoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "unknown sound format"),
+            Self::Truncated => write!(f, "truncated sound data"),
+            Self::MissingChunk(id) => write!(f, "missing `{}` chunk in WAV", id),
+            Self::UnsupportedEncoding { format, bits } => write!(
+                f,
+                "unsupported WAV encoding (format: {}, bits per sample: {})",
+                format, bits
+            ),
+            Self::InvalidHeader => write!(f, "invalid sound header"),
+            Self::Ogg(err) => write!(f, "failed to decode OGG: {}", err),
+        }
+    }
+}
+
+impl Error for SoundError {}
+
+/// Interleaved `f32` samples (cheap to clone)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    samples: Arc<[f32]>,
+    n_channels: u32,
+    sample_rate: u32,
+}
+
+impl Sound {
+    pub fn from_samples(samples: Vec<f32>, n_channels: u32, sample_rate: u32) -> Self {
+        assert!(n_channels > 0, "sound requires at least one channel");
+        assert!(sample_rate > 0, "sound requires a non-zero sample rate");
+        Self {
+            samples: samples.into(),
+            n_channels,
+            sample_rate,
+        }
+    }
+
+    /// Decodes WAV or OGG (`ogg` feature) by the magic number
+    pub fn decode(bytes: &[u8]) -> Result<Self, SoundError> {
+        match bytes.get(0..4) {
+            Some(b"RIFF") => Self::from_wav(bytes),
+            #[cfg(feature = "ogg")]
+            Some(b"OggS") => Self::from_ogg(bytes),
+            _ => Err(SoundError::UnknownFormat),
+        }
+    }
+
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, SoundError> {
+        if bytes.len() < 12 {
+            return Err(SoundError::Truncated);
+        }
+        if &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(SoundError::UnknownFormat);
+        }
+
+        let mut fmt = None;
+        let mut data = None;
+
+        let mut rest = &bytes[12..];
+        while rest.len() >= 8 {
+            let id = &rest[0..4];
+            let len = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            let body = rest.get(8..8 + len).ok_or(SoundError::Truncated)?;
+
+            match id {
+                b"fmt " => fmt = Some(WavFormat::parse(body)?),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+
+            // chunks are aligned to 2 bytes
+            let next = (8 + len + 1) & !1;
+            rest = rest.get(next..).unwrap_or(&[]);
+        }
+
+        let fmt = fmt.ok_or(SoundError::MissingChunk("fmt "))?;
+        let data = data.ok_or(SoundError::MissingChunk("data"))?;
+        let samples = fmt.decode(data)?;
+
+        Ok(Self::from_samples(
+            samples,
+            fmt.n_channels as u32,
+            fmt.sample_rate,
+        ))
+    }
+
+    #[cfg(feature = "ogg")]
+    pub fn from_ogg(bytes: &[u8]) -> Result<Self, SoundError> {
+        use lewton::inside_ogg::OggStreamReader;
+
+        let mut reader = OggStreamReader::new(std::io::Cursor::new(bytes))
+            .map_err(|err| SoundError::Ogg(err.to_string()))?;
+
+        let n_channels = reader.ident_hdr.audio_channels as u32;
+        let sample_rate = reader.ident_hdr.audio_sample_rate;
+        if n_channels == 0 || sample_rate == 0 {
+            return Err(SoundError::InvalidHeader);
+        }
+
+        let mut samples = Vec::new();
+        while let Some(packet) = reader
+            .read_dec_packet_itl()
+            .map_err(|err| SoundError::Ogg(err.to_string()))?
+        {
+            samples.extend(packet.iter().map(|&x| x as f32 / 32768.0));
+        }
+
+        Ok(Self::from_samples(samples, n_channels, sample_rate))
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn channels(&self) -> u32 {
+        self.n_channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn n_frames(&self) -> usize {
+        self.samples.len() / self.n_channels as usize
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.n_frames() as f64 / self.sample_rate as f64)
+    }
+}
+
+/// `fmt ` chunk
+#[derive(Debug, Clone, Copy)]
+struct WavFormat {
+    format: u16,
+    n_channels: u16,
+    sample_rate: u32,
+    bits: u16,
+}
+
+impl WavFormat {
+    const PCM: u16 = 1;
+    const FLOAT: u16 = 3;
+    const EXTENSIBLE: u16 = 0xFFFE;
+
+    fn parse(body: &[u8]) -> Result<Self, SoundError> {
+        if body.len() < 16 {
+            return Err(SoundError::Truncated);
+        }
+
+        let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+        let mut format = u16_at(0);
+        if format == Self::EXTENSIBLE {
+            // the first two bytes of the sub format GUID
+            format = body.get(24..26).map(|_| u16_at(24)).unwrap_or(0);
+        }
+
+        let fmt = Self {
+            format,
+            n_channels: u16_at(2),
+            sample_rate: u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+            bits: u16_at(14),
+        };
+
+        if fmt.n_channels == 0 || fmt.sample_rate == 0 {
+            return Err(SoundError::InvalidHeader);
+        }
+
+        Ok(fmt)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<f32>, SoundError> {
+        let samples = match (self.format, self.bits) {
+            (Self::PCM, 8) => data.iter().map(|&x| (x as f32 - 128.0) / 128.0).collect(),
+            (Self::PCM, 16) => data
+                .chunks_exact(2)
+                .map(|x| i16::from_le_bytes([x[0], x[1]]) as f32 / 32768.0)
+                .collect(),
+            (Self::PCM, 24) => data
+                .chunks_exact(3)
+                .map(|x| (i32::from_le_bytes([0, x[0], x[1], x[2]]) >> 8) as f32 / 8388608.0)
+                .collect(),
+            (Self::PCM, 32) => data
+                .chunks_exact(4)
+                .map(|x| i32::from_le_bytes([x[0], x[1], x[2], x[3]]) as f32 / 2147483648.0)
+                .collect(),
+            (Self::FLOAT, 32) => data
+                .chunks_exact(4)
+                .map(|x| f32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+                .collect(),
+            (format, bits) => return Err(SoundError::UnsupportedEncoding { format, bits }),
+        };
+
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// RIFF chunk with the padding byte
+    fn chunk(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    fn riff(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body = chunks.concat();
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(4 + body.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// `fmt ` chunk body
+    fn fmt(format: u16, n_channels: u16, sample_rate: u32, bits: u16) -> Vec<u8> {
+        let block_align = n_channels * bits / 8;
+        let mut body = Vec::new();
+        body.extend_from_slice(&format.to_le_bytes());
+        body.extend_from_slice(&n_channels.to_le_bytes());
+        body.extend_from_slice(&sample_rate.to_le_bytes());
+        body.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        body.extend_from_slice(&block_align.to_le_bytes());
+        body.extend_from_slice(&bits.to_le_bytes());
+        body
+    }
+
+    fn wav(fmt: Vec<u8>, data: &[u8]) -> Vec<u8> {
+        self::riff(&[self::chunk(b"fmt ", &fmt), self::chunk(b"data", data)])
+    }
+
+    #[test]
+    fn pcm_8() {
+        let sound = Sound::from_wav(&self::wav(self::fmt(1, 1, 8000, 8), &[0, 128, 192])).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.0, 0.5]);
+        assert_eq!(sound.sample_rate(), 8000);
+        assert_eq!(sound.n_frames(), 3);
+    }
+
+    #[test]
+    fn pcm_24() {
+        let data = [0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40];
+        let sound = Sound::from_wav(&self::wav(self::fmt(1, 1, 44100, 24), &data)).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn pcm_32() {
+        let data: Vec<u8> = [i32::MIN, 0, 1 << 30]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let sound = Sound::from_wav(&self::wav(self::fmt(1, 1, 44100, 32), &data)).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn float() {
+        let data: Vec<u8> = [0.25f32, -0.75, 1.0, 0.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let sound = Sound::from_wav(&self::wav(self::fmt(3, 2, 48000, 32), &data)).unwrap();
+        assert_eq!(sound.samples(), &[0.25, -0.75, 1.0, 0.0]);
+        assert_eq!(sound.channels(), 2);
+        assert_eq!(sound.n_frames(), 2);
+        assert_eq!(sound.duration(), Duration::from_secs_f64(2.0 / 48000.0));
+    }
+
+    #[test]
+    fn extensible() {
+        let mut fmt = self::fmt(WavFormat::EXTENSIBLE, 2, 44100, 16);
+        // extension size, valid bits and channel mask
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&16u16.to_le_bytes());
+        fmt.extend_from_slice(&3u32.to_le_bytes());
+        // sub format GUID (KSDATAFORMAT_SUBTYPE_PCM)
+        fmt.extend_from_slice(&[
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38,
+            0x9B, 0x71,
+        ]);
+
+        let data: Vec<u8> = [i16::MIN, 16384]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let sound = Sound::from_wav(&self::wav(fmt, &data)).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.5]);
+        assert_eq!(sound.channels(), 2);
+    }
+
+    #[test]
+    fn odd_chunk_padding() {
+        let bytes = self::riff(&[
+            self::chunk(b"LIST", &[1, 2, 3]),
+            self::chunk(b"fmt ", &self::fmt(1, 1, 8000, 8)),
+            self::chunk(b"data", &[0, 128, 192]),
+            self::chunk(b"junk", &[0]),
+        ]);
+        let sound = Sound::from_wav(&bytes).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.0, 0.5]);
+
+        // the padding byte of the last chunk may be missing
+        let sound = Sound::from_wav(&bytes[..bytes.len() - 1]).unwrap();
+        assert_eq!(sound.samples(), &[-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn errors() {
+        let fmt = self::chunk(b"fmt ", &self::fmt(1, 1, 8000, 16));
+        let data = self::chunk(b"data", &[0; 4]);
+
+        let bytes = self::riff(&[fmt.clone(), data.clone()]);
+        assert_eq!(
+            Sound::from_wav(&bytes[..bytes.len() - 1]),
+            Err(SoundError::Truncated)
+        );
+        assert_eq!(Sound::from_wav(&bytes[..8]), Err(SoundError::Truncated));
+        assert_eq!(
+            Sound::from_wav(&self::riff(&[
+                self::chunk(b"fmt ", &[1, 0, 1, 0]),
+                data.clone()
+            ])),
+            Err(SoundError::Truncated)
+        );
+
+        assert_eq!(
+            Sound::from_wav(&self::riff(&[data])),
+            Err(SoundError::MissingChunk("fmt "))
+        );
+        assert_eq!(
+            Sound::from_wav(&self::riff(&[fmt])),
+            Err(SoundError::MissingChunk("data"))
+        );
+
+        assert_eq!(
+            Sound::from_wav(&self::wav(self::fmt(2, 1, 8000, 4), &[0; 4])),
+            Err(SoundError::UnsupportedEncoding { format: 2, bits: 4 })
+        );
+        assert_eq!(
+            Sound::from_wav(&self::wav(self::fmt(1, 1, 8000, 12), &[0; 4])),
+            Err(SoundError::UnsupportedEncoding {
+                format: 1,
+                bits: 12
+            })
+        );
+
+        assert_eq!(
+            Sound::from_wav(&self::wav(self::fmt(1, 1, 0, 16), &[0; 4])),
+            Err(SoundError::InvalidHeader)
+        );
+        assert_eq!(
+            Sound::from_wav(&self::wav(self::fmt(1, 0, 8000, 16), &[0; 4])),
+            Err(SoundError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_sample_rate() {
+        Sound::from_samples(vec![0.0; 4], 1, 0);
+    }
+}
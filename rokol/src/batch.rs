@@ -0,0 +1,365 @@
+/*!
+Batched 2D sprite renderer
+
+[`SpriteBatch`] accumulates textured, tinted and rotated quads and draws them with as few draw
+calls as possible (one per run of the same [`Image`]):
+
+```no_run
+use rokol::{batch::{self, Sprite, SpriteBatch}, gfx as rg};
+
+let mut batch = SpriteBatch::new(4096);
+let img = rg::Image::default();
+
+// inside a rendering pass:
+batch.begin(batch::ortho(1280.0, 720.0));
+batch.push(Sprite {
+    img,
+    pos: [100.0, 100.0],
+    size: [32.0, 32.0],
+    ..Default::default()
+});
+batch.end();
+```
+
+The built-in shader is for the `glcore33` backend. Use [`SpriteBatch::with_shader`] for other
+backends.
+*/
+
+use crate as rokol;
+
+use crate::gfx::{
+    self as rg, BakedResource, Bindings, BlendFactor, Image, IndexBuffer, LayoutDesc, Pipeline,
+    Shader, ShaderStage, StreamBuffer,
+};
+
+/// Maximum number of quads in one draw call (limited by `u16` indices)
+pub const MAX_QUADS_PER_DRAW: usize = (u16::MAX as usize + 1) / 4;
+
+/// Vertex of the sprite shader
+#[derive(LayoutDesc, Debug, Clone, Copy, Default, PartialEq)]
+#[repr(C)]
+pub struct SpriteVertex {
+    /// Position in the coordinate system of the transform
+    pub pos: [f32; 2],
+    pub color: [u8; 4],
+    pub uv: [f32; 2],
+}
+
+/// Uniform block of the sprite shader (vertex stage, slot 0)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct SpriteUniforms {
+    /// Column-major 4x4 matrix
+    pub transform: [f32; 16],
+}
+
+// `#[repr(C)]` with `f32` fields only
+unsafe impl rg::Pod for SpriteUniforms {}
+
+/// Orthographic projection from pixel coordinates (origin at the top-left corner) to NDC
+pub fn ortho(w: f32, h: f32) -> [f32; 16] {
+    #[rustfmt::skip]
+    let m = [
+        2.0 / w, 0.0, 0.0, 0.0,
+        0.0, -2.0 / h, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        -1.0, 1.0, 0.0, 1.0,
+    ];
+    m
+}
+
+/// Textured, tinted and rotated quad
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub img: Image,
+    /// Where the `origin` of the sprite is placed
+    pub pos: [f32; 2],
+    pub size: [f32; 2],
+    /// Normalized pivot of the sprite (`[0.0, 0.0]` is the top-left corner)
+    pub origin: [f32; 2],
+    /// Rotation around the `origin` in radians
+    pub rot: f32,
+    /// Normalized texture rectangle `[x, y, w, h]`
+    pub uv_rect: [f32; 4],
+    pub color: [u8; 4],
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Self {
+            img: Image::default(),
+            pos: [0.0, 0.0],
+            size: [0.0, 0.0],
+            origin: [0.0, 0.0],
+            rot: 0.0,
+            uv_rect: [0.0, 0.0, 1.0, 1.0],
+            color: [255, 255, 255, 255],
+        }
+    }
+}
+
+impl Sprite {
+    /// Vertices in the order of top-left, top-right, bottom-right and bottom-left
+    pub fn vertices(&self) -> [SpriteVertex; 4] {
+        let [w, h] = self.size;
+        let [ox, oy] = self.origin;
+        let [u, v, uw, vh] = self.uv_rect;
+        let (sin, cos) = self.rot.sin_cos();
+
+        let corner = |cx: f32, cy: f32| {
+            let (lx, ly) = ((cx - ox) * w, (cy - oy) * h);
+            SpriteVertex {
+                pos: [
+                    self.pos[0] + lx * cos - ly * sin,
+                    self.pos[1] + lx * sin + ly * cos,
+                ],
+                color: self.color,
+                uv: [u + cx * uw, v + cy * vh],
+            }
+        };
+
+        [
+            corner(0.0, 0.0),
+            corner(1.0, 0.0),
+            corner(1.0, 1.0),
+            corner(0.0, 1.0),
+        ]
+    }
+}
+
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+mod shader {
+    pub const VS: &str = r#"#version 330
+
+uniform mat4 transform;
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec4 color0;
+layout(location = 2) in vec2 texcoord0;
+
+out vec4 color;
+out vec2 uv;
+
+void main() {
+    gl_Position = transform * vec4(position, 0.0, 1.0);
+    color = color0;
+    uv = texcoord0;
+}
+"#;
+
+    pub const FS: &str = r#"#version 330
+
+uniform sampler2D tex;
+
+in vec4 color;
+in vec2 uv;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = texture(tex, uv) * color;
+}
+"#;
+}
+
+/// Creates the built-in sprite shader (`glcore33`)
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+pub fn sprite_shader() -> Shader {
+    let vs = format!("{}\0", shader::VS);
+    let fs = format!("{}\0", shader::FS);
+
+    let mut desc = unsafe { rg::shader_desc(&vs, &fs) };
+
+    let ub = &mut desc.vs.uniform_blocks[0];
+    ub.size = std::mem::size_of::<SpriteUniforms>() as _;
+    ub.uniforms[0].name = b"transform\0".as_ptr() as *const _;
+    ub.uniforms[0].type_ = rg::UniformType::Mat4.to_ffi();
+
+    desc.fs.images[0].name = b"tex\0".as_ptr() as *const _;
+    desc.fs.images[0].image_type = rg::ImageType::Dim2.to_ffi();
+    desc.fs.images[0].sampler_type = rg::SamplerType::Float.to_ffi();
+
+    desc.label = rg::intern_label("rokol-sprite-shader");
+
+    // the sources are copied by `sokol_gfx.h`
+    Shader::create(&desc)
+}
+
+/// Quads drawn with a stream vertex buffer and a static index buffer
+#[derive(Debug)]
+pub struct SpriteBatch {
+    shd: Shader,
+    pip: Pipeline,
+    vbuf: StreamBuffer,
+    ibuf: IndexBuffer<u16>,
+    /// Queued quads with their images
+    quads: Vec<(Image, [SpriteVertex; 4])>,
+    transform: [f32; 16],
+    /// Sort quads by image on flush. It reduces draw calls but breaks the painter's order of
+    /// overlapping quads, so it's off by default.
+    pub sort_by_image: bool,
+}
+
+impl Drop for SpriteBatch {
+    fn drop(&mut self) {
+        if unsafe { rokol::ffi::gfx::sg_isvalid() } {
+            Pipeline::destroy(self.pip);
+            Shader::destroy(self.shd);
+        }
+    }
+}
+
+impl SpriteBatch {
+    /// Creates a batch with the built-in shader. The vertex buffer is initially sized for
+    /// `n_quads` quads per frame and grows on demand.
+    #[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+    pub fn new(n_quads: usize) -> Self {
+        Self::with_shader(self::sprite_shader(), n_quads)
+    }
+
+    /// Creates a batch with a shader compatible with [`SpriteVertex`], [`SpriteUniforms`] and one
+    /// 2D image in the fragment stage. The shader is destroyed with the batch.
+    pub fn with_shader(shd: Shader, n_quads: usize) -> Self {
+        let mut pip_desc = rg::pipeline_desc::<SpriteVertex, u16>(shd);
+        let blend = &mut pip_desc.colors[0].blend;
+        blend.enabled = true;
+        blend.src_factor_rgb = BlendFactor::SrcAlpha.to_ffi();
+        blend.dst_factor_rgb = BlendFactor::OneMinusSrcAlpha.to_ffi();
+        pip_desc.label = rg::intern_label("rokol-sprite-pipeline");
+        let pip = Pipeline::create(&pip_desc);
+
+        let indices = (0..MAX_QUADS_PER_DRAW as u16)
+            .flat_map(|i| {
+                let v = i * 4;
+                [v, v + 1, v + 2, v, v + 2, v + 3]
+            })
+            .collect::<Vec<_>>();
+        let ibuf = IndexBuffer::immutable(&indices, "rokol-sprite-indices");
+
+        let vbuf_size = std::mem::size_of::<[SpriteVertex; 4]>() * n_quads.max(1);
+        let vbuf = StreamBuffer::vertex(vbuf_size, "rokol-sprite-vertices");
+
+        Self {
+            shd,
+            pip,
+            vbuf,
+            ibuf,
+            quads: Vec::with_capacity(n_quads),
+            transform: self::ortho(1.0, 1.0),
+            sort_by_image: false,
+        }
+    }
+
+    /// Starts a batch with a column-major transform (e.g. [`ortho`])
+    pub fn begin(&mut self, transform: [f32; 16]) {
+        self.quads.clear();
+        self.transform = transform;
+    }
+
+    pub fn push(&mut self, sprite: Sprite) {
+        self.quads.push((sprite.img, sprite.vertices()));
+    }
+
+    /// Pushes a quad with precomputed vertices
+    pub fn push_quad(&mut self, img: Image, quad: [SpriteVertex; 4]) {
+        self.quads.push((img, quad));
+    }
+
+    /// Number of queued quads
+    pub fn len(&self) -> usize {
+        self.quads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quads.is_empty()
+    }
+
+    /// Draws the queued quads. Must be called inside a rendering pass.
+    pub fn end(&mut self) {
+        if self.quads.is_empty() {
+            return;
+        }
+
+        if self.sort_by_image {
+            // stable: keeps the order of quads with the same image
+            self.quads.sort_by_key(|(img, _)| img.id);
+        }
+
+        rg::apply_pipeline(self.pip);
+        let uniforms = SpriteUniforms {
+            transform: self.transform,
+        };
+        rg::apply_uniforms_as(ShaderStage::Vs, 0, &uniforms);
+
+        let mut bind = Bindings::default();
+        self.ibuf.set_to(&mut bind);
+
+        let quads = std::mem::take(&mut self.quads);
+        for (i, chunk) in quads.chunks(MAX_QUADS_PER_DRAW).enumerate() {
+            let verts = chunk.iter().map(|(_, q)| *q).collect::<Vec<_>>();
+            let range = match self.vbuf.alloc(&verts) {
+                Some(range) => range,
+                None => {
+                    log::warn!(
+                        "rokol: sprite batch dropped {} quads (the buffer grows on next frame)",
+                        quads.len() - i * MAX_QUADS_PER_DRAW
+                    );
+                    break;
+                }
+            };
+            range.set_vertex(&mut bind, 0);
+
+            // draw runs of the same image
+            let mut start = 0;
+            while start < chunk.len() {
+                let img = chunk[start].0;
+                let len = chunk[start..].iter().take_while(|(i, _)| *i == img).count();
+
+                bind.fs_images[0] = img;
+                rg::apply_bindings(&bind);
+                rg::draw(start as u32 * 6, len as u32 * 6, 1);
+
+                start += len;
+            }
+        }
+
+        // reuse the allocation
+        self.quads = quads;
+        self.quads.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sprite_vertices() {
+        let sprite = Sprite {
+            pos: [10.0, 20.0],
+            size: [4.0, 2.0],
+            uv_rect: [0.5, 0.0, 0.5, 0.25],
+            color: [1, 2, 3, 4],
+            ..Default::default()
+        };
+        let pos = sprite.vertices().map(|v| v.pos);
+        assert_eq!(
+            pos,
+            [[10.0, 20.0], [14.0, 20.0], [14.0, 22.0], [10.0, 22.0]]
+        );
+        let uv = sprite.vertices().map(|v| v.uv);
+        assert_eq!(uv, [[0.5, 0.0], [1.0, 0.0], [1.0, 0.25], [0.5, 0.25]]);
+        assert!(sprite.vertices().iter().all(|v| v.color == [1, 2, 3, 4]));
+
+        // rotated by 90 degrees around the center
+        let sprite = Sprite {
+            origin: [0.5, 0.5],
+            rot: std::f32::consts::FRAC_PI_2,
+            ..sprite
+        };
+        let expected = [[11.0, 18.0], [11.0, 22.0], [9.0, 22.0], [9.0, 18.0]];
+        for (v, p) in sprite.vertices().iter().zip(expected) {
+            assert!((v.pos[0] - p[0]).abs() < 1e-5 && (v.pos[1] - p[1]).abs() < 1e-5);
+        }
+    }
+}
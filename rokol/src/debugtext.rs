@@ -0,0 +1,277 @@
+/*!
+On-screen debug text with [`sokol_debugtext.h`] (`impl-debugtext` feature)
+
+Text is drawn with built-in 8x8 bitmap fonts on a virtual canvas, so no TTF file is needed. Set up
+after [`crate::gfx::setup`] and call [`draw`] inside a rendering pass:
+
+```no_run
+use std::fmt::Write;
+use rokol::{debugtext::{self as dtx, DebugText, Font}, gfx as rg};
+
+dtx::setup_default();
+
+// every frame:
+dtx::canvas(640.0, 360.0);
+dtx::origin(1.0, 1.0);
+dtx::font(Font::C64);
+dtx::color([255, 255, 0, 255]);
+writeln!(DebugText, "frame: {}", 42).unwrap();
+
+rg::begin_default_pass(&rg::PassAction::clear([0.0, 0.0, 0.0, 1.0]), 1280, 720);
+dtx::draw();
+rg::end_pass();
+rg::commit();
+```
+
+Positions are in character cells (8x8 pixels of the canvas). The fonts cover 8-bit characters only;
+non-ASCII text is drawn byte by byte.
+
+[`sokol_debugtext.h`]: https://github.com/floooh/sokol/blob/master/util/sokol_debugtext.h
+*/
+
+use std::{fmt, os::raw::c_char};
+
+use rokol_ffi::debugtext as ffi;
+
+/// `sdtx_desc_t`. Zero fields are replaced with Sokol defaults
+pub type DebugTextDesc = ffi::sdtx_desc_t;
+
+/// `sdtx_context_desc_t`. Zero fields are replaced with Sokol defaults (640x400 canvas, 4096
+/// characters per frame)
+pub type ContextDesc = ffi::sdtx_context_desc_t;
+
+/// `sdtx_font_desc_t`
+pub type FontDesc = ffi::sdtx_font_desc_t;
+
+/// Text rendering context. Each context has its own canvas, cursor and color.
+pub type Context = ffi::sdtx_context;
+
+/// Maximum number of font slots
+pub const MAX_FONTS: usize = ffi::SDTX_MAX_FONTS as usize;
+
+/// Built-in font. [`setup_default`] loads them in the slots of their discriminants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Font {
+    Kc853 = 0,
+    Kc854 = 1,
+    Z1013 = 2,
+    Cpc = 3,
+    C64 = 4,
+    Oric = 5,
+}
+
+impl Font {
+    pub const ALL: [Self; 6] = [
+        Self::Kc853,
+        Self::Kc854,
+        Self::Z1013,
+        Self::Cpc,
+        Self::C64,
+        Self::Oric,
+    ];
+
+    pub fn desc(self) -> FontDesc {
+        unsafe {
+            match self {
+                Self::Kc853 => ffi::sdtx_font_kc853(),
+                Self::Kc854 => ffi::sdtx_font_kc854(),
+                Self::Z1013 => ffi::sdtx_font_z1013(),
+                Self::Cpc => ffi::sdtx_font_cpc(),
+                Self::C64 => ffi::sdtx_font_c64(),
+                Self::Oric => ffi::sdtx_font_oric(),
+            }
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Setup
+
+/// [`DebugTextDesc`] with all the [`Font`]s loaded
+pub fn default_desc() -> DebugTextDesc {
+    let mut desc = DebugTextDesc::default();
+    for font in Font::ALL {
+        desc.fonts[font as usize] = font.desc();
+    }
+    desc
+}
+
+/// Sets up `sokol_debugtext.h`. Call after [`crate::gfx::setup`].
+pub fn setup(desc: &DebugTextDesc) {
+    unsafe {
+        ffi::sdtx_setup(desc);
+    }
+}
+
+/// Sets up `sokol_debugtext.h` with [`default_desc`]
+pub fn setup_default() {
+    self::setup(&self::default_desc());
+}
+
+/// Call before [`crate::gfx::shutdown`]
+pub fn shutdown() {
+    unsafe {
+        ffi::sdtx_shutdown();
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Contexts
+
+pub fn make_context(desc: &ContextDesc) -> Context {
+    unsafe { ffi::sdtx_make_context(desc) }
+}
+
+pub fn destroy_context(ctx: Context) {
+    unsafe {
+        ffi::sdtx_destroy_context(ctx);
+    }
+}
+
+pub fn set_context(ctx: Context) {
+    unsafe {
+        ffi::sdtx_set_context(ctx);
+    }
+}
+
+pub fn get_context() -> Context {
+    unsafe { ffi::sdtx_get_context() }
+}
+
+pub fn default_context() -> Context {
+    unsafe { ffi::sdtx_default_context() }
+}
+
+// --------------------------------------------------------------------------------
+// States of the current context
+
+pub fn font(font: Font) {
+    self::font_index(font as usize);
+}
+
+/// Selects a font slot of [`DebugTextDesc::fonts`]
+pub fn font_index(index: usize) {
+    debug_assert!(index < MAX_FONTS, "font index out of range: {}", index);
+    unsafe {
+        ffi::sdtx_font(index as i32);
+    }
+}
+
+/// Sets the virtual canvas size in pixels. It's stretched to the viewport on [`draw`].
+pub fn canvas(w: f32, h: f32) {
+    unsafe {
+        ffi::sdtx_canvas(w, h);
+    }
+}
+
+/// Sets the origin of the cursor in character cells
+pub fn origin(x: f32, y: f32) {
+    unsafe {
+        ffi::sdtx_origin(x, y);
+    }
+}
+
+/// Moves the cursor to the origin
+pub fn home() {
+    unsafe {
+        ffi::sdtx_home();
+    }
+}
+
+/// Sets the cursor position relative to the origin in character cells
+pub fn pos(x: f32, y: f32) {
+    unsafe {
+        ffi::sdtx_pos(x, y);
+    }
+}
+
+pub fn pos_x(x: f32) {
+    unsafe {
+        ffi::sdtx_pos_x(x);
+    }
+}
+
+pub fn pos_y(y: f32) {
+    unsafe {
+        ffi::sdtx_pos_y(y);
+    }
+}
+
+/// Moves the cursor in character cells
+pub fn move_cursor(dx: f32, dy: f32) {
+    unsafe {
+        ffi::sdtx_move(dx, dy);
+    }
+}
+
+pub fn move_x(dx: f32) {
+    unsafe {
+        ffi::sdtx_move_x(dx);
+    }
+}
+
+pub fn move_y(dy: f32) {
+    unsafe {
+        ffi::sdtx_move_y(dy);
+    }
+}
+
+/// Moves the cursor to the start of the next line
+pub fn crlf() {
+    unsafe {
+        ffi::sdtx_crlf();
+    }
+}
+
+pub fn color(color: [u8; 4]) {
+    unsafe {
+        ffi::sdtx_color4b(color[0], color[1], color[2], color[3]);
+    }
+}
+
+pub fn color_f(color: [f32; 4]) {
+    unsafe {
+        ffi::sdtx_color4f(color[0], color[1], color[2], color[3]);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Text
+
+/// Puts a character. `\n`, `\r` and `\t` move the cursor.
+pub fn putc(c: u8) {
+    unsafe {
+        ffi::sdtx_putc(c as c_char);
+    }
+}
+
+/// Puts a string (stops at the first `\0`)
+pub fn puts(s: &str) {
+    // `sdtx_putr` takes `int` length
+    for chunk in s.as_bytes().chunks(i32::MAX as usize) {
+        unsafe {
+            ffi::sdtx_putr(chunk.as_ptr() as *const c_char, chunk.len() as i32);
+        }
+    }
+}
+
+/// [`fmt::Write`] to the current context
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugText;
+
+impl fmt::Write for DebugText {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self::puts(s);
+        Ok(())
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Rendering
+
+/// Draws the text of the current context and rewinds it. Must be called inside a rendering pass.
+pub fn draw() {
+    unsafe {
+        ffi::sdtx_draw();
+    }
+}
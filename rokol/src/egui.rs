@@ -0,0 +1,550 @@
+/*!
+[`egui`] painter (`egui` feature)
+
+[`Painter`] applies egui's texture deltas to [`Image`]s and draws the tessellated meshes into
+stream buffers with scissor rects:
+
+```no_run
+use rokol::{gfx as rg, egui::{input::Input, Painter}};
+
+let ctx = egui::Context::default();
+let mut input = Input::default();
+let mut painter = Painter::new();
+
+// every frame (after feeding events to `input`):
+let raw_input = input.take([1280.0, 720.0], 1.0);
+let output = ctx.run(raw_input, |ctx| {
+    egui::Window::new("Debug").show(ctx, |ui| ui.label("Hello"));
+});
+let primitives = ctx.tessellate(output.shapes);
+
+rg::begin_default_pass(&rg::PassAction::clear([0.0, 0.0, 0.0, 1.0]), 1280, 720);
+painter.paint(1.0, [1280, 720], &primitives, &output.textures_delta);
+rg::end_pass();
+rg::commit();
+```
+
+[`input`] turns `rokol::app` (or SDL) events into [`egui::RawInput`].
+
+Managed textures are kept in CPU memory, too, so that partial updates (such as new glyphs in the
+font atlas) can be patched and re-uploaded. Use [`texture_id`] to show your own [`Image`]s.
+
+The built-in shader is for the `glcore33` backend. Use [`Painter::with_shader`] for other
+backends.
+*/
+
+pub mod input;
+
+pub use egui;
+
+use std::{collections::HashMap, fmt};
+
+use egui::{
+    epaint::{ImageDelta, Primitive},
+    ClippedPrimitive, ImageData, PaintCallbackInfo, Rect, TextureFilter, TextureId, TextureOptions,
+    TexturesDelta,
+};
+
+use crate as rokol;
+
+use crate::{
+    gfx::{
+        self as rg, BakedResource, Bindings, BlendFactor, Image, LayoutDesc, Shader, StreamBuffer,
+    },
+    ui::UiPipeline,
+};
+
+/// Vertex of the egui shader (same layout as [`egui::epaint::Vertex`])
+#[derive(LayoutDesc, Debug, Clone, Copy, Default, PartialEq)]
+#[repr(C)]
+pub struct EguiVertex {
+    pub pos: [f32; 2],
+    pub uv: [f32; 2],
+    /// sRGBA with premultiplied alpha
+    pub color: [u8; 4],
+}
+
+// `#[repr(C)]` with the same fields as `EguiVertex`
+unsafe impl rg::Pod for egui::epaint::Vertex {}
+
+/// [`TextureId`] of an [`Image`] for `egui::Image` and friends
+pub fn texture_id(img: Image) -> TextureId {
+    TextureId::User(img.id as u64)
+}
+
+/// Column-major orthographic projection of the screen in points
+fn projection(size: [f32; 2]) -> [f32; 16] {
+    let [w, h] = size;
+    #[rustfmt::skip]
+    let m = [
+        2.0 / w, 0.0, 0.0, 0.0,
+        0.0, -2.0 / h, 0.0, 0.0,
+        0.0, 0.0, -1.0, 0.0,
+        -1.0, 1.0, 0.0, 1.0,
+    ];
+    m
+}
+
+/// Creates the built-in egui shader (`glcore33`)
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+pub fn egui_shader() -> Shader {
+    crate::ui::shader("rokol-egui-shader")
+}
+
+/// [`egui::PaintCallback::callback`] run by [`Painter`]
+///
+/// The scissor rect is set to the clip rect of the callback. The painter's pipeline and bindings
+/// are applied again after the callback.
+pub struct CallbackFn {
+    f: Box<dyn Fn(&PaintCallbackInfo) + Send + Sync>,
+}
+
+impl fmt::Debug for CallbackFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackFn").finish_non_exhaustive()
+    }
+}
+
+impl CallbackFn {
+    pub fn new(f: impl Fn(&PaintCallbackInfo) + Send + Sync + 'static) -> Self {
+        Self { f: Box::new(f) }
+    }
+}
+
+/// Managed texture with the CPU copy of the pixels
+#[derive(Debug)]
+struct Texture {
+    img: Image,
+    size: [usize; 2],
+    /// RGBA8 with premultiplied alpha
+    pixels: Vec<u8>,
+    options: TextureOptions,
+}
+
+impl Texture {
+    /// Creates the CPU copy only. The image is made with [`Texture::create_image`].
+    fn new(size: [usize; 2], pixels: Vec<u8>, options: TextureOptions) -> Self {
+        Self {
+            img: Image::default(),
+            size,
+            pixels,
+            options,
+        }
+    }
+
+    fn create_image(&mut self) {
+        fn filter(filter: TextureFilter) -> rg::Filter {
+            match filter {
+                TextureFilter::Nearest => rg::Filter::Nearest,
+                TextureFilter::Linear => rg::Filter::Linear,
+            }
+        }
+
+        self.img = Image::create(&rg::ImageDesc {
+            type_: rg::ImageType::Dim2.to_ffi(),
+            width: self.size[0] as i32,
+            height: self.size[1] as i32,
+            usage: rg::ResourceUsage::Dynamic.to_ffi(),
+            pixel_format: rg::PixelFormat::Rgba8.to_ffi(),
+            min_filter: filter(self.options.minification).to_ffi(),
+            mag_filter: filter(self.options.magnification).to_ffi(),
+            wrap_u: rg::Wrap::ClampToEdge.to_ffi(),
+            wrap_v: rg::Wrap::ClampToEdge.to_ffi(),
+            label: rg::intern_label("rokol-egui-texture"),
+            ..Default::default()
+        });
+    }
+
+    /// Dynamic images can't have initial data, so even new textures are uploaded with this
+    fn upload(&self) {
+        let mut data = rg::ImageData::default();
+        data.subimage[0][0] = self.pixels.as_slice().into();
+        rg::update::update_image_or_queue(self.img, &data);
+    }
+
+    /// Copies a patch at `pos` into the CPU copy. Returns `false` if it's out of bounds.
+    fn patch(&mut self, pos: [usize; 2], size: [usize; 2], pixels: &[u8]) -> bool {
+        let [x, y] = pos;
+        let [w, h] = size;
+        if x + w > self.size[0] || y + h > self.size[1] || pixels.len() < w * h * 4 {
+            log::warn!("rokol: egui texture patch out of bounds");
+            return false;
+        }
+
+        for row in 0..h {
+            let src = row * w * 4;
+            let dst = ((y + row) * self.size[0] + x) * 4;
+            self.pixels[dst..dst + w * 4].copy_from_slice(&pixels[src..src + w * 4]);
+        }
+        true
+    }
+}
+
+/// What [`update_texture`] did to the CPU copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextureUpdate {
+    /// A new texture was inserted. The image of the replaced texture (if any) should be destroyed.
+    Created(Option<Image>),
+    /// The pixels were replaced or patched and should be uploaded
+    Updated,
+    /// The delta was ignored
+    Skipped,
+}
+
+/// CPU side of [`Painter::set_texture`]
+fn update_texture(
+    textures: &mut HashMap<u64, Texture>,
+    id: u64,
+    pos: Option<[usize; 2]>,
+    size: [usize; 2],
+    pixels: Vec<u8>,
+    options: TextureOptions,
+) -> TextureUpdate {
+    match (pos, textures.get_mut(&id)) {
+        (Some(pos), Some(tex)) => {
+            if tex.patch(pos, size, &pixels) {
+                TextureUpdate::Updated
+            } else {
+                TextureUpdate::Skipped
+            }
+        }
+        (Some(_), None) => {
+            log::warn!("rokol: egui tried to patch a missing texture");
+            TextureUpdate::Skipped
+        }
+        (None, Some(tex)) if tex.size == size && tex.options == options => {
+            tex.pixels = pixels;
+            TextureUpdate::Updated
+        }
+        (None, _) => {
+            let old = textures.insert(id, Texture::new(size, pixels, options));
+            TextureUpdate::Created(old.map(|tex| tex.img))
+        }
+    }
+}
+
+/// RGBA8 pixels with premultiplied alpha
+fn rgba_pixels(image: &ImageData) -> Vec<u8> {
+    match image {
+        ImageData::Color(img) => img.pixels.iter().flat_map(|c| c.to_array()).collect(),
+        ImageData::Font(img) => img.srgba_pixels(None).flat_map(|c| c.to_array()).collect(),
+    }
+}
+
+/// Draws egui's [`ClippedPrimitive`]s with stream vertex and index buffers
+#[derive(Debug)]
+pub struct Painter {
+    pip: UiPipeline,
+    vbuf: StreamBuffer,
+    ibuf: StreamBuffer,
+    textures: HashMap<u64, Texture>,
+}
+
+impl Drop for Painter {
+    fn drop(&mut self) {
+        if unsafe { rokol::ffi::gfx::sg_isvalid() } {
+            for tex in self.textures.values() {
+                Image::destroy(tex.img);
+            }
+        }
+    }
+}
+
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+impl Default for Painter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Painter {
+    /// Creates a painter with the built-in shader
+    #[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+    pub fn new() -> Self {
+        Self::with_shader(self::egui_shader())
+    }
+
+    /// Creates a painter with a shader compatible with [`EguiVertex`], a `mat4` uniform block in
+    /// the vertex stage and one 2D image in the fragment stage. The shader is destroyed with the
+    /// painter.
+    pub fn with_shader(shd: Shader) -> Self {
+        assert_eq!(
+            std::mem::size_of::<EguiVertex>(),
+            std::mem::size_of::<egui::epaint::Vertex>()
+        );
+
+        let pip = UiPipeline::create::<EguiVertex, u32>(
+            shd,
+            // premultiplied alpha
+            rg::BlendState {
+                src_factor_rgb: BlendFactor::One.to_ffi(),
+                dst_factor_rgb: BlendFactor::OneMinusSrcAlpha.to_ffi(),
+                src_factor_alpha: BlendFactor::OneMinusDstAlpha.to_ffi(),
+                dst_factor_alpha: BlendFactor::One.to_ffi(),
+                ..Default::default()
+            },
+            "rokol-egui-pipeline",
+        );
+
+        let vbuf = StreamBuffer::vertex(
+            std::mem::size_of::<EguiVertex>() * 16 * 1024,
+            "rokol-egui-vertices",
+        );
+        let ibuf =
+            StreamBuffer::index(std::mem::size_of::<u32>() * 32 * 1024, "rokol-egui-indices");
+
+        Self {
+            pip,
+            vbuf,
+            ibuf,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// [`Image`] of a texture. Managed textures exist once egui has sent them in a
+    /// [`TexturesDelta`].
+    pub fn image(&self, id: TextureId) -> Option<Image> {
+        match id {
+            TextureId::Managed(id) => self.textures.get(&id).map(|tex| tex.img),
+            TextureId::User(id) => Some(Image { id: id as u32 }),
+        }
+    }
+
+    /// Creates or updates a managed texture (called by [`Painter::paint`])
+    pub fn set_texture(&mut self, id: TextureId, delta: &ImageDelta) {
+        let id = match id {
+            TextureId::Managed(id) => id,
+            TextureId::User(_) => {
+                log::warn!("rokol: egui tried to update a user texture");
+                return;
+            }
+        };
+
+        let size = delta.image.size();
+        let pixels = self::rgba_pixels(&delta.image);
+
+        match self::update_texture(
+            &mut self.textures,
+            id,
+            delta.pos,
+            size,
+            pixels,
+            delta.options,
+        ) {
+            TextureUpdate::Created(old) => {
+                if let Some(old) = old {
+                    Image::destroy(old);
+                }
+                let tex = self.textures.get_mut(&id).unwrap();
+                tex.create_image();
+                tex.upload();
+            }
+            TextureUpdate::Updated => self.textures[&id].upload(),
+            TextureUpdate::Skipped => {}
+        }
+    }
+
+    /// Destroys a managed texture (called by [`Painter::paint`])
+    pub fn free_texture(&mut self, id: TextureId) {
+        if let TextureId::Managed(id) = id {
+            if let Some(tex) = self.textures.remove(&id) {
+                Image::destroy(tex.img);
+            }
+        }
+    }
+
+    /// Applies the texture delta and draws the primitives. Must be called inside a rendering pass
+    /// covering the framebuffer of `screen_size_px`.
+    pub fn paint(
+        &mut self,
+        pixels_per_point: f32,
+        screen_size_px: [u32; 2],
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+    ) {
+        for (id, delta) in &textures_delta.set {
+            self.set_texture(*id, delta);
+        }
+
+        let [fb_w, fb_h] = [screen_size_px[0] as f32, screen_size_px[1] as f32];
+        if fb_w > 0.0 && fb_h > 0.0 && pixels_per_point > 0.0 {
+            self.paint_primitives(pixels_per_point, screen_size_px, primitives);
+            rg::scissor(0, 0, screen_size_px[0], screen_size_px[1]);
+        }
+
+        for id in &textures_delta.free {
+            self.free_texture(*id);
+        }
+    }
+
+    fn paint_primitives(
+        &mut self,
+        pixels_per_point: f32,
+        screen_size_px: [u32; 2],
+        primitives: &[ClippedPrimitive],
+    ) {
+        let [fb_w, fb_h] = [screen_size_px[0] as f32, screen_size_px[1] as f32];
+
+        let transform = self::projection([fb_w / pixels_per_point, fb_h / pixels_per_point]);
+        self.pip.apply(&transform);
+
+        // returns `false` if the clip rect is empty
+        let scissor = |clip_rect: Rect| {
+            let x0 = (clip_rect.min.x * pixels_per_point).round().max(0.0);
+            let y0 = (clip_rect.min.y * pixels_per_point).round().max(0.0);
+            let x1 = (clip_rect.max.x * pixels_per_point).round().min(fb_w);
+            let y1 = (clip_rect.max.y * pixels_per_point).round().min(fb_h);
+            if x1 <= x0 || y1 <= y0 {
+                return false;
+            }
+            rg::scissor_f(x0, y0, x1 - x0, y1 - y0);
+            true
+        };
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    if mesh.indices.is_empty() || !scissor(*clip_rect) {
+                        continue;
+                    }
+
+                    let img = match self.image(mesh.texture_id) {
+                        Some(img) => img,
+                        None => continue,
+                    };
+
+                    let (vs, is) = match (
+                        self.vbuf.alloc(&mesh.vertices),
+                        self.ibuf.alloc(&mesh.indices),
+                    ) {
+                        (Some(vs), Some(is)) => (vs, is),
+                        _ => {
+                            log::warn!(
+                                "rokol: egui meshes overflowed (the buffers grow on next frame)"
+                            );
+                            break;
+                        }
+                    };
+
+                    let mut bind = Bindings::default();
+                    vs.set_vertex(&mut bind, 0);
+                    is.set_index(&mut bind);
+                    bind.fs_images[0] = img;
+                    rg::apply_bindings(&bind);
+
+                    rg::draw(0, mesh.indices.len() as u32, 1);
+                }
+                Primitive::Callback(callback) => {
+                    let f = match callback.callback.downcast_ref::<CallbackFn>() {
+                        Some(f) => f,
+                        None => {
+                            log::warn!("rokol: unknown egui paint callback");
+                            continue;
+                        }
+                    };
+
+                    let clip_rect = clip_rect.intersect(callback.rect);
+                    if !scissor(clip_rect) {
+                        continue;
+                    }
+
+                    (f.f)(&PaintCallbackInfo {
+                        viewport: callback.rect,
+                        clip_rect,
+                        pixels_per_point,
+                        screen_size_px,
+                    });
+                    self.pip.apply(&transform);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn texture(size: [usize; 2]) -> Texture {
+        Texture::new(size, vec![0; size[0] * size[1] * 4], TextureOptions::LINEAR)
+    }
+
+    #[test]
+    fn patch_rows() {
+        let mut tex = self::texture([4, 3]);
+        let patch: Vec<u8> = (1..=16).collect();
+        assert!(tex.patch([1, 1], [2, 2], &patch));
+
+        let row = |y: usize| &tex.pixels[y * 16..(y + 1) * 16];
+        assert_eq!(row(0), &[0; 16]);
+        assert_eq!(&row(1)[..4], &[0; 4]);
+        assert_eq!(&row(1)[4..12], &patch[..8]);
+        assert_eq!(&row(1)[12..], &[0; 4]);
+        assert_eq!(&row(2)[4..12], &patch[8..]);
+    }
+
+    #[test]
+    fn patch_bounds() {
+        let mut tex = self::texture([4, 3]);
+        assert!(!tex.patch([3, 0], [2, 1], &[1; 8]));
+        assert!(!tex.patch([0, 2], [1, 2], &[1; 8]));
+        // too few pixels for the patch size
+        assert!(!tex.patch([0, 0], [2, 2], &[1; 8]));
+        assert!(tex.pixels.iter().all(|&x| x == 0));
+
+        // touching the edges
+        assert!(tex.patch([2, 2], [2, 1], &[1; 8]));
+        assert_eq!(&tex.pixels[40..], &[1; 8]);
+    }
+
+    #[test]
+    fn update_branches() {
+        let mut textures = HashMap::new();
+        let options = TextureOptions::LINEAR;
+
+        // patching a missing texture
+        let update =
+            self::update_texture(&mut textures, 1, Some([0, 0]), [1, 1], vec![1; 4], options);
+        assert_eq!(update, TextureUpdate::Skipped);
+        assert!(textures.is_empty());
+
+        // create
+        let update = self::update_texture(&mut textures, 1, None, [2, 2], vec![1; 16], options);
+        assert_eq!(update, TextureUpdate::Created(None));
+        textures.get_mut(&1).unwrap().img = Image { id: 10 };
+
+        // replace the pixels of the same size and options
+        let update = self::update_texture(&mut textures, 1, None, [2, 2], vec![2; 16], options);
+        assert_eq!(update, TextureUpdate::Updated);
+        assert_eq!(textures[&1].pixels, vec![2; 16]);
+        assert_eq!(textures[&1].img, Image { id: 10 });
+
+        // patch
+        let update =
+            self::update_texture(&mut textures, 1, Some([1, 1]), [1, 1], vec![3; 4], options);
+        assert_eq!(update, TextureUpdate::Updated);
+        assert_eq!(&textures[&1].pixels[12..], &[3; 4]);
+        assert_eq!(&textures[&1].pixels[..12], &[2; 12]);
+
+        // out-of-bounds patch
+        let update =
+            self::update_texture(&mut textures, 1, Some([2, 0]), [1, 1], vec![4; 4], options);
+        assert_eq!(update, TextureUpdate::Skipped);
+
+        // recreate on resize or new options
+        let update = self::update_texture(&mut textures, 1, None, [3, 1], vec![5; 12], options);
+        assert_eq!(update, TextureUpdate::Created(Some(Image { id: 10 })));
+        assert_eq!(textures[&1].size, [3, 1]);
+        textures.get_mut(&1).unwrap().img = Image { id: 11 };
+
+        let nearest = TextureOptions::NEAREST;
+        let update = self::update_texture(&mut textures, 1, None, [3, 1], vec![5; 12], nearest);
+        assert_eq!(update, TextureUpdate::Created(Some(Image { id: 11 })));
+        assert_eq!(textures[&1].options, nearest);
+        assert_eq!(textures.len(), 1);
+    }
+}
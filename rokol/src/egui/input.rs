@@ -0,0 +1,496 @@
+/*!
+Input adapters that turn window events into [`RawInput`]
+
+Pass every event to [`Input::handle_event`] (`rokol::app`) or [`Input::handle_sdl_event`]
+(`sdl2`) and call [`Input::take`] before `egui::Context::run`. Ask the context whether egui
+wants the event (`wants_pointer_input`, `wants_keyboard_input`) to let the game skip it.
+
+Paste events are made from `rokol::app`'s `ClipboardPasted` event. With SDL, push
+[`Event::Paste`] yourself.
+*/
+
+use std::time::Instant;
+
+use egui::{Event, Key, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2};
+
+/// Scroll distance of one wheel step in points
+const POINTS_PER_LINE: f32 = 50.0;
+
+/// Accumulates egui events until the next frame
+#[derive(Debug, Clone)]
+pub struct Input {
+    events: Vec<Event>,
+    modifiers: Modifiers,
+    pointer: Pos2,
+    focused: bool,
+    start: Instant,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            modifiers: Modifiers::NONE,
+            pointer: Pos2::ZERO,
+            focused: true,
+            start: Instant::now(),
+        }
+    }
+}
+
+/// [`Modifiers`] where `command` is Super on macOS and Ctrl elsewhere
+pub fn modifiers(ctrl: bool, shift: bool, alt: bool, super_: bool) -> Modifiers {
+    let is_mac = cfg!(target_os = "macos");
+    Modifiers {
+        alt,
+        ctrl,
+        shift,
+        mac_cmd: is_mac && super_,
+        command: if is_mac { super_ } else { ctrl },
+    }
+}
+
+impl Input {
+    /// Pushes an event as is
+    pub fn push(&mut self, ev: Event) {
+        self.events.push(ev);
+    }
+
+    /// Makes [`RawInput`] of this frame out of the screen size in points and the DPI scale
+    pub fn take(&mut self, size: [f32; 2], pixels_per_point: f32) -> RawInput {
+        RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::from(size))),
+            pixels_per_point: Some(pixels_per_point),
+            time: Some(self.start.elapsed().as_secs_f64()),
+            modifiers: self.modifiers,
+            events: std::mem::take(&mut self.events),
+            focused: self.focused,
+            ..Default::default()
+        }
+    }
+
+    /// [`Input::take`] with the `rokol::app` window
+    #[cfg(feature = "impl-app")]
+    pub fn take_app(&mut self) -> RawInput {
+        let scale = crate::app::dpi_scale().max(1e-3);
+        let [w, h] = crate::app::size_f();
+        let mut raw = self.take([w / scale, h / scale], scale);
+        raw.predicted_dt = crate::app::frame_duration() as f32;
+        raw
+    }
+
+    /// Sets the modifiers sent with the following events
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    /// Pushes a key event, along with [`Event::Copy`] or [`Event::Cut`] on the shortcuts
+    pub fn key(&mut self, key: Key, pressed: bool) {
+        if pressed && self.modifiers.command {
+            match key {
+                Key::C => self.events.push(Event::Copy),
+                Key::X => self.events.push(Event::Cut),
+                _ => {}
+            }
+        }
+
+        self.events.push(Event::Key {
+            key,
+            pressed,
+            // egui detects repeats by itself
+            repeat: false,
+            modifiers: self.modifiers,
+        });
+    }
+
+    /// Pushes text input unless Ctrl or Cmd is held
+    pub fn text(&mut self, text: String) {
+        // shortcuts are not text
+        if !text.is_empty() && !self.modifiers.ctrl && !self.modifiers.mac_cmd {
+            self.events.push(Event::Text(text));
+        }
+    }
+
+    /// Pushes a button event at the last pointer position
+    pub fn button(&mut self, button: PointerButton, pressed: bool) {
+        self.events.push(Event::PointerButton {
+            pos: self.pointer,
+            button,
+            pressed,
+            modifiers: self.modifiers,
+        });
+    }
+
+    /// Pushes a pointer position in points
+    pub fn pointer_moved(&mut self, pos: Pos2) {
+        self.pointer = pos;
+        self.events.push(Event::PointerMoved(pos));
+    }
+
+    /// Pushes a scroll in lines (wheel steps)
+    pub fn scroll(&mut self, lines: [f32; 2]) {
+        let delta = Vec2::from(lines) * POINTS_PER_LINE;
+        if delta != Vec2::ZERO {
+            self.events.push(Event::Scroll(delta));
+        }
+    }
+
+    /// Pushes a window focus change
+    pub fn focus(&mut self, focused: bool) {
+        self.focused = focused;
+        self.events.push(Event::WindowFocused(focused));
+        if !focused {
+            // keys held while the window loses focus are never released
+            self.modifiers = Modifiers::NONE;
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// `rokol::app`
+
+#[cfg(feature = "impl-app")]
+impl Input {
+    /// Feeds a `rokol::app` event
+    pub fn handle_event(&mut self, ev: &crate::app::Event) {
+        use crate::app::{EventType, Mod};
+
+        let scale = crate::app::dpi_scale().max(1e-3);
+        let ty = EventType::from_ffi(ev.type_);
+
+        if matches!(
+            ty,
+            EventType::KeyDown
+                | EventType::KeyUp
+                | EventType::Char
+                | EventType::MouseDown
+                | EventType::MouseUp
+                | EventType::MouseScroll
+        ) {
+            let mods = Mod::from_bits_truncate(ev.modifiers);
+            self.modifiers = self::modifiers(
+                mods.contains(Mod::CONTROL),
+                mods.contains(Mod::SHIFT),
+                mods.contains(Mod::ALT),
+                mods.contains(Mod::SUPER),
+            );
+        }
+
+        match ty {
+            EventType::KeyDown | EventType::KeyUp => {
+                if let Some(key) = self::egui_key(crate::app::Key::from_ffi(ev.key_code)) {
+                    self.key(key, ty == EventType::KeyDown);
+                }
+            }
+            EventType::Char => {
+                if let Some(c) = char::from_u32(ev.char_code).filter(|c| !c.is_control()) {
+                    self.text(c.to_string());
+                }
+            }
+            EventType::MouseDown | EventType::MouseUp => {
+                self.pointer = Pos2::new(ev.mouse_x / scale, ev.mouse_y / scale);
+                if let Some(button) =
+                    self::egui_button(crate::app::Mouse::from_ffi(ev.mouse_button))
+                {
+                    self.button(button, ty == EventType::MouseDown);
+                }
+            }
+            EventType::MouseMove => {
+                self.pointer_moved(Pos2::new(ev.mouse_x / scale, ev.mouse_y / scale));
+            }
+            EventType::MouseScroll => {
+                self.scroll([ev.scroll_x, ev.scroll_y]);
+            }
+            EventType::MouseLeave => {
+                self.events.push(Event::PointerGone);
+            }
+            EventType::Focused => self.focus(true),
+            EventType::Unocused => self.focus(false),
+            EventType::ClipboardPasted => match crate::app::clipboard() {
+                Ok(text) => self.events.push(Event::Paste(text)),
+                Err(err) => log::warn!("rokol: non UTF-8 clipboard: {}", err),
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Maps a `rokol::app` mouse button to an egui pointer button
+#[cfg(feature = "impl-app")]
+pub fn egui_button(button: crate::app::Mouse) -> Option<PointerButton> {
+    use crate::app::Mouse;
+
+    Some(match button {
+        Mouse::Left => PointerButton::Primary,
+        Mouse::Right => PointerButton::Secondary,
+        Mouse::Middle => PointerButton::Middle,
+        Mouse::Invalid => return None,
+    })
+}
+
+/// Maps a `rokol::app` key to an egui key
+#[cfg(feature = "impl-app")]
+pub fn egui_key(key: crate::app::Key) -> Option<Key> {
+    use crate::app::Key as K;
+
+    Some(match key {
+        K::Down => Key::ArrowDown,
+        K::Left => Key::ArrowLeft,
+        K::Right => Key::ArrowRight,
+        K::Up => Key::ArrowUp,
+        K::Escape => Key::Escape,
+        K::Tab => Key::Tab,
+        K::Backspace => Key::Backspace,
+        K::Enter | K::KPEnter => Key::Enter,
+        K::Space => Key::Space,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Minus | K::KPSubtract => Key::Minus,
+        K::Equal | K::KPAdd => Key::PlusEquals,
+        K::Kbd0 | K::KP0 => Key::Num0,
+        K::Kbd1 | K::KP1 => Key::Num1,
+        K::Kbd2 | K::KP2 => Key::Num2,
+        K::Kbd3 | K::KP3 => Key::Num3,
+        K::Kbd4 | K::KP4 => Key::Num4,
+        K::Kbd5 | K::KP5 => Key::Num5,
+        K::Kbd6 | K::KP6 => Key::Num6,
+        K::Kbd7 | K::KP7 => Key::Num7,
+        K::Kbd8 | K::KP8 => Key::Num8,
+        K::Kbd9 | K::KP9 => Key::Num9,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::F13 => Key::F13,
+        K::F14 => Key::F14,
+        K::F15 => Key::F15,
+        K::F16 => Key::F16,
+        K::F17 => Key::F17,
+        K::F18 => Key::F18,
+        K::F19 => Key::F19,
+        K::F20 => Key::F20,
+        _ => return None,
+    })
+}
+
+// --------------------------------------------------------------------------------
+// SDL
+
+#[cfg(feature = "sdl2")]
+impl Input {
+    /// Feeds an SDL event. Mouse positions are expected in points (logical pixels).
+    pub fn handle_sdl_event(&mut self, ev: &sdl2::event::Event) {
+        use sdl2::{
+            event::{Event as SdlEvent, WindowEvent},
+            keyboard::Mod,
+        };
+
+        match ev {
+            SdlEvent::KeyDown {
+                keycode, keymod, ..
+            }
+            | SdlEvent::KeyUp {
+                keycode, keymod, ..
+            } => {
+                self.modifiers = self::modifiers(
+                    keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+                    keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+                    keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+                    keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+                );
+                if let Some(key) = keycode.and_then(self::egui_key_sdl) {
+                    self.key(key, matches!(ev, SdlEvent::KeyDown { .. }));
+                }
+            }
+            SdlEvent::TextInput { text, .. } => {
+                self.text(text.clone());
+            }
+            SdlEvent::MouseButtonDown {
+                mouse_btn, x, y, ..
+            }
+            | SdlEvent::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.pointer = Pos2::new(*x as f32, *y as f32);
+                if let Some(button) = self::egui_button_sdl(*mouse_btn) {
+                    self.button(button, matches!(ev, SdlEvent::MouseButtonDown { .. }));
+                }
+            }
+            SdlEvent::MouseMotion { x, y, .. } => {
+                self.pointer_moved(Pos2::new(*x as f32, *y as f32));
+            }
+            SdlEvent::MouseWheel { x, y, .. } => {
+                self.scroll([*x as f32, *y as f32]);
+            }
+            SdlEvent::Window { win_event, .. } => match win_event {
+                WindowEvent::Leave => self.events.push(Event::PointerGone),
+                WindowEvent::FocusGained => self.focus(true),
+                WindowEvent::FocusLost => self.focus(false),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Maps an SDL mouse button to an egui pointer button
+#[cfg(feature = "sdl2")]
+pub fn egui_button_sdl(button: sdl2::mouse::MouseButton) -> Option<PointerButton> {
+    use sdl2::mouse::MouseButton as M;
+
+    Some(match button {
+        M::Left => PointerButton::Primary,
+        M::Right => PointerButton::Secondary,
+        M::Middle => PointerButton::Middle,
+        M::X1 => PointerButton::Extra1,
+        M::X2 => PointerButton::Extra2,
+        M::Unknown => return None,
+    })
+}
+
+/// Maps an SDL keycode to an egui key
+#[cfg(feature = "sdl2")]
+pub fn egui_key_sdl(key: sdl2::keyboard::Keycode) -> Option<Key> {
+    use sdl2::keyboard::Keycode as K;
+
+    Some(match key {
+        K::Down => Key::ArrowDown,
+        K::Left => Key::ArrowLeft,
+        K::Right => Key::ArrowRight,
+        K::Up => Key::ArrowUp,
+        K::Escape => Key::Escape,
+        K::Tab => Key::Tab,
+        K::Backspace => Key::Backspace,
+        K::Return | K::KpEnter => Key::Enter,
+        K::Space => Key::Space,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Minus | K::KpMinus => Key::Minus,
+        K::Equals | K::Plus | K::KpPlus => Key::PlusEquals,
+        K::Num0 | K::Kp0 => Key::Num0,
+        K::Num1 | K::Kp1 => Key::Num1,
+        K::Num2 | K::Kp2 => Key::Num2,
+        K::Num3 | K::Kp3 => Key::Num3,
+        K::Num4 | K::Kp4 => Key::Num4,
+        K::Num5 | K::Kp5 => Key::Num5,
+        K::Num6 | K::Kp6 => Key::Num6,
+        K::Num7 | K::Kp7 => Key::Num7,
+        K::Num8 | K::Kp8 => Key::Num8,
+        K::Num9 | K::Kp9 => Key::Num9,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::F13 => Key::F13,
+        K::F14 => Key::F14,
+        K::F15 => Key::F15,
+        K::F16 => Key::F16,
+        K::F17 => Key::F17,
+        K::F18 => Key::F18,
+        K::F19 => Key::F19,
+        K::F20 => Key::F20,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shortcuts() {
+        let mut input = Input {
+            modifiers: self::modifiers(true, false, false, true),
+            ..Default::default()
+        };
+        input.key(Key::C, true);
+        input.text("c".to_string());
+
+        let raw = input.take([640.0, 360.0], 2.0);
+        assert_eq!(raw.pixels_per_point, Some(2.0));
+        assert_eq!(raw.screen_rect.unwrap().max, Pos2::new(640.0, 360.0));
+        assert!(raw.modifiers.command);
+        // a copy event and a key event without text
+        assert!(matches!(
+            raw.events[..],
+            [Event::Copy, Event::Key { key: Key::C, .. }]
+        ));
+        assert!(input.take([640.0, 360.0], 2.0).events.is_empty());
+    }
+}
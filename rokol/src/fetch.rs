@@ -0,0 +1,466 @@
+/*!
+Asynchronous file loading with [`sokol_fetch.h`] (`impl-fetch` feature)
+
+Files are read on I/O threads. Completion closures run inside [`dowork`] on the thread that called
+[`setup`], so call [`dowork`] every frame (e.g. from `RApp::frame`):
+
+```no_run
+use rokol::fetch::{self, FetchDesc, Request};
+
+fetch::setup(&FetchDesc {
+    num_channels: 2,
+    ..Default::default()
+});
+
+Request::new("assets/level.json")
+    .channel(1)
+    .send(|res| match res.data {
+        Ok(bytes) => println!("loaded {} ({} bytes)", res.path, bytes.len()),
+        Err(err) => println!("failed to load {}: {}", res.path, err),
+    })
+    .unwrap();
+
+// every frame:
+fetch::dowork();
+```
+
+Requests on the same channel are handled in order; each channel handles `num_lanes` requests at
+once. The lane of a request is chosen by Sokol and reported in the [`Response`].
+
+Integration helpers:
+
+* [`load_image`] allocates an [`Image`] right now and initializes it when the file is fetched and
+  decoded (like [`crate::gfx::loader`] without worker threads).
+* [`fetch_file`] returns a [`FetchedFile`] to poll every frame, e.g. to pass the bytes to
+  `FontStash::add_font_mem` of the `fons` module.
+
+[`sokol_fetch.h`]: https://github.com/floooh/sokol/blob/master/sokol_fetch.h
+*/
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    ffi::{c_void, CString},
+    fmt,
+    mem::size_of,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use rokol_ffi::fetch as ffi;
+
+#[cfg(feature = "impl-gfx")]
+use crate::gfx::{
+    loader::{self, DecodeError, DecodedImage},
+    BakedResource, Image, ImageDesc,
+};
+
+/// `sfetch_desc_t`. Zero fields are replaced with Sokol defaults (128 requests, 1 channel, 1 lane)
+pub type FetchDesc = ffi::sfetch_desc_t;
+
+/// Handle of a sent request
+pub type Handle = ffi::sfetch_handle_t;
+
+/// Size of the buffer allocated for a [`Request`] without a buffer. Such files are streamed in
+/// chunks of this size.
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Error of a [`Request`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FetchError {
+    FileNotFound,
+    /// No buffer was bound to the request
+    NoBuffer,
+    /// The file doesn't fit in the buffer of the request
+    BufferTooSmall,
+    UnexpectedEof,
+    InvalidHttpStatus,
+    Cancelled,
+    /// The path contains a nul byte
+    InvalidPath,
+    /// `sokol_fetch.h` is not set up, the request pool is full or the channel is out of range
+    Rejected,
+    /// The image pool is full ([`load_image`])
+    PoolExhausted,
+}
+
+impl FetchError {
+    fn from_ffi(code: ffi::sfetch_error_t) -> Self {
+        match code {
+            ffi::sfetch_error_t::SFETCH_ERROR_FILE_NOT_FOUND => Self::FileNotFound,
+            ffi::sfetch_error_t::SFETCH_ERROR_NO_BUFFER => Self::NoBuffer,
+            ffi::sfetch_error_t::SFETCH_ERROR_BUFFER_TOO_SMALL => Self::BufferTooSmall,
+            ffi::sfetch_error_t::SFETCH_ERROR_UNEXPECTED_EOF => Self::UnexpectedEof,
+            ffi::sfetch_error_t::SFETCH_ERROR_INVALID_HTTP_STATUS => Self::InvalidHttpStatus,
+            _ => Self::Cancelled,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::FileNotFound => "file not found",
+            Self::NoBuffer => "no buffer",
+            Self::BufferTooSmall => "buffer too small",
+            Self::UnexpectedEof => "unexpected end of file",
+            Self::InvalidHttpStatus => "invalid HTTP status",
+            Self::Cancelled => "cancelled",
+            Self::InvalidPath => "invalid path",
+            Self::Rejected => "request rejected",
+            Self::PoolExhausted => "image pool is exhausted",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Result of a [`Request`] given to the completion closure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub path: String,
+    pub channel: u32,
+    pub lane: u32,
+    /// Content of the file
+    pub data: Result<Vec<u8>, FetchError>,
+}
+
+// --------------------------------------------------------------------------------
+// Setup
+
+thread_local! {
+    /// Requests waiting for their completion callbacks (freed on [`shutdown`])
+    static PENDING: RefCell<HashSet<*mut Pending>> = RefCell::new(HashSet::new());
+}
+
+/// Sets up `sokol_fetch.h` for the current thread
+pub fn setup(desc: &FetchDesc) {
+    unsafe {
+        ffi::sfetch_setup(desc);
+    }
+}
+
+/// Shuts down `sokol_fetch.h` and drops unfinished requests without calling their closures
+pub fn shutdown() {
+    unsafe {
+        // joins the I/O threads
+        ffi::sfetch_shutdown();
+    }
+
+    let pending = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+    for ptr in pending {
+        drop(unsafe { Box::from_raw(ptr) });
+    }
+}
+
+pub fn is_valid() -> bool {
+    unsafe { ffi::sfetch_valid() }
+}
+
+/// Actual [`FetchDesc`] with defaults applied
+pub fn desc() -> FetchDesc {
+    unsafe { ffi::sfetch_desc() }
+}
+
+/// Maximum length of a path in bytes (including the nul terminator)
+pub fn max_path() -> usize {
+    unsafe { ffi::sfetch_max_path() as usize }
+}
+
+/// Pumps responses and runs completion closures. Call it every frame on the thread that called
+/// [`setup`].
+pub fn dowork() {
+    unsafe {
+        ffi::sfetch_dowork();
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Requests
+
+/// Builder of a file request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    path: String,
+    channel: u32,
+    chunk_size: u32,
+    buffer: Option<Vec<u8>>,
+}
+
+impl Request {
+    /// Request on channel `0`, streamed in chunks of [`DEFAULT_CHUNK_SIZE`]
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            channel: 0,
+            chunk_size: 0,
+            buffer: None,
+        }
+    }
+
+    pub fn channel(mut self, channel: u32) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Loads the file into the capacity of the buffer. The file fails with
+    /// [`FetchError::BufferTooSmall`] if it doesn't fit (unless streamed with
+    /// [`Request::chunk_size`]).
+    pub fn buffer(mut self, buffer: Vec<u8>) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// Streams the file in chunks. The buffer must be at least as large as the chunks.
+    pub fn chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Sends the request. The closure runs in [`dowork`] when the request is finished, failed or
+    /// cancelled.
+    pub fn send(self, on_complete: impl FnOnce(Response) + 'static) -> Result<Handle, FetchError> {
+        let path = CString::new(self.path.as_str()).map_err(|_| FetchError::InvalidPath)?;
+
+        let (buffer, chunk_size) = match self.buffer {
+            Some(buf) => (buf, self.chunk_size),
+            None if self.chunk_size == 0 => (
+                Vec::with_capacity(DEFAULT_CHUNK_SIZE as usize),
+                DEFAULT_CHUNK_SIZE,
+            ),
+            None => (
+                Vec::with_capacity(self.chunk_size as usize),
+                self.chunk_size,
+            ),
+        };
+        assert!(
+            buffer.capacity() >= chunk_size as usize,
+            "fetch buffer is smaller than the chunk size"
+        );
+
+        let pending: *mut Pending = Box::into_raw(Box::new(Pending {
+            path: self.path,
+            buffer,
+            is_streaming: chunk_size > 0,
+            data: Vec::new(),
+            on_complete: Box::new(on_complete),
+        }));
+
+        // `sokol_fetch.h` copies the path and the user data (the pointer)
+        let req = unsafe {
+            ffi::sfetch_request_t {
+                channel: self.channel,
+                path: path.as_ptr(),
+                callback: Some(self::response_cb),
+                buffer_ptr: (*pending).buffer.as_mut_ptr() as *mut c_void,
+                buffer_size: (*pending).buffer.capacity() as u32,
+                chunk_size,
+                user_data_ptr: &pending as *const *mut Pending as *const c_void,
+                user_data_size: size_of::<*mut Pending>() as u32,
+                ..Default::default()
+            }
+        };
+
+        let handle = unsafe { ffi::sfetch_send(&req) };
+        if !self::is_handle_valid(handle) {
+            drop(unsafe { Box::from_raw(pending) });
+            return Err(FetchError::Rejected);
+        }
+
+        PENDING.with(|p| p.borrow_mut().insert(pending));
+        Ok(handle)
+    }
+}
+
+/// If the request is still in flight
+pub fn is_handle_valid(h: Handle) -> bool {
+    unsafe { ffi::sfetch_handle_valid(h) }
+}
+
+/// Cancels a request. Its closure is called with [`FetchError::Cancelled`].
+pub fn cancel(h: Handle) {
+    unsafe {
+        ffi::sfetch_cancel(h);
+    }
+}
+
+/// Pauses a streamed request
+pub fn pause(h: Handle) {
+    unsafe {
+        ffi::sfetch_pause(h);
+    }
+}
+
+/// Resumes a paused request
+pub fn resume(h: Handle) {
+    unsafe {
+        ffi::sfetch_continue(h);
+    }
+}
+
+struct Pending {
+    path: String,
+    /// Bound to the request (whole file or a chunk)
+    buffer: Vec<u8>,
+    is_streaming: bool,
+    /// Chunks accumulated so far
+    data: Vec<u8>,
+    on_complete: Box<dyn FnOnce(Response)>,
+}
+
+unsafe extern "C" fn response_cb(res: *const ffi::sfetch_response_t) {
+    let res = &*res;
+    let ptr = *(res.user_data as *const *mut Pending);
+
+    if res.fetched {
+        let pending = &mut *ptr;
+        pending.buffer.set_len(res.fetched_size as usize);
+        if pending.is_streaming {
+            pending.data.extend_from_slice(&pending.buffer);
+        } else {
+            pending.data = std::mem::take(&mut pending.buffer);
+        }
+    }
+
+    if !res.finished {
+        return;
+    }
+
+    PENDING.with(|p| p.borrow_mut().remove(&ptr));
+    let pending = Box::from_raw(ptr);
+
+    let data = if res.failed {
+        Err(FetchError::from_ffi(res.error_code))
+    } else {
+        Ok(pending.data)
+    };
+
+    let response = Response {
+        path: pending.path.clone(),
+        channel: res.channel,
+        lane: res.lane,
+        data,
+    };
+
+    // unwinding into C is undefined behavior
+    let on_complete = pending.on_complete;
+    if panic::catch_unwind(AssertUnwindSafe(move || on_complete(response))).is_err() {
+        log::warn!("rokol: completion of `{}` panicked", pending.path);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Integration
+
+type FileSlot = Rc<RefCell<Option<Result<Vec<u8>, FetchError>>>>;
+
+/// File loaded by [`fetch_file`]
+#[derive(Debug, Clone, Default)]
+pub struct FetchedFile {
+    slot: FileSlot,
+}
+
+impl FetchedFile {
+    /// If the request is finished (or failed)
+    pub fn is_done(&self) -> bool {
+        self.slot.borrow().is_some()
+    }
+
+    /// Takes the result if the request is finished
+    pub fn take(&self) -> Option<Result<Vec<u8>, FetchError>> {
+        self.slot.borrow_mut().take()
+    }
+}
+
+/// Sends a request that stores the content in a [`FetchedFile`]
+pub fn fetch_file(req: Request) -> Result<FetchedFile, FetchError> {
+    let file = FetchedFile::default();
+    let slot = Rc::clone(&file.slot);
+    req.send(move |res| *slot.borrow_mut() = Some(res.data))?;
+    Ok(file)
+}
+
+/// Allocates an [`Image`] and initializes it when the file is fetched and decoded (or marks it as
+/// failed). Size, pixel format and data of the `template` are overwritten with the decoded image.
+///
+/// Use [`crate::gfx::loader::resolve_image`] to draw a placeholder while loading.
+#[cfg(feature = "impl-gfx")]
+pub fn load_image(
+    req: Request,
+    template: ImageDesc,
+    decode: impl FnOnce(&[u8]) -> Result<DecodedImage, DecodeError> + 'static,
+) -> Result<Image, FetchError> {
+    let img = loader::alloc_image().map_err(|_| FetchError::PoolExhausted)?;
+
+    let res = req.send(move |res| {
+        if !unsafe { rokol_ffi::gfx::sg_isvalid() } {
+            return;
+        }
+
+        let loaded = res
+            .data
+            .map_err(DecodeError::from)
+            .and_then(|bytes| decode(&bytes))
+            .and_then(|decoded| loader::init_image(img, template, &decoded));
+
+        match loaded {
+            Ok(()) => {}
+            Err(err) => {
+                log::warn!("rokol: failed to load image `{}`: {}", res.path, err);
+                unsafe {
+                    rokol_ffi::gfx::sg_fail_image(img);
+                }
+            }
+        }
+    });
+
+    if let Err(err) = res {
+        Image::dealloc(img);
+        return Err(err);
+    }
+
+    Ok(img)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn panicking_completion() {
+        let pending = Box::into_raw(Box::new(Pending {
+            path: "broken.bin".to_string(),
+            buffer: Vec::new(),
+            is_streaming: false,
+            data: Vec::new(),
+            on_complete: Box::new(|_| panic!("broken completion")),
+        }));
+        PENDING.with(|p| p.borrow_mut().insert(pending));
+
+        // `user_data` points to a copy of the pointer (see `Request::send`)
+        let mut user_data = pending;
+        let res = ffi::sfetch_response_t {
+            handle: ffi::sfetch_handle_t { id: 1 },
+            dispatched: true,
+            fetched: false,
+            paused: false,
+            finished: true,
+            failed: true,
+            cancelled: false,
+            error_code: ffi::sfetch_error_t::SFETCH_ERROR_FILE_NOT_FOUND,
+            channel: 0,
+            lane: 0,
+            path: std::ptr::null(),
+            user_data: &mut user_data as *mut *mut Pending as *mut c_void,
+            fetched_offset: 0,
+            fetched_size: 0,
+            buffer_ptr: std::ptr::null_mut(),
+            buffer_size: 0,
+        };
+
+        // the panic doesn't unwind into C and the request is dropped
+        unsafe { response_cb(&res) };
+        assert!(PENDING.with(|p| p.borrow().is_empty()));
+    }
+}
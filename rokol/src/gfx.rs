@@ -28,6 +28,17 @@ For example, for one frame with one screen rendering pass:
 * [`end_pass`]
 * [`commit`]
 
+# Loading
+
+* [`loader`]: decode resource data on worker threads and initialize them on [`loader::Loader::pump`]
+
+# Debugging
+
+* [`trace`]: [`TraceHooks`] as a Rust trait
+* [`stats`]: per-frame render statistics
+* [`record`]: capture `sokol_gfx.h` calls to a file and replay them
+* `registry`: live resources and leak report (`registry` feature)
+
 # References
 
 * Sokol articles (The Brain Dump)
@@ -43,12 +54,36 @@ For example, for one frame with one screen rendering pass:
 use {
     rokol_ffi::gfx as ffi,
     std::{
+        collections::BTreeSet,
         ffi::{c_void, CString},
         mem::size_of,
+        os::raw::c_char,
+        sync::Mutex,
     },
 };
 
-/// Implements [`LayoutDesc`] constructor (i.e., `layout_desc` method)
+mod buffer;
+mod config;
+pub mod loader;
+pub mod record;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod stats;
+mod stream;
+pub mod trace;
+pub mod update;
+
+pub use self::{
+    buffer::{pipeline_desc, BufferError, Index, IndexBuffer, Pod, VertexBuffer, VertexLayout},
+    config::{GfxConfig, GfxConfigError, MAX_POOL_SIZE},
+    stream::{StreamBuffer, StreamRange},
+};
+
+#[cfg(feature = "registry")]
+use self::registry::ResourceKind;
+
+/// Implements [`LayoutDesc`] constructor (i.e., `layout_desc` method), [`VertexLayout`] and
+/// [`Pod`]. Fails to compile if the struct has padding bytes.
 ///
 /// TODO: support more types?
 pub use rokol_derive::LayoutDesc;
@@ -56,7 +91,7 @@ pub use rokol_derive::LayoutDesc;
 /// Field of [`SetupDesc`]
 pub type SetupContextDesc = ffi::sg_context_desc;
 
-/// [`setup`] parameters
+/// [`setup`] parameters. Consider using [`GfxConfig`].
 pub type SetupDesc = ffi::sg_desc;
 
 /// Sets up `sokol_gfx.h`. You'd want to use glue code in this crate.
@@ -66,8 +101,11 @@ pub fn setup(desc: &SetupDesc) {
     }
 }
 
-/// Cleans up `sokol_gfx.h`
+/// Cleans up `sokol_gfx.h`. Logs leaked resources with `registry` feature.
 pub fn shutdown() {
+    #[cfg(feature = "registry")]
+    registry::report_leaks();
+
     unsafe {
         ffi::sg_shutdown();
     }
@@ -771,21 +809,31 @@ impl BakedResource for Buffer {
     type Desc = BufferDesc;
 
     fn create(desc: &Self::Desc) -> Self::Id {
-        unsafe { ffi::sg_make_buffer(desc) }
+        let id = unsafe { ffi::sg_make_buffer(desc) };
+        #[cfg(feature = "registry")]
+        registry::on_create(ResourceKind::Buffer, id.id, desc.label);
+        id
     }
 
     fn alloc() -> Self::Id {
-        unsafe { ffi::sg_alloc_buffer() }
+        let id = unsafe { ffi::sg_alloc_buffer() };
+        #[cfg(feature = "registry")]
+        registry::on_alloc(ResourceKind::Buffer, id.id);
+        id
     }
 
     fn init(id: Self::Id, desc: &Self::Desc) {
         unsafe { ffi::sg_init_buffer(id, desc) }
+        #[cfg(feature = "registry")]
+        registry::on_init(ResourceKind::Buffer, id.id, desc.label);
     }
 
     fn destroy(id: Self::Id) {
         unsafe {
             ffi::sg_destroy_buffer(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Buffer, id.id);
     }
 
     fn uninit(id: Self::Id) {
@@ -798,6 +846,8 @@ impl BakedResource for Buffer {
         unsafe {
             ffi::sg_dealloc_buffer(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Buffer, id.id);
     }
 }
 
@@ -814,21 +864,31 @@ impl BakedResource for Image {
     type Desc = ImageDesc;
 
     fn create(desc: &Self::Desc) -> Self::Id {
-        unsafe { ffi::sg_make_image(desc) }
+        let id = unsafe { ffi::sg_make_image(desc) };
+        #[cfg(feature = "registry")]
+        registry::on_create(ResourceKind::Image, id.id, desc.label);
+        id
     }
 
     fn alloc() -> Self::Id {
-        unsafe { ffi::sg_alloc_image() }
+        let id = unsafe { ffi::sg_alloc_image() };
+        #[cfg(feature = "registry")]
+        registry::on_alloc(ResourceKind::Image, id.id);
+        id
     }
 
     fn init(id: Self::Id, desc: &Self::Desc) {
         unsafe { ffi::sg_init_image(id, desc) }
+        #[cfg(feature = "registry")]
+        registry::on_init(ResourceKind::Image, id.id, desc.label);
     }
 
     fn destroy(id: Self::Id) {
         unsafe {
             ffi::sg_destroy_image(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Image, id.id);
     }
 
     fn uninit(id: Self::Id) {
@@ -841,6 +901,8 @@ impl BakedResource for Image {
         unsafe {
             ffi::sg_dealloc_image(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Image, id.id);
     }
 }
 
@@ -857,21 +919,31 @@ impl BakedResource for Pipeline {
     type Desc = PipelineDesc;
 
     fn create(desc: &Self::Desc) -> Self::Id {
-        unsafe { ffi::sg_make_pipeline(desc) }
+        let id = unsafe { ffi::sg_make_pipeline(desc) };
+        #[cfg(feature = "registry")]
+        registry::on_create(ResourceKind::Pipeline, id.id, desc.label);
+        id
     }
 
     fn alloc() -> Self::Id {
-        unsafe { ffi::sg_alloc_pipeline() }
+        let id = unsafe { ffi::sg_alloc_pipeline() };
+        #[cfg(feature = "registry")]
+        registry::on_alloc(ResourceKind::Pipeline, id.id);
+        id
     }
 
     fn init(id: Self::Id, desc: &Self::Desc) {
         unsafe { ffi::sg_init_pipeline(id, desc) }
+        #[cfg(feature = "registry")]
+        registry::on_init(ResourceKind::Pipeline, id.id, desc.label);
     }
 
     fn destroy(id: Self::Id) {
         unsafe {
             ffi::sg_destroy_pipeline(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Pipeline, id.id);
     }
 
     fn uninit(id: Self::Id) {
@@ -884,6 +956,8 @@ impl BakedResource for Pipeline {
         unsafe {
             ffi::sg_dealloc_pipeline(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Pipeline, id.id);
     }
 }
 
@@ -899,21 +973,31 @@ impl BakedResource for Pass {
     type Desc = PassDesc;
 
     fn create(desc: &Self::Desc) -> Self::Id {
-        unsafe { ffi::sg_make_pass(desc) }
+        let id = unsafe { ffi::sg_make_pass(desc) };
+        #[cfg(feature = "registry")]
+        registry::on_create(ResourceKind::Pass, id.id, desc.label);
+        id
     }
 
     fn alloc() -> Self::Id {
-        unsafe { ffi::sg_alloc_pass() }
+        let id = unsafe { ffi::sg_alloc_pass() };
+        #[cfg(feature = "registry")]
+        registry::on_alloc(ResourceKind::Pass, id.id);
+        id
     }
 
     fn init(id: Self::Id, desc: &Self::Desc) {
         unsafe { ffi::sg_init_pass(id, desc) }
+        #[cfg(feature = "registry")]
+        registry::on_init(ResourceKind::Pass, id.id, desc.label);
     }
 
     fn destroy(id: Self::Id) {
         unsafe {
             ffi::sg_destroy_pass(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Pass, id.id);
     }
 
     fn uninit(id: Self::Id) {
@@ -926,6 +1010,8 @@ impl BakedResource for Pass {
         unsafe {
             ffi::sg_dealloc_pass(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Pass, id.id);
     }
 }
 
@@ -944,21 +1030,31 @@ impl BakedResource for Shader {
     type Desc = ShaderDesc;
 
     fn create(desc: &Self::Desc) -> Self::Id {
-        unsafe { ffi::sg_make_shader(desc) }
+        let id = unsafe { ffi::sg_make_shader(desc) };
+        #[cfg(feature = "registry")]
+        registry::on_create(ResourceKind::Shader, id.id, desc.label);
+        id
     }
 
     fn alloc() -> Self::Id {
-        unsafe { ffi::sg_alloc_shader() }
+        let id = unsafe { ffi::sg_alloc_shader() };
+        #[cfg(feature = "registry")]
+        registry::on_alloc(ResourceKind::Shader, id.id);
+        id
     }
 
     fn init(id: Self::Id, desc: &Self::Desc) {
         unsafe { ffi::sg_init_shader(id, desc) }
+        #[cfg(feature = "registry")]
+        registry::on_init(ResourceKind::Shader, id.id, desc.label);
     }
 
     fn destroy(id: Self::Id) {
         unsafe {
             ffi::sg_destroy_shader(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Shader, id.id);
     }
 
     fn uninit(id: Self::Id) {
@@ -971,6 +1067,8 @@ impl BakedResource for Shader {
         unsafe {
             ffi::sg_dealloc_shader(id);
         }
+        #[cfg(feature = "registry")]
+        registry::on_dealloc(ResourceKind::Shader, id.id);
     }
 }
 
@@ -1039,6 +1137,7 @@ pub fn commit() {
     unsafe {
         ffi::sg_commit();
     }
+    self::update::on_commit();
 }
 
 /// Applies [`Pipeline`]: vertex-layouts, shader and render states)
@@ -1068,6 +1167,15 @@ pub fn apply_uniforms(stage: ShaderStage, ub_index: u32, data: &[u8]) {
     }
 }
 
+/// [`apply_uniforms`] with a uniform block of [`Pod`] type
+pub fn apply_uniforms_as<T: Pod>(stage: ShaderStage, ub_index: u32, data: &T) {
+    self::apply_uniforms(
+        stage,
+        ub_index,
+        buffer::as_bytes(std::slice::from_ref(data)),
+    );
+}
+
 /// `draw(base_elems, n_elems, n_instances)`
 pub fn draw(base_elem: u32, n_elems: u32, n_instances: u32) {
     unsafe {
@@ -1116,7 +1224,7 @@ pub fn viewport_f(x: f32, y: f32, w: f32, h: f32) {
 ///
 /// Requires [`ResourceUsage::Dynamic`] or [`ResourceUsage::Stream`].
 ///
-/// WARNING: can be called only once a frame
+/// WARNING: can be called only once a frame. See [`update::update_buffer`] for a safe version.
 pub unsafe fn update_buffer(buf: Buffer, data: &[u8]) {
     let size = size_of::<u8>() * data.len();
     let data = Range {
@@ -1142,7 +1250,8 @@ pub fn append_buffer(buf: Buffer, data: &[u8]) -> i32 {
     unsafe { ffi::sg_append_buffer(buf, &data) }
 }
 
-/// Only one update per frame is allowed for buffer and image resources
+/// Only one update per frame is allowed for buffer and image resources. See
+/// [`update::update_image`] for a safe version.
 pub unsafe fn update_image(img: Image, content: &ImageData) {
     ffi::sg_update_image(img, content);
 }
@@ -1173,7 +1282,7 @@ pub unsafe fn shader_desc(vs: &str, fs: &str) -> ShaderDesc {
     desc
 }
 
-/// [Non-Sokol] Helper for creating index buffer
+/// [Non-Sokol] Helper for creating index buffer. Consider using [`IndexBuffer`].
 pub fn ibuf_desc_immutable(buf: &[u8], label: &str) -> BufferDesc {
     let size = std::mem::size_of::<u8>() * buf.len();
     unsafe {
@@ -1192,7 +1301,7 @@ pub fn ibuf_desc_dyn(size: usize, usage: ResourceUsage, label: &str) -> BufferDe
     unsafe { buf_desc(std::ptr::null_mut(), size, BufferType::Index, usage, label) }
 }
 
-/// [Non-Sokol] Helper for creating immutable vertex buffer
+/// [Non-Sokol] Helper for creating immutable vertex buffer. Consider using [`VertexBuffer`].
 pub fn vbuf_desc_immutable(buf: &[u8], label: &str) -> BufferDesc {
     let size = std::mem::size_of::<u8>() * buf.len();
     unsafe {
@@ -1228,17 +1337,34 @@ pub unsafe fn buf_desc(
         },
         type_: buffer_type.to_ffi(),
         usage: usage.to_ffi(),
-        label: if label.is_empty() {
-            std::ptr::null_mut()
-        } else {
-            // FIXME: lifetime. maybe use global CString storage?
-            let label = CString::new(label).expect("Unable to create CString in BufferDesc::new");
-            label.as_ptr() as *mut _
-        },
+        label: self::intern_label(label),
         ..Default::default()
     }
 }
 
+/// [Non-Sokol] Returns a pointer to a label that lives until the end of the program (null if the
+/// label is empty)
+///
+/// Labels are deduplicated, so it's okay to intern the same label many times.
+pub fn intern_label(label: &str) -> *const c_char {
+    static LABELS: Mutex<BTreeSet<CString>> = Mutex::new(BTreeSet::new());
+
+    if label.is_empty() {
+        return std::ptr::null();
+    }
+
+    let label = CString::new(label).expect("Unable to create CString for label");
+    let mut labels = LABELS.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(interned) = labels.get(&label) {
+        return interned.as_ptr();
+    }
+
+    // the heap buffer of a `CString` doesn't move
+    let ptr = label.as_ptr();
+    labels.insert(label);
+    ptr
+}
+
 #[cfg(test)]
 mod test {
     use super::LayoutDesc;
@@ -1268,5 +1394,9 @@ mod test {
     #[test]
     fn layout_derive() {
         assert_eq!(Vertex::layout_desc(), Vertex::manual_layout_desc());
+        assert_eq!(
+            <Vertex as rg::VertexLayout>::layout_desc(),
+            Vertex::manual_layout_desc()
+        );
     }
 }
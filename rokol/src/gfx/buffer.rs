@@ -0,0 +1,474 @@
+/*!
+Typed vertex and index buffers that remember their element count
+*/
+
+use std::{
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::{size_of, size_of_val},
+    slice,
+};
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{
+    self as rg,
+    update::{self, UpdateError},
+    BakedResource, Bindings, Buffer, BufferType, IndexType, PipelineDesc, ResourceUsage,
+};
+
+/// Plain data that can be viewed as bytes. Implemented by `#[derive(LayoutDesc)]`.
+///
+/// # Safety
+///
+/// The type has to be `#[repr(C)]` without padding bytes, pointers, references or fields with
+/// invalid bit patterns.
+pub unsafe trait Pod {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for f32 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Vertex type with [`LayoutDesc`](crate::gfx::LayoutDesc). Implemented by
+/// `#[derive(LayoutDesc)]`.
+///
+/// # Safety
+///
+/// Vertices are uploaded as bytes, so the requirements of [`Pod`] apply. The layout has to match
+/// the fields.
+pub unsafe trait VertexLayout: Pod {
+    fn layout_desc() -> rg::LayoutDesc;
+}
+
+/// `u16` | `u32`
+pub trait Index: Copy + Pod + private::Sealed {
+    const INDEX_TYPE: IndexType;
+}
+
+impl Index for u16 {
+    const INDEX_TYPE: IndexType = IndexType::UInt16;
+}
+
+impl Index for u32 {
+    const INDEX_TYPE: IndexType = IndexType::UInt32;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+}
+
+/// Error on writing to a [`VertexBuffer`] or an [`IndexBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferError {
+    /// Violation of the once-per-frame rule
+    Update(UpdateError),
+    /// The data (or the appended data in this frame) doesn't fit in the buffer. `sokol_gfx.h`
+    /// would drop the data and the following draw calls
+    Overflow,
+}
+
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Update(err) => fmt::Display::fmt(err, f),
+            Self::Overflow => f.write_str("data exceeds the buffer capacity"),
+        }
+    }
+}
+
+impl Error for BufferError {}
+
+impl From<UpdateError> for BufferError {
+    fn from(err: UpdateError) -> Self {
+        Self::Update(err)
+    }
+}
+
+/// Views plain data as bytes
+pub(crate) fn as_bytes<T: Pod>(items: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts(items.as_ptr() as *const u8, size_of_val(items)) }
+}
+
+/// [`Buffer`] of `T` elements
+#[derive(Debug)]
+struct TypedBuffer<T: Pod> {
+    buf: Buffer,
+    usage: ResourceUsage,
+    /// Number of elements written by the creation or the last update, or appended in the frame of
+    /// the last append
+    len: usize,
+    /// Maximum number of elements
+    capacity: usize,
+    /// Frame of the last append
+    append_frame: u64,
+    /// Bytes appended in `append_frame` (each append is aligned to 4 bytes by `sokol_gfx.h`)
+    appended: usize,
+    _ty: PhantomData<T>,
+}
+
+impl<T: Pod> TypedBuffer<T> {
+    fn immutable(ty: BufferType, items: &[T], label: &str) -> Self {
+        let data = self::as_bytes(items);
+        let desc = unsafe {
+            rg::buf_desc(
+                data.as_ptr() as *const _,
+                data.len(),
+                ty,
+                ResourceUsage::Immutable,
+                label,
+            )
+        };
+
+        Self {
+            buf: Buffer::create(&desc),
+            usage: ResourceUsage::Immutable,
+            len: items.len(),
+            capacity: items.len(),
+            append_frame: 0,
+            appended: 0,
+            _ty: PhantomData,
+        }
+    }
+
+    fn dynamic(ty: BufferType, capacity: usize, usage: ResourceUsage, label: &str) -> Self {
+        assert!(
+            usage == ResourceUsage::Dynamic || usage == ResourceUsage::Stream,
+            "dynamic buffer requires `Dynamic` or `Stream` usage"
+        );
+
+        let desc = unsafe {
+            rg::buf_desc(
+                std::ptr::null(),
+                size_of::<T>() * capacity,
+                ty,
+                usage,
+                label,
+            )
+        };
+
+        Self {
+            buf: Buffer::create(&desc),
+            usage,
+            len: 0,
+            capacity,
+            append_frame: 0,
+            appended: 0,
+            _ty: PhantomData,
+        }
+    }
+
+    fn update(&mut self, items: &[T]) -> Result<(), BufferError> {
+        let buf = self.buf;
+        self.update_with(items, |bytes| update::update_buffer(buf, bytes))
+    }
+
+    /// Checks the capacity and updates the buffer with `update`
+    fn update_with(
+        &mut self,
+        items: &[T],
+        update: impl FnOnce(&[u8]) -> Result<(), UpdateError>,
+    ) -> Result<(), BufferError> {
+        assert!(
+            self.usage != ResourceUsage::Immutable,
+            "tried to update immutable buffer"
+        );
+
+        if items.len() > self.capacity {
+            return Err(BufferError::Overflow);
+        }
+
+        update(self::as_bytes(items))?;
+        self.len = items.len();
+        Ok(())
+    }
+
+    fn append(&mut self, items: &[T]) -> Result<i32, BufferError> {
+        let buf = self.buf;
+        self.append_with(items, update::frame(), |bytes| {
+            let offset = update::append_buffer(buf, bytes)?;
+            if unsafe { ffi::sg_query_buffer_overflow(buf) } {
+                return Err(BufferError::Overflow);
+            }
+            Ok(offset)
+        })
+    }
+
+    /// Checks the capacity in the `frame` and appends to the buffer with `append`
+    fn append_with(
+        &mut self,
+        items: &[T],
+        frame: u64,
+        append: impl FnOnce(&[u8]) -> Result<i32, BufferError>,
+    ) -> Result<i32, BufferError> {
+        assert!(
+            self.usage != ResourceUsage::Immutable,
+            "tried to append to immutable buffer"
+        );
+
+        // `sokol_gfx.h` restarts appending every frame
+        if frame != self.append_frame {
+            self.append_frame = frame;
+            self.appended = 0;
+            self.len = 0;
+        }
+
+        let bytes = self::as_bytes(items);
+        if self.appended + bytes.len() > size_of::<T>() * self.capacity {
+            return Err(BufferError::Overflow);
+        }
+
+        let offset = append(bytes)?;
+
+        self.appended += (bytes.len() + 3) & !3;
+        self.len += items.len();
+        Ok(offset)
+    }
+}
+
+impl<T: Pod> Drop for TypedBuffer<T> {
+    fn drop(&mut self) {
+        if unsafe { ffi::sg_isvalid() } {
+            Buffer::destroy(self.buf);
+        }
+    }
+}
+
+/// Owned vertex [`Buffer`] of `V` (destroyed on drop)
+#[derive(Debug)]
+pub struct VertexBuffer<V: VertexLayout> {
+    inner: TypedBuffer<V>,
+}
+
+impl<V: VertexLayout> VertexBuffer<V> {
+    pub fn immutable(verts: &[V], label: &str) -> Self {
+        Self {
+            inner: TypedBuffer::immutable(BufferType::Vertex, verts, label),
+        }
+    }
+
+    /// Creates a buffer of [`ResourceUsage::Dynamic`] or [`ResourceUsage::Stream`] that can
+    /// contain `capacity` vertices
+    pub fn dynamic(capacity: usize, usage: ResourceUsage, label: &str) -> Self {
+        Self {
+            inner: TypedBuffer::dynamic(BufferType::Vertex, capacity, usage, label),
+        }
+    }
+
+    pub fn buffer(&self) -> Buffer {
+        self.inner.buf
+    }
+
+    /// Number of vertices written by the creation or the last update, or appended in the frame of
+    /// the last append
+    pub fn len(&self) -> usize {
+        self.inner.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.len == 0
+    }
+
+    /// Maximum number of vertices
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Vertex layout of `V`
+    pub fn layout_desc() -> rg::LayoutDesc {
+        V::layout_desc()
+    }
+
+    /// Sets the vertex layout of `V` to a [`PipelineDesc`]
+    pub fn set_layout(desc: &mut PipelineDesc) {
+        desc.layout = V::layout_desc();
+    }
+
+    /// Overwrites the buffer. Only one update is allowed per frame ([`update`]).
+    pub fn update(&mut self, verts: &[V]) -> Result<(), BufferError> {
+        self.inner.update(verts)
+    }
+
+    /// Appends vertices and returns the byte offset to them (for
+    /// [`Bindings::vertex_buffer_offsets`]). Can be called multiple times per frame until the
+    /// appended vertices exceed the capacity.
+    pub fn append(&mut self, verts: &[V]) -> Result<i32, BufferError> {
+        self.inner.append(verts)
+    }
+
+    /// Sets the buffer to a vertex buffer slot with zero offset
+    pub fn set_to(&self, bind: &mut Bindings, slot: usize) {
+        bind.vertex_buffers[slot] = self.inner.buf;
+        bind.vertex_buffer_offsets[slot] = 0;
+    }
+}
+
+/// Owned index [`Buffer`] of `u16` or `u32` (destroyed on drop)
+#[derive(Debug)]
+pub struct IndexBuffer<I: Index> {
+    inner: TypedBuffer<I>,
+}
+
+impl<I: Index> IndexBuffer<I> {
+    pub fn immutable(indices: &[I], label: &str) -> Self {
+        Self {
+            inner: TypedBuffer::immutable(BufferType::Index, indices, label),
+        }
+    }
+
+    /// Creates a buffer of [`ResourceUsage::Dynamic`] or [`ResourceUsage::Stream`] that can
+    /// contain `capacity` indices
+    pub fn dynamic(capacity: usize, usage: ResourceUsage, label: &str) -> Self {
+        Self {
+            inner: TypedBuffer::dynamic(BufferType::Index, capacity, usage, label),
+        }
+    }
+
+    pub fn buffer(&self) -> Buffer {
+        self.inner.buf
+    }
+
+    /// Number of indices written by the creation or the last update, or appended in the frame of
+    /// the last append
+    pub fn len(&self) -> usize {
+        self.inner.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.len == 0
+    }
+
+    /// Maximum number of indices
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    pub fn index_type() -> IndexType {
+        I::INDEX_TYPE
+    }
+
+    /// Sets the index type of `I` to a [`PipelineDesc`]
+    pub fn set_index_type(desc: &mut PipelineDesc) {
+        desc.index_type = I::INDEX_TYPE.to_ffi();
+    }
+
+    /// Overwrites the buffer. Only one update is allowed per frame ([`update`]).
+    pub fn update(&mut self, indices: &[I]) -> Result<(), BufferError> {
+        self.inner.update(indices)
+    }
+
+    /// Appends indices and returns the byte offset to them (for
+    /// [`Bindings::index_buffer_offset`]). Can be called multiple times per frame until the
+    /// appended indices exceed the capacity.
+    pub fn append(&mut self, indices: &[I]) -> Result<i32, BufferError> {
+        self.inner.append(indices)
+    }
+
+    /// Sets the buffer to the index buffer slot with zero offset
+    pub fn set_to(&self, bind: &mut Bindings) {
+        bind.index_buffer = self.inner.buf;
+        bind.index_buffer_offset = 0;
+    }
+
+    /// Draws [`len`](Self::len) indices from the start of the buffer
+    ///
+    /// Appended chunks are aligned to 4 bytes, so after appending odd numbers of `u16` indices,
+    /// bind the offsets of [`append`](Self::append) and draw each chunk instead.
+    pub fn draw(&self, n_instances: u32) {
+        rg::draw(0, self.inner.len as u32, n_instances);
+    }
+}
+
+/// [Non-Sokol] Helper for creating a [`PipelineDesc`] with the vertex layout of `V` and the index
+/// type of `I`
+pub fn pipeline_desc<V: VertexLayout, I: Index>(shader: rg::Shader) -> PipelineDesc {
+    PipelineDesc {
+        shader,
+        layout: V::layout_desc(),
+        index_type: I::INDEX_TYPE.to_ffi(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn dynamic<T: Pod>(capacity: usize) -> TypedBuffer<T> {
+        TypedBuffer {
+            buf: Buffer { id: 1 },
+            usage: ResourceUsage::Stream,
+            len: 0,
+            capacity,
+            append_frame: 0,
+            appended: 0,
+            _ty: PhantomData,
+        }
+    }
+
+    #[test]
+    fn append_per_frame() {
+        let mut buf = dynamic::<u16>(8);
+        // offsets that `sokol_gfx.h` would return
+        let pos = Cell::new(0);
+        let append = |bytes: &[u8]| -> Result<i32, BufferError> {
+            let offset = pos.get();
+            pos.set(offset + ((bytes.len() as i32 + 3) & !3));
+            Ok(offset)
+        };
+
+        assert_eq!(buf.append_with(&[0; 3], 1, &append), Ok(0));
+        // aligned to 4 bytes
+        assert_eq!(buf.appended, 8);
+        assert_eq!(buf.append_with(&[0; 4], 1, &append), Ok(8));
+        assert_eq!(buf.len, 7);
+
+        // 16 bytes are used, so even one index doesn't fit
+        assert_eq!(
+            buf.append_with(&[0; 1], 1, |_| unreachable!()),
+            Err(BufferError::Overflow)
+        );
+        assert_eq!(buf.len, 7);
+
+        // reset on a new frame
+        pos.set(0);
+        assert_eq!(buf.append_with(&[0; 8], 2, &append), Ok(0));
+        assert_eq!((buf.appended, buf.len), (16, 8));
+
+        // the backend can refuse, too
+        assert_eq!(
+            buf.append_with(&[], 2, |_| Err(UpdateError::AppendAfterUpdate.into())),
+            Err(BufferError::Update(UpdateError::AppendAfterUpdate))
+        );
+        assert_eq!((buf.appended, buf.len), (16, 8));
+    }
+
+    #[test]
+    fn update_capacity() {
+        let mut buf = dynamic::<u32>(2);
+        assert_eq!(
+            buf.update_with(&[1, 2], |bytes| {
+                assert_eq!(bytes.len(), 8);
+                Ok(())
+            }),
+            Ok(())
+        );
+        assert_eq!(buf.len, 2);
+
+        assert_eq!(
+            buf.update_with(&[1, 2, 3], |_| unreachable!()),
+            Err(BufferError::Overflow)
+        );
+        assert_eq!(
+            buf.update_with(&[1], |_| Err(UpdateError::UpdatedTwice)),
+            Err(BufferError::Update(UpdateError::UpdatedTwice))
+        );
+        assert_eq!(buf.len, 2);
+    }
+}
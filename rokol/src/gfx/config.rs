@@ -0,0 +1,278 @@
+/*!
+Typed [`SetupDesc`] without the context
+*/
+
+use std::{error::Error, fmt};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::gfx::{SetupContextDesc, SetupDesc};
+
+/// `_SG_MAX_POOL_SIZE` in `sokol_gfx.h` (the first slot is reserved)
+pub const MAX_POOL_SIZE: u32 = (1 << 16) - 1;
+
+/// Parameters of [`SetupDesc`] except the context. Defaults are the same as `sokol_gfx.h`.
+///
+/// ```no_run
+/// use rokol::gfx::GfxConfig;
+///
+/// let config = GfxConfig::default()
+///     .buffer_pool_size(256)
+///     .uniform_buffer_size(8 * 1024 * 1024);
+///
+/// # #[cfg(feature = "impl-app")] {
+/// let desc = config.to_desc(rokol::glue::sapp::context_desc()).unwrap();
+/// rokol::gfx::setup(&desc);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GfxConfig {
+    pub buffer_pool_size: u32,
+    pub image_pool_size: u32,
+    pub shader_pool_size: u32,
+    pub pipeline_pool_size: u32,
+    pub pass_pool_size: u32,
+    pub context_pool_size: u32,
+    /// Size of per-frame uniform buffer in bytes (Metal, D3D11)
+    pub uniform_buffer_size: u32,
+    /// Size of per-frame staging buffer in bytes (WebGPU)
+    pub staging_buffer_size: u32,
+    /// Number of cached samplers (Metal, D3D11)
+    pub sampler_cache_size: u32,
+}
+
+impl Default for GfxConfig {
+    fn default() -> Self {
+        Self {
+            buffer_pool_size: 128,
+            image_pool_size: 128,
+            shader_pool_size: 32,
+            pipeline_pool_size: 64,
+            pass_pool_size: 16,
+            context_pool_size: 16,
+            uniform_buffer_size: 4 * 1024 * 1024,
+            staging_buffer_size: 8 * 1024 * 1024,
+            sampler_cache_size: 64,
+        }
+    }
+}
+
+/// Invalid value in [`GfxConfig`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GfxConfigError {
+    /// Pool size has to be in `1..=MAX_POOL_SIZE`
+    PoolSize { field: &'static str, size: u32 },
+    /// Buffer or cache size has to be in `1..=i32::MAX`
+    Size { field: &'static str, size: u32 },
+}
+
+impl fmt::Display for GfxConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PoolSize { field, size } => write!(
+                f,
+                "`{}` has to be in 1..={}, given {}",
+                field, MAX_POOL_SIZE, size
+            ),
+            Self::Size { field, size } => write!(
+                f,
+                "`{}` has to be in 1..={}, given {}",
+                field,
+                i32::MAX,
+                size
+            ),
+        }
+    }
+}
+
+impl Error for GfxConfigError {}
+
+impl GfxConfig {
+    pub fn buffer_pool_size(mut self, size: u32) -> Self {
+        self.buffer_pool_size = size;
+        self
+    }
+
+    pub fn image_pool_size(mut self, size: u32) -> Self {
+        self.image_pool_size = size;
+        self
+    }
+
+    pub fn shader_pool_size(mut self, size: u32) -> Self {
+        self.shader_pool_size = size;
+        self
+    }
+
+    pub fn pipeline_pool_size(mut self, size: u32) -> Self {
+        self.pipeline_pool_size = size;
+        self
+    }
+
+    pub fn pass_pool_size(mut self, size: u32) -> Self {
+        self.pass_pool_size = size;
+        self
+    }
+
+    pub fn context_pool_size(mut self, size: u32) -> Self {
+        self.context_pool_size = size;
+        self
+    }
+
+    pub fn uniform_buffer_size(mut self, size: u32) -> Self {
+        self.uniform_buffer_size = size;
+        self
+    }
+
+    pub fn staging_buffer_size(mut self, size: u32) -> Self {
+        self.staging_buffer_size = size;
+        self
+    }
+
+    pub fn sampler_cache_size(mut self, size: u32) -> Self {
+        self.sampler_cache_size = size;
+        self
+    }
+
+    /// Checks the values so that `sg_setup` doesn't fail on assertion
+    pub fn validate(&self) -> Result<(), GfxConfigError> {
+        let pools = [
+            ("buffer_pool_size", self.buffer_pool_size),
+            ("image_pool_size", self.image_pool_size),
+            ("shader_pool_size", self.shader_pool_size),
+            ("pipeline_pool_size", self.pipeline_pool_size),
+            ("pass_pool_size", self.pass_pool_size),
+            ("context_pool_size", self.context_pool_size),
+        ];
+
+        for &(field, size) in &pools {
+            if size == 0 || size > MAX_POOL_SIZE {
+                return Err(GfxConfigError::PoolSize { field, size });
+            }
+        }
+
+        let sizes = [
+            ("uniform_buffer_size", self.uniform_buffer_size),
+            ("staging_buffer_size", self.staging_buffer_size),
+            ("sampler_cache_size", self.sampler_cache_size),
+        ];
+
+        for &(field, size) in &sizes {
+            if size == 0 || size > i32::MAX as u32 {
+                return Err(GfxConfigError::Size { field, size });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates the config and merges it with the context
+    pub fn to_desc(&self, context: SetupContextDesc) -> Result<SetupDesc, GfxConfigError> {
+        self.validate()?;
+
+        Ok(SetupDesc {
+            buffer_pool_size: self.buffer_pool_size as i32,
+            image_pool_size: self.image_pool_size as i32,
+            shader_pool_size: self.shader_pool_size as i32,
+            pipeline_pool_size: self.pipeline_pool_size as i32,
+            pass_pool_size: self.pass_pool_size as i32,
+            context_pool_size: self.context_pool_size as i32,
+            uniform_buffer_size: self.uniform_buffer_size as i32,
+            staging_buffer_size: self.staging_buffer_size as i32,
+            sampler_cache_size: self.sampler_cache_size as i32,
+            context,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        assert_eq!(GfxConfig::default().validate(), Ok(()));
+
+        assert_eq!(
+            GfxConfig::default().shader_pool_size(0).validate(),
+            Err(GfxConfigError::PoolSize {
+                field: "shader_pool_size",
+                size: 0,
+            })
+        );
+        assert_eq!(
+            GfxConfig::default()
+                .pass_pool_size(MAX_POOL_SIZE + 1)
+                .validate(),
+            Err(GfxConfigError::PoolSize {
+                field: "pass_pool_size",
+                size: MAX_POOL_SIZE + 1,
+            })
+        );
+        assert!(GfxConfig::default()
+            .image_pool_size(MAX_POOL_SIZE)
+            .validate()
+            .is_ok());
+
+        assert_eq!(
+            GfxConfig::default().sampler_cache_size(0).validate(),
+            Err(GfxConfigError::Size {
+                field: "sampler_cache_size",
+                size: 0,
+            })
+        );
+        assert_eq!(
+            GfxConfig::default()
+                .uniform_buffer_size(i32::MAX as u32 + 1)
+                .validate(),
+            Err(GfxConfigError::Size {
+                field: "uniform_buffer_size",
+                size: i32::MAX as u32 + 1,
+            })
+        );
+    }
+
+    #[test]
+    fn to_desc() {
+        let config = GfxConfig::default()
+            .buffer_pool_size(1)
+            .image_pool_size(2)
+            .shader_pool_size(3)
+            .pipeline_pool_size(4)
+            .pass_pool_size(5)
+            .context_pool_size(6)
+            .uniform_buffer_size(7)
+            .staging_buffer_size(8)
+            .sampler_cache_size(9);
+
+        let context = SetupContextDesc {
+            sample_count: 4,
+            ..Default::default()
+        };
+        let desc = config.to_desc(context).unwrap();
+
+        assert_eq!(
+            [
+                desc.buffer_pool_size,
+                desc.image_pool_size,
+                desc.shader_pool_size,
+                desc.pipeline_pool_size,
+                desc.pass_pool_size,
+                desc.context_pool_size,
+                desc.uniform_buffer_size,
+                desc.staging_buffer_size,
+                desc.sampler_cache_size,
+            ],
+            [1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        assert_eq!(desc.context.sample_count, 4);
+
+        assert!(config
+            .shader_pool_size(0)
+            .to_desc(Default::default())
+            .is_err());
+    }
+}
@@ -0,0 +1,520 @@
+/*!
+Asynchronous resource loading via `alloc` / `init` split of [`BakedResource`]
+
+The render thread allocates resource IDs immediately, worker threads decode data and
+[`Loader::pump`] initializes the resources on the render thread (or marks them as failed):
+
+```no_run
+use rokol::gfx::{self as rg, loader::{self, DecodedImage, Loader}};
+
+let mut loader = Loader::new(2);
+
+// the ID is available right now (unless the image pool is exhausted)
+let img = loader.load_image(rg::ImageDesc::default(), || {
+    let pixels = std::fs::read("assets/a.rgba")?;
+    Ok(DecodedImage {
+        w: 64,
+        h: 64,
+        format: rg::PixelFormat::Rgba8,
+        pixels,
+    })
+}).unwrap();
+
+// every frame:
+loader.pump();
+let placeholder = rg::Image::default();
+let img_to_bind = loader::resolve_image(img, placeholder);
+```
+
+Descriptors are kept on the render thread, so [`crate::gfx::intern_label`] labels or `'static`
+pointers are fine. A panic in a decode function fails the resource.
+*/
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{BakedResource, Buffer, BufferDesc, Image, ImageDesc, PixelFormat, Range};
+
+/// Error returned by decode functions
+pub type DecodeError = Box<dyn Error + Send + Sync>;
+
+/// Error when the resource pool is exhausted (`alloc` returned `SG_INVALID_ID`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError {
+    /// `"image"` or `"buffer"`
+    pub kind: &'static str,
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} pool is exhausted", self.kind)
+    }
+}
+
+impl Error for AllocError {}
+
+/// Allocates an [`Image`], failing on `SG_INVALID_ID`
+pub(crate) fn alloc_image() -> Result<Image, AllocError> {
+    let img = Image::alloc();
+    if img.id == ffi::SG_INVALID_ID as u32 {
+        Err(AllocError { kind: "image" })
+    } else {
+        Ok(img)
+    }
+}
+
+/// Allocates a [`Buffer`], failing on `SG_INVALID_ID`
+pub(crate) fn alloc_buffer() -> Result<Buffer, AllocError> {
+    let buf = Buffer::alloc();
+    if buf.id == ffi::SG_INVALID_ID as u32 {
+        Err(AllocError { kind: "buffer" })
+    } else {
+        Ok(buf)
+    }
+}
+
+/// Pixels decoded on a worker thread
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedImage {
+    pub w: u32,
+    pub h: u32,
+    pub format: PixelFormat,
+    /// Pixels of the top-level mipmap
+    pub pixels: Vec<u8>,
+}
+
+/// Buffer data decoded on a worker thread
+pub type DecodedBuffer = Vec<u8>;
+
+enum Decoded {
+    Image(DecodedImage),
+    Buffer(DecodedBuffer),
+}
+
+/// Resource ID waiting for decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PendingId {
+    Image(u32),
+    Buffer(u32),
+}
+
+/// Descriptor template kept on the render thread
+enum Template {
+    Image(Box<ImageDesc>),
+    Buffer(BufferDesc),
+}
+
+type Job = Box<dyn FnOnce() -> Result<Decoded, DecodeError> + Send + 'static>;
+
+/// Runs a job, turning a panic into an error
+fn run(job: Job) -> Result<Decoded, DecodeError> {
+    panic::catch_unwind(AssertUnwindSafe(job)).unwrap_or_else(|_| Err("decode panicked".into()))
+}
+
+/// Result of [`Loader::pump`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PumpResult {
+    /// Number of resources initialized in the pump
+    pub n_loaded: usize,
+    /// Number of resources failed in the pump
+    pub n_failed: usize,
+}
+
+/// Worker pool that decodes resource data
+///
+/// Resources still loading are deallocated on drop.
+pub struct Loader {
+    workers: Vec<JoinHandle<()>>,
+    job_tx: Option<mpsc::Sender<(PendingId, Job)>>,
+    result_rx: mpsc::Receiver<(PendingId, Result<Decoded, DecodeError>)>,
+    result_tx: mpsc::Sender<(PendingId, Result<Decoded, DecodeError>)>,
+    pending: HashMap<PendingId, Template>,
+}
+
+impl fmt::Debug for Loader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Loader")
+            .field("n_workers", &self.workers.len())
+            .field("n_pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl Loader {
+    /// Spawns `n_workers` (at least one) worker threads
+    pub fn new(n_workers: usize) -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<(PendingId, Job)>();
+        let (result_tx, result_rx) = mpsc::channel();
+
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let workers = (0..n_workers.max(1))
+            .map(|i| {
+                let job_rx = Arc::clone(&job_rx);
+                let result_tx = result_tx.clone();
+                thread::Builder::new()
+                    .name(format!("rokol-loader-{}", i))
+                    .spawn(move || loop {
+                        let (id, job) = {
+                            let rx = job_rx.lock().unwrap_or_else(|err| err.into_inner());
+                            match rx.recv() {
+                                Ok(x) => x,
+                                // the loader is dropped
+                                Err(_) => break,
+                            }
+                        };
+                        if result_tx.send((id, self::run(job))).is_err() {
+                            break;
+                        }
+                    })
+                    .expect("Unable to spawn loader thread")
+            })
+            .collect();
+
+        Self {
+            workers,
+            job_tx: Some(job_tx),
+            result_rx,
+            result_tx,
+            pending: HashMap::new(),
+        }
+    }
+
+    fn spawn(&mut self, id: PendingId, template: Template, job: Job) {
+        self.pending.insert(id, template);
+
+        let tx = self.job_tx.as_ref().unwrap();
+        if let Err(mpsc::SendError((id, job))) = tx.send((id, job)) {
+            // workers are gone; decode on this thread
+            self.result_tx.send((id, self::run(job))).ok();
+        }
+    }
+
+    /// Allocates an [`Image`] and decodes the pixels on a worker thread. Size, pixel format and
+    /// data of the `template` are overwritten with the decoded image.
+    pub fn load_image(
+        &mut self,
+        template: ImageDesc,
+        decode: impl FnOnce() -> Result<DecodedImage, DecodeError> + Send + 'static,
+    ) -> Result<Image, AllocError> {
+        let img = self::alloc_image()?;
+        self.spawn(
+            PendingId::Image(img.id),
+            Template::Image(Box::new(template)),
+            Box::new(move || decode().map(Decoded::Image)),
+        );
+        Ok(img)
+    }
+
+    /// Allocates a [`Buffer`] and decodes the data on a worker thread. Size and data of the
+    /// `template` are overwritten with the decoded data.
+    pub fn load_buffer(
+        &mut self,
+        template: BufferDesc,
+        decode: impl FnOnce() -> Result<DecodedBuffer, DecodeError> + Send + 'static,
+    ) -> Result<Buffer, AllocError> {
+        let buf = self::alloc_buffer()?;
+        self.spawn(
+            PendingId::Buffer(buf.id),
+            Template::Buffer(template),
+            Box::new(move || decode().map(Decoded::Buffer)),
+        );
+        Ok(buf)
+    }
+
+    /// Number of resources still loading
+    pub fn n_pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Initializes decoded resources (or marks them as failed). Call it every frame on the
+    /// render thread.
+    pub fn pump(&mut self) -> PumpResult {
+        self.pump_with(|id, template, decoded| {
+            let res = decoded.and_then(|decoded| match (template, decoded) {
+                (Template::Image(desc), Decoded::Image(decoded)) => {
+                    self::init_image(Image { id: id.raw() }, *desc, &decoded)
+                }
+                (Template::Buffer(mut desc), Decoded::Buffer(data)) => {
+                    desc.size = data.len() as _;
+                    desc.data = Range {
+                        ptr: data.as_ptr() as *const _,
+                        size: data.len() as _,
+                    };
+                    Buffer::init(Buffer { id: id.raw() }, &desc);
+                    Ok(())
+                }
+                _ => unreachable!("decoded data doesn't match the resource type"),
+            });
+
+            match res {
+                Ok(()) => true,
+                Err(err) => {
+                    log::warn!("rokol: failed to load {:?}: {}", id, err);
+                    id.fail();
+                    false
+                }
+            }
+        })
+    }
+
+    /// Hands the finished jobs to `finish`, which returns if the resource is loaded
+    fn pump_with(
+        &mut self,
+        mut finish: impl FnMut(PendingId, Template, Result<Decoded, DecodeError>) -> bool,
+    ) -> PumpResult {
+        let mut res = PumpResult::default();
+
+        while let Ok((id, decoded)) = self.result_rx.try_recv() {
+            let template = match self.pending.remove(&id) {
+                Some(t) => t,
+                None => continue,
+            };
+
+            if finish(id, template, decoded) {
+                res.n_loaded += 1;
+            } else {
+                res.n_failed += 1;
+            }
+        }
+
+        res
+    }
+}
+
+impl PendingId {
+    fn raw(self) -> u32 {
+        match self {
+            Self::Image(id) | Self::Buffer(id) => id,
+        }
+    }
+
+    fn fail(self) {
+        unsafe {
+            match self {
+                Self::Image(id) => ffi::sg_fail_image(Image { id }),
+                Self::Buffer(id) => ffi::sg_fail_buffer(Buffer { id }),
+            }
+        }
+    }
+
+    fn dealloc(self) {
+        match self {
+            Self::Image(id) => Image::dealloc(Image { id }),
+            Self::Buffer(id) => Buffer::dealloc(Buffer { id }),
+        }
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        // stop the workers after finishing queued jobs
+        self.job_tx = None;
+        for worker in self.workers.drain(..) {
+            worker.join().ok();
+        }
+
+        if unsafe { ffi::sg_isvalid() } {
+            for (id, _) in self.pending.drain() {
+                id.dealloc();
+            }
+        }
+    }
+}
+
+/// Initializes an allocated [`Image`] with the decoded pixels. Size, pixel format and data of the
+/// `template` are overwritten.
+///
+/// Returns an error without touching the image if the pixels don't match the size and the
+/// format. Then the caller should mark the image as failed.
+pub(crate) fn init_image(
+    img: Image,
+    mut template: ImageDesc,
+    decoded: &DecodedImage,
+) -> Result<(), DecodeError> {
+    self::validate_image(decoded)?;
+
+    template.width = decoded.w as i32;
+    template.height = decoded.h as i32;
+    template.pixel_format = decoded.format.to_ffi();
+    template.data.subimage[0][0] = Range {
+        ptr: decoded.pixels.as_ptr() as *const _,
+        size: decoded.pixels.len() as _,
+    };
+    Image::init(img, &template);
+    Ok(())
+}
+
+/// Checks the decoded image before it reaches the validation layer of `sokol_gfx.h`, which aborts
+fn validate_image(decoded: &DecodedImage) -> Result<(), DecodeError> {
+    let (w, h) = (decoded.w as usize, decoded.h as usize);
+    if w == 0 || h == 0 || w > i32::MAX as usize || h > i32::MAX as usize {
+        return Err(format!("invalid image size: {}x{}", w, h).into());
+    }
+
+    let expected = self::surface_size(decoded.format, w, h)
+        .ok_or_else(|| format!("can't load pixels of {:?}", decoded.format))?;
+    if decoded.pixels.len() != expected {
+        return Err(format!(
+            "{} bytes of pixels for {}x{} {:?} image (expected {} bytes)",
+            decoded.pixels.len(),
+            w,
+            h,
+            decoded.format,
+            expected
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Byte size of the top-level mipmap (`_sg_surface_pitch` of `sokol_gfx.h`). Returns `None` for
+/// formats that can't have data or too big sizes.
+fn surface_size(format: PixelFormat, w: usize, h: usize) -> Option<usize> {
+    use PixelFormat as F;
+
+    // (block width and height, bytes per block)
+    let (block, block_bytes) = match format {
+        F::R8 | F::R8Sn | F::R8Ui | F::R8Si => (1, 1),
+        F::R16 | F::R16Sn | F::R16Ui | F::R16Si | F::R16F => (1, 2),
+        F::Rg8 | F::Rg8Sn | F::Rg8Ui | F::Rg8Si => (1, 2),
+        F::R32Ui | F::R32Si | F::R32F => (1, 4),
+        F::Rg16 | F::Rg16Sn | F::Rg16Ui | F::Rg16Si | F::Rg16F => (1, 4),
+        F::_Default | F::Rgba8 | F::Rgba8Sn | F::Rgba8Ui | F::Rgba8Si | F::Bgra8 => (1, 4),
+        F::Rgb10A2 | F::Rg11B10F => (1, 4),
+        F::Rg32Ui | F::Rg32Si | F::Rg32F => (1, 8),
+        F::Rgba16 | F::Rgba16Sn | F::Rgba16Ui | F::Rgba16Si | F::Rgba16F => (1, 8),
+        F::Rgba32Ui | F::Rgba32Si | F::Rgba32F => (1, 16),
+        F::Bc1Rgba | F::Bc4R | F::Bc4Rsc | F::Etc2Rgb8 | F::Etc2Rgb8A1 => (4, 8),
+        F::Bc2Rgba | F::Bc3Rgba | F::Bc5Rg | F::Bc5Rgsn | F::Bc6hRgf | F::Bc6hRgbuf => (4, 16),
+        F::Bc7Rgba | F::Etc2Rgba8 | F::Etc2Rg11 | F::Etc2Rg11Sn => (4, 16),
+        F::PvrtcRgb4Bpp | F::PvrtcRgba24pp | F::PvrtcRgb2Bpp | F::PvrtcRgba2Bpp => {
+            // 8 bytes per 4 pixels in a row, at least 2 of them
+            let row = (w / 4).max(2).checked_mul(8)?;
+            return row.checked_mul(h.div_ceil(4));
+        }
+        _ => return None,
+    };
+
+    let row = w.div_ceil(block).checked_mul(block_bytes)?;
+    row.checked_mul(h.div_ceil(block))
+}
+
+/// Returns `img` if it's loaded, `placeholder` otherwise
+pub fn resolve_image(img: Image, placeholder: Image) -> Image {
+    let state = unsafe { ffi::sg_query_image_state(img) };
+    if state == ffi::sg_resource_state::SG_RESOURCESTATE_VALID {
+        img
+    } else {
+        placeholder
+    }
+}
+
+/// Returns `buf` if it's loaded, `placeholder` otherwise
+pub fn resolve_buffer(buf: Buffer, placeholder: Buffer) -> Buffer {
+    let state = unsafe { ffi::sg_query_buffer_state(buf) };
+    if state == ffi::sg_resource_state::SG_RESOURCESTATE_VALID {
+        buf
+    } else {
+        placeholder
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn pump_finished_jobs() {
+        let mut loader = Loader::new(2);
+
+        let image = || {
+            Ok(Decoded::Image(DecodedImage {
+                w: 1,
+                h: 1,
+                format: PixelFormat::Rgba8,
+                pixels: vec![0; 4],
+            }))
+        };
+        loader.spawn(
+            PendingId::Image(1),
+            Template::Image(Default::default()),
+            Box::new(image),
+        );
+        loader.spawn(
+            PendingId::Buffer(2),
+            Template::Buffer(Default::default()),
+            Box::new(|| Err("missing file".into())),
+        );
+        loader.spawn(
+            PendingId::Image(3),
+            Template::Image(Default::default()),
+            Box::new(|| panic!("decoder bug")),
+        );
+        assert_eq!(loader.n_pending(), 3);
+
+        let mut total = PumpResult::default();
+        let mut finished = Vec::new();
+        let start = Instant::now();
+        while loader.n_pending() > 0 {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "jobs never finished"
+            );
+            let res = loader.pump_with(|id, _template, decoded| {
+                finished.push((id, decoded.is_ok()));
+                decoded.is_ok()
+            });
+            total.n_loaded += res.n_loaded;
+            total.n_failed += res.n_failed;
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        finished.sort_by_key(|(id, _)| id.raw());
+        assert_eq!(
+            finished,
+            vec![
+                (PendingId::Image(1), true),
+                (PendingId::Buffer(2), false),
+                (PendingId::Image(3), false),
+            ]
+        );
+        assert_eq!(
+            total,
+            PumpResult {
+                n_loaded: 1,
+                n_failed: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn validate_decoded_image() {
+        let image = |w, h, format, len| DecodedImage {
+            w,
+            h,
+            format,
+            pixels: vec![0; len],
+        };
+
+        assert!(validate_image(&image(2, 3, PixelFormat::Rgba8, 24)).is_ok());
+        assert!(validate_image(&image(2, 3, PixelFormat::R16F, 12)).is_ok());
+        // 2x1 blocks of 8 bytes
+        assert!(validate_image(&image(5, 3, PixelFormat::Bc1Rgba, 16)).is_ok());
+
+        assert!(validate_image(&image(2, 3, PixelFormat::Rgba8, 23)).is_err());
+        assert!(validate_image(&image(0, 3, PixelFormat::Rgba8, 0)).is_err());
+        assert!(validate_image(&image(u32::MAX, 1, PixelFormat::R8, 0)).is_err());
+        assert!(validate_image(&image(1, 1, PixelFormat::Depth, 4)).is_err());
+    }
+}
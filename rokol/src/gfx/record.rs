@@ -0,0 +1,1974 @@
+/*!
+Records `sokol_gfx.h` calls into a file and replays them
+
+# Recording
+
+[`install`] a [`Recorder`] right after [`crate::gfx::setup`]. It keeps track of living resources
+and captures every call of a frame on request:
+
+```no_run
+use rokol::gfx::record;
+
+let mut recorder = record::install();
+
+// on user request:
+recorder.with_mut(|r| r.start_capture());
+
+// .. render a frame and `commit` ..
+
+if let Some(capture) = recorder.with_mut(|r| r.take_capture()) {
+    capture.save("frame.rktrace").unwrap();
+}
+```
+
+A [`Capture`] starts with the creation commands of the resources alive at the start of the
+capture, followed by the calls of the frame until `commit`. Resources allocated with `alloc_*` are
+recorded with their `alloc`, `init` (as `Make*`) and `fail` calls, so frames of the
+[`loader`](crate::gfx::loader) replay the same way.
+
+If dynamic resources were updated before the capture, their last contents are re-uploaded right
+after the creation commands and committed as a setup frame. Start capturing before updating
+anything in the frame. Appended data is not restored, since `sokol_gfx.h` discards it every frame.
+
+# Replaying
+
+[`Capture::replay`] re-issues the commands to a [`Backend`]:
+
+* [`GfxBackend`] calls `rokol::gfx` (any graphics backend, including the `dummy` one)
+* `Vec<Command>` just collects the commands, which is handy for diffing call streams in tests
+
+# File format
+
+Descriptors are stored field by field without pointers, followed by the data they pointed to.
+Enums and booleans are validated on load, so a corrupted file is an error rather than an invalid
+descriptor. Resources injected from native handles (e.g. `gl_buffers`) can't be replayed.
+*/
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::{CStr, CString},
+    fmt, fs,
+    io::{self, Read, Write},
+    os::raw::c_char,
+    path::Path,
+};
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{
+    self as rg,
+    trace::{self, TraceHandle, Tracer},
+    update::OwnedImageData,
+    BakedResource, Bindings, Buffer, BufferDesc, Image, ImageData, ImageDesc, Pass, PassDesc,
+    Pipeline, PipelineDesc, Shader, ShaderDesc, ShaderStage,
+};
+
+mod fields;
+
+use self::fields::Field;
+
+/// Magic number of capture files
+pub const MAGIC: [u8; 4] = *b"RKTR";
+
+/// Version of the capture file format
+pub const VERSION: u32 = 3;
+
+/// Installs a [`Recorder`]
+pub fn install() -> TraceHandle<Recorder> {
+    trace::install(Recorder::new())
+}
+
+// --------------------------------------------------------------------------------
+// Commands
+
+/// Recorded `sokol_gfx.h` call
+///
+/// Resources are referred to by the IDs at recording time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    MakeBuffer {
+        id: u32,
+        desc: Box<OwnedBufferDesc>,
+    },
+    MakeImage {
+        id: u32,
+        desc: Box<OwnedImageDesc>,
+    },
+    MakeShader {
+        id: u32,
+        desc: Box<OwnedShaderDesc>,
+    },
+    MakePipeline {
+        id: u32,
+        desc: Box<OwnedPipelineDesc>,
+    },
+    MakePass {
+        id: u32,
+        desc: Box<OwnedPassDesc>,
+    },
+    DestroyBuffer(u32),
+    DestroyImage(u32),
+    DestroyShader(u32),
+    DestroyPipeline(u32),
+    DestroyPass(u32),
+    AllocBuffer(u32),
+    AllocImage(u32),
+    AllocShader(u32),
+    AllocPipeline(u32),
+    AllocPass(u32),
+    FailBuffer(u32),
+    FailImage(u32),
+    FailShader(u32),
+    FailPipeline(u32),
+    FailPass(u32),
+    UninitBuffer(u32),
+    UninitImage(u32),
+    UninitShader(u32),
+    UninitPipeline(u32),
+    UninitPass(u32),
+    DeallocBuffer(u32),
+    DeallocImage(u32),
+    DeallocShader(u32),
+    DeallocPipeline(u32),
+    DeallocPass(u32),
+    UpdateBuffer {
+        id: u32,
+        data: Vec<u8>,
+    },
+    AppendBuffer {
+        id: u32,
+        data: Vec<u8>,
+    },
+    UpdateImage {
+        id: u32,
+        data: OwnedImageData,
+    },
+    BeginDefaultPass {
+        action: ffi::sg_pass_action,
+        w: i32,
+        h: i32,
+    },
+    BeginPass {
+        id: u32,
+        action: ffi::sg_pass_action,
+    },
+    ApplyViewport {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        origin_top_left: bool,
+    },
+    ApplyScissorRect {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        origin_top_left: bool,
+    },
+    ApplyPipeline(u32),
+    ApplyBindings(Bindings),
+    ApplyUniforms {
+        stage: ShaderStage,
+        ub_index: i32,
+        data: Vec<u8>,
+    },
+    Draw {
+        base_elem: i32,
+        n_elems: i32,
+        n_instances: i32,
+    },
+    EndPass,
+    Commit,
+    PushDebugGroup(String),
+    PopDebugGroup,
+}
+
+impl Command {
+    /// Returns `(kind, id)` if it's a resource creation (or initialization) command. Failing an
+    /// allocated resource counts as initialization.
+    fn created(&self) -> Option<(u8, u32)> {
+        match self {
+            Self::MakeBuffer { id, .. } => Some((0, *id)),
+            Self::MakeImage { id, .. } => Some((1, *id)),
+            Self::MakeShader { id, .. } => Some((2, *id)),
+            Self::MakePipeline { id, .. } => Some((3, *id)),
+            Self::MakePass { id, .. } => Some((4, *id)),
+            Self::FailBuffer(id) => Some((0, *id)),
+            Self::FailImage(id) => Some((1, *id)),
+            Self::FailShader(id) => Some((2, *id)),
+            Self::FailPipeline(id) => Some((3, *id)),
+            Self::FailPass(id) => Some((4, *id)),
+            _ => None,
+        }
+    }
+
+    /// Returns `(kind, id)` if it's a resource allocation command
+    fn allocated(&self) -> Option<(u8, u32)> {
+        match self {
+            Self::AllocBuffer(id) => Some((0, *id)),
+            Self::AllocImage(id) => Some((1, *id)),
+            Self::AllocShader(id) => Some((2, *id)),
+            Self::AllocPipeline(id) => Some((3, *id)),
+            Self::AllocPass(id) => Some((4, *id)),
+            _ => None,
+        }
+    }
+
+    /// Returns `(kind, id)` if it's a resource uninitialization command
+    fn uninitialized(&self) -> Option<(u8, u32)> {
+        match self {
+            Self::UninitBuffer(id) => Some((0, *id)),
+            Self::UninitImage(id) => Some((1, *id)),
+            Self::UninitShader(id) => Some((2, *id)),
+            Self::UninitPipeline(id) => Some((3, *id)),
+            Self::UninitPass(id) => Some((4, *id)),
+            _ => None,
+        }
+    }
+
+    /// Returns `(kind, id)` if it's a resource destruction (or deallocation) command
+    fn destroyed(&self) -> Option<(u8, u32)> {
+        match self {
+            Self::DestroyBuffer(id) => Some((0, *id)),
+            Self::DestroyImage(id) => Some((1, *id)),
+            Self::DestroyShader(id) => Some((2, *id)),
+            Self::DestroyPipeline(id) => Some((3, *id)),
+            Self::DestroyPass(id) => Some((4, *id)),
+            Self::DeallocBuffer(id) => Some((0, *id)),
+            Self::DeallocImage(id) => Some((1, *id)),
+            Self::DeallocShader(id) => Some((2, *id)),
+            Self::DeallocPipeline(id) => Some((3, *id)),
+            Self::DeallocPass(id) => Some((4, *id)),
+            _ => None,
+        }
+    }
+}
+
+unsafe fn cstring(ptr: *const c_char) -> Option<CString> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_owned())
+    }
+}
+
+fn cstr_ptr(s: &Option<CString>) -> *const c_char {
+    s.as_ref().map(|s| s.as_ptr()).unwrap_or(std::ptr::null())
+}
+
+unsafe fn range_vec(range: &ffi::sg_range) -> Option<Vec<u8>> {
+    if range.ptr.is_null() {
+        None
+    } else {
+        Some(std::slice::from_raw_parts(range.ptr as *const u8, range.size as usize).to_vec())
+    }
+}
+
+fn vec_range(data: &Option<Vec<u8>>) -> ffi::sg_range {
+    match data {
+        Some(data) => ffi::sg_range {
+            ptr: data.as_ptr() as *const _,
+            size: data.len() as _,
+        },
+        None => ffi::sg_range::default(),
+    }
+}
+
+/// [`BufferDesc`] with owned data
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedBufferDesc {
+    /// Pointers and native handles are cleared
+    pub desc: BufferDesc,
+    pub data: Option<Vec<u8>>,
+    pub label: Option<CString>,
+}
+
+impl OwnedBufferDesc {
+    /// # Safety
+    ///
+    /// Pointers in the `desc` have to be valid
+    pub unsafe fn new(desc: &BufferDesc) -> Self {
+        let mut raw = *desc;
+        raw.data = Default::default();
+        raw.label = std::ptr::null();
+        raw.gl_buffers = [0; 2];
+        raw.mtl_buffers = [std::ptr::null(); 2];
+        raw.d3d11_buffer = std::ptr::null();
+        raw.wgpu_buffer = std::ptr::null();
+
+        Self {
+            desc: raw,
+            data: self::range_vec(&desc.data),
+            label: self::cstring(desc.label),
+        }
+    }
+
+    /// Descriptor that borrows data from `self`
+    pub fn to_desc(&self) -> BufferDesc {
+        BufferDesc {
+            data: self::vec_range(&self.data),
+            label: self::cstr_ptr(&self.label),
+            ..self.desc
+        }
+    }
+}
+
+/// [`ImageDesc`] with owned data
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedImageDesc {
+    /// Pointers and native handles are cleared
+    pub desc: ImageDesc,
+    pub data: OwnedImageData,
+    pub label: Option<CString>,
+}
+
+impl OwnedImageDesc {
+    /// # Safety
+    ///
+    /// Pointers in the `desc` have to be valid
+    pub unsafe fn new(desc: &ImageDesc) -> Self {
+        let mut raw = *desc;
+        raw.data = Default::default();
+        raw.label = std::ptr::null();
+        raw.gl_textures = [0; 2];
+        raw.gl_texture_target = 0;
+        raw.mtl_textures = [std::ptr::null(); 2];
+        raw.d3d11_texture = std::ptr::null();
+        raw.d3d11_shader_resource_view = std::ptr::null();
+        raw.wgpu_texture = std::ptr::null();
+
+        Self {
+            desc: raw,
+            data: OwnedImageData::new(&desc.data),
+            label: self::cstring(desc.label),
+        }
+    }
+
+    /// Descriptor that borrows data from `self`
+    pub fn to_desc(&self) -> ImageDesc {
+        ImageDesc {
+            data: self.data.to_data(),
+            label: self::cstr_ptr(&self.label),
+            ..self.desc
+        }
+    }
+}
+
+/// Visits every string pointer of a shader descriptor in a fixed order
+fn visit_shader_strings(desc: &mut ShaderDesc, mut f: impl FnMut(&mut *const c_char)) {
+    for attr in desc.attrs.iter_mut() {
+        f(&mut attr.name);
+        f(&mut attr.sem_name);
+    }
+
+    for stage in [&mut desc.vs, &mut desc.fs] {
+        f(&mut stage.source);
+        f(&mut stage.entry);
+        f(&mut stage.d3d11_target);
+        for ub in stage.uniform_blocks.iter_mut() {
+            for u in ub.uniforms.iter_mut() {
+                f(&mut u.name);
+            }
+        }
+        for img in stage.images.iter_mut() {
+            f(&mut img.name);
+        }
+    }
+
+    f(&mut desc.label);
+}
+
+/// [`ShaderDesc`] with owned strings and byte code
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedShaderDesc {
+    /// Pointers are cleared
+    pub desc: ShaderDesc,
+    /// Strings in the visiting order of `visit_shader_strings`
+    pub strings: Vec<Option<CString>>,
+    pub vs_bytecode: Option<Vec<u8>>,
+    pub fs_bytecode: Option<Vec<u8>>,
+}
+
+impl OwnedShaderDesc {
+    /// # Safety
+    ///
+    /// Pointers in the `desc` have to be valid
+    pub unsafe fn new(desc: &ShaderDesc) -> Self {
+        let mut raw = *desc;
+        raw.vs.bytecode = Default::default();
+        raw.fs.bytecode = Default::default();
+
+        let mut strings = Vec::new();
+        self::visit_shader_strings(&mut raw, |ptr| {
+            strings.push(self::cstring(*ptr));
+            *ptr = std::ptr::null();
+        });
+
+        Self {
+            desc: raw,
+            strings,
+            vs_bytecode: self::range_vec(&desc.vs.bytecode),
+            fs_bytecode: self::range_vec(&desc.fs.bytecode),
+        }
+    }
+
+    /// Descriptor that borrows data from `self`
+    pub fn to_desc(&self) -> ShaderDesc {
+        let mut desc = self.desc;
+        desc.vs.bytecode = self::vec_range(&self.vs_bytecode);
+        desc.fs.bytecode = self::vec_range(&self.fs_bytecode);
+
+        let mut strings = self.strings.iter();
+        self::visit_shader_strings(&mut desc, |ptr| {
+            *ptr = strings
+                .next()
+                .map(self::cstr_ptr)
+                .unwrap_or(std::ptr::null());
+        });
+
+        desc
+    }
+}
+
+/// [`PipelineDesc`] with owned label
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedPipelineDesc {
+    /// Label is cleared
+    pub desc: PipelineDesc,
+    pub label: Option<CString>,
+}
+
+impl OwnedPipelineDesc {
+    /// # Safety
+    ///
+    /// The label pointer has to be valid
+    pub unsafe fn new(desc: &PipelineDesc) -> Self {
+        Self {
+            desc: PipelineDesc {
+                label: std::ptr::null(),
+                ..*desc
+            },
+            label: self::cstring(desc.label),
+        }
+    }
+
+    /// Descriptor that borrows data from `self`
+    pub fn to_desc(&self) -> PipelineDesc {
+        PipelineDesc {
+            label: self::cstr_ptr(&self.label),
+            ..self.desc
+        }
+    }
+}
+
+/// [`PassDesc`] with owned label
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedPassDesc {
+    /// Label is cleared
+    pub desc: PassDesc,
+    pub label: Option<CString>,
+}
+
+impl OwnedPassDesc {
+    /// # Safety
+    ///
+    /// The label pointer has to be valid
+    pub unsafe fn new(desc: &PassDesc) -> Self {
+        Self {
+            desc: PassDesc {
+                label: std::ptr::null(),
+                ..*desc
+            },
+            label: self::cstring(desc.label),
+        }
+    }
+
+    /// Descriptor that borrows data from `self`
+    pub fn to_desc(&self) -> PassDesc {
+        PassDesc {
+            label: self::cstr_ptr(&self.label),
+            ..self.desc
+        }
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Recorder
+
+/// [`Tracer`] that captures `sokol_gfx.h` calls of a frame
+///
+/// The recorder keeps the creation data of living resources (e.g. pixels of immutable images) and
+/// the last contents of dynamic resources, so that they can be replayed.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    /// Allocation and creation commands of living resources in creation order
+    live: Vec<Command>,
+    /// Last contents of updated buffers
+    buffer_contents: BTreeMap<u32, Vec<u8>>,
+    /// Last contents of updated images
+    image_contents: BTreeMap<u32, OwnedImageData>,
+    /// Capture in progress
+    capturing: Option<Vec<Command>>,
+    /// Finished capture
+    capture: Option<Capture>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts capturing. The capture finishes on next `commit`
+    pub fn start_capture(&mut self) {
+        let mut commands = self.live.clone();
+
+        let n_creations = commands.len();
+        commands.extend(
+            self.buffer_contents
+                .iter()
+                .map(|(id, data)| Command::UpdateBuffer {
+                    id: *id,
+                    data: data.clone(),
+                }),
+        );
+        commands.extend(
+            self.image_contents
+                .iter()
+                .map(|(id, data)| Command::UpdateImage {
+                    id: *id,
+                    data: data.clone(),
+                }),
+        );
+        // resources can be updated only once per frame
+        if commands.len() > n_creations {
+            commands.push(Command::Commit);
+        }
+
+        self.capturing = Some(commands);
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.capturing.is_some()
+    }
+
+    /// Takes the last finished capture
+    pub fn take_capture(&mut self) -> Option<Capture> {
+        self.capture.take()
+    }
+
+    fn push(&mut self, cmd: Command) {
+        if let Some(capturing) = self.capturing.as_mut() {
+            capturing.push(cmd);
+        }
+    }
+
+    /// Pushes an allocation, creation or failure command
+    fn push_created(&mut self, cmd: Command) {
+        // failed allocation (exhausted pool) creates nothing
+        let alive = matches!(cmd.created().or(cmd.allocated()), Some((_, id)) if id != 0);
+        if alive {
+            self.live.push(cmd.clone());
+        }
+        self.push(cmd);
+    }
+
+    fn push_uninitialized(&mut self, cmd: Command) {
+        let target = cmd.uninitialized();
+        self.live.retain(|c| c.created() != target);
+        self.forget_contents(target);
+        self.push(cmd);
+    }
+
+    fn push_destroyed(&mut self, cmd: Command) {
+        let target = cmd.destroyed();
+        self.live
+            .retain(|c| c.created() != target && c.allocated() != target);
+        self.forget_contents(target);
+        self.push(cmd);
+    }
+
+    fn forget_contents(&mut self, target: Option<(u8, u32)>) {
+        match target {
+            Some((0, id)) => {
+                self.buffer_contents.remove(&id);
+            }
+            Some((1, id)) => {
+                self.image_contents.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Tracer for Recorder {
+    fn make_buffer(&mut self, desc: &BufferDesc, result: Buffer) {
+        self.init_buffer(result, desc);
+    }
+
+    fn make_image(&mut self, desc: &ImageDesc, result: Image) {
+        self.init_image(result, desc);
+    }
+
+    fn make_shader(&mut self, desc: &ShaderDesc, result: Shader) {
+        self.init_shader(result, desc);
+    }
+
+    fn make_pipeline(&mut self, desc: &PipelineDesc, result: Pipeline) {
+        self.init_pipeline(result, desc);
+    }
+
+    fn make_pass(&mut self, desc: &PassDesc, result: Pass) {
+        self.init_pass(result, desc);
+    }
+
+    fn init_buffer(&mut self, buf: Buffer, desc: &BufferDesc) {
+        let desc = Box::new(unsafe { OwnedBufferDesc::new(desc) });
+        self.push_created(Command::MakeBuffer { id: buf.id, desc });
+    }
+
+    fn init_image(&mut self, img: Image, desc: &ImageDesc) {
+        let desc = Box::new(unsafe { OwnedImageDesc::new(desc) });
+        self.push_created(Command::MakeImage { id: img.id, desc });
+    }
+
+    fn init_shader(&mut self, shd: Shader, desc: &ShaderDesc) {
+        let desc = Box::new(unsafe { OwnedShaderDesc::new(desc) });
+        self.push_created(Command::MakeShader { id: shd.id, desc });
+    }
+
+    fn init_pipeline(&mut self, pip: Pipeline, desc: &PipelineDesc) {
+        let desc = Box::new(unsafe { OwnedPipelineDesc::new(desc) });
+        self.push_created(Command::MakePipeline { id: pip.id, desc });
+    }
+
+    fn init_pass(&mut self, pass: Pass, desc: &PassDesc) {
+        let desc = Box::new(unsafe { OwnedPassDesc::new(desc) });
+        self.push_created(Command::MakePass { id: pass.id, desc });
+    }
+
+    fn destroy_buffer(&mut self, buf: Buffer) {
+        self.push_destroyed(Command::DestroyBuffer(buf.id));
+    }
+
+    fn destroy_image(&mut self, img: Image) {
+        self.push_destroyed(Command::DestroyImage(img.id));
+    }
+
+    fn destroy_shader(&mut self, shd: Shader) {
+        self.push_destroyed(Command::DestroyShader(shd.id));
+    }
+
+    fn destroy_pipeline(&mut self, pip: Pipeline) {
+        self.push_destroyed(Command::DestroyPipeline(pip.id));
+    }
+
+    fn destroy_pass(&mut self, pass: Pass) {
+        self.push_destroyed(Command::DestroyPass(pass.id));
+    }
+
+    fn uninit_buffer(&mut self, buf: Buffer) {
+        self.push_uninitialized(Command::UninitBuffer(buf.id));
+    }
+
+    fn uninit_image(&mut self, img: Image) {
+        self.push_uninitialized(Command::UninitImage(img.id));
+    }
+
+    fn uninit_shader(&mut self, shd: Shader) {
+        self.push_uninitialized(Command::UninitShader(shd.id));
+    }
+
+    fn uninit_pipeline(&mut self, pip: Pipeline) {
+        self.push_uninitialized(Command::UninitPipeline(pip.id));
+    }
+
+    fn uninit_pass(&mut self, pass: Pass) {
+        self.push_uninitialized(Command::UninitPass(pass.id));
+    }
+
+    fn alloc_buffer(&mut self, result: Buffer) {
+        self.push_created(Command::AllocBuffer(result.id));
+    }
+
+    fn alloc_image(&mut self, result: Image) {
+        self.push_created(Command::AllocImage(result.id));
+    }
+
+    fn alloc_shader(&mut self, result: Shader) {
+        self.push_created(Command::AllocShader(result.id));
+    }
+
+    fn alloc_pipeline(&mut self, result: Pipeline) {
+        self.push_created(Command::AllocPipeline(result.id));
+    }
+
+    fn alloc_pass(&mut self, result: Pass) {
+        self.push_created(Command::AllocPass(result.id));
+    }
+
+    fn dealloc_buffer(&mut self, buf: Buffer) {
+        self.push_destroyed(Command::DeallocBuffer(buf.id));
+    }
+
+    fn dealloc_image(&mut self, img: Image) {
+        self.push_destroyed(Command::DeallocImage(img.id));
+    }
+
+    fn dealloc_shader(&mut self, shd: Shader) {
+        self.push_destroyed(Command::DeallocShader(shd.id));
+    }
+
+    fn dealloc_pipeline(&mut self, pip: Pipeline) {
+        self.push_destroyed(Command::DeallocPipeline(pip.id));
+    }
+
+    fn dealloc_pass(&mut self, pass: Pass) {
+        self.push_destroyed(Command::DeallocPass(pass.id));
+    }
+
+    fn fail_buffer(&mut self, buf: Buffer) {
+        self.push_created(Command::FailBuffer(buf.id));
+    }
+
+    fn fail_image(&mut self, img: Image) {
+        self.push_created(Command::FailImage(img.id));
+    }
+
+    fn fail_shader(&mut self, shd: Shader) {
+        self.push_created(Command::FailShader(shd.id));
+    }
+
+    fn fail_pipeline(&mut self, pip: Pipeline) {
+        self.push_created(Command::FailPipeline(pip.id));
+    }
+
+    fn fail_pass(&mut self, pass: Pass) {
+        self.push_created(Command::FailPass(pass.id));
+    }
+
+    fn update_buffer(&mut self, buf: Buffer, data: &[u8]) {
+        self.buffer_contents.insert(buf.id, data.to_vec());
+        self.push(Command::UpdateBuffer {
+            id: buf.id,
+            data: data.to_vec(),
+        });
+    }
+
+    fn update_image(&mut self, img: Image, data: &ImageData) {
+        let data = unsafe { OwnedImageData::new(data) };
+        self.image_contents.insert(img.id, data.clone());
+        self.push(Command::UpdateImage { id: img.id, data });
+    }
+
+    fn append_buffer(&mut self, buf: Buffer, data: &[u8], _offset: i32) {
+        self.push(Command::AppendBuffer {
+            id: buf.id,
+            data: data.to_vec(),
+        });
+    }
+
+    fn begin_default_pass(&mut self, pa: &ffi::sg_pass_action, w: i32, h: i32) {
+        self.push(Command::BeginDefaultPass { action: *pa, w, h });
+    }
+
+    fn begin_pass(&mut self, pass: Pass, pa: &ffi::sg_pass_action) {
+        self.push(Command::BeginPass {
+            id: pass.id,
+            action: *pa,
+        });
+    }
+
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {
+        self.push(Command::ApplyViewport {
+            x,
+            y,
+            w,
+            h,
+            origin_top_left,
+        });
+    }
+
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {
+        self.push(Command::ApplyScissorRect {
+            x,
+            y,
+            w,
+            h,
+            origin_top_left,
+        });
+    }
+
+    fn apply_pipeline(&mut self, pip: Pipeline) {
+        self.push(Command::ApplyPipeline(pip.id));
+    }
+
+    fn apply_bindings(&mut self, bind: &Bindings) {
+        self.push(Command::ApplyBindings(*bind));
+    }
+
+    fn apply_uniforms(&mut self, stage: ShaderStage, ub_index: i32, data: &[u8]) {
+        self.push(Command::ApplyUniforms {
+            stage,
+            ub_index,
+            data: data.to_vec(),
+        });
+    }
+
+    fn draw(&mut self, base_elem: i32, n_elems: i32, n_instances: i32) {
+        self.push(Command::Draw {
+            base_elem,
+            n_elems,
+            n_instances,
+        });
+    }
+
+    fn end_pass(&mut self) {
+        self.push(Command::EndPass);
+    }
+
+    fn commit(&mut self) {
+        if let Some(mut commands) = self.capturing.take() {
+            commands.push(Command::Commit);
+            self.capture = Some(Capture { commands });
+        }
+    }
+
+    fn push_debug_group(&mut self, name: &str) {
+        self.push(Command::PushDebugGroup(name.to_string()));
+    }
+
+    fn pop_debug_group(&mut self) {
+        self.push(Command::PopDebugGroup);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Capture
+
+/// Error on reading or writing a capture file
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(io::Error),
+    /// Not a capture file
+    BadMagic,
+    UnsupportedVersion(u32),
+    Corrupted(&'static str),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "IO error on capture file: {}", err),
+            Self::BadMagic => write!(f, "not a rokol capture file"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported capture file version: {}", v),
+            Self::Corrupted(why) => write!(f, "corrupted capture file: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<io::Error> for CaptureError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Recorded commands of a frame
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Capture {
+    pub commands: Vec<Command>,
+}
+
+impl Capture {
+    pub fn new(commands: Vec<Command>) -> Self {
+        Self { commands }
+    }
+
+    /// Re-issues the commands to the backend
+    pub fn replay(&self, backend: &mut impl Backend) {
+        for cmd in &self.commands {
+            backend.issue(cmd);
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CaptureError> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CaptureError> {
+        let mut bytes = Vec::new();
+        fs::File::open(path)?.read_to_end(&mut bytes)?;
+        Self::read(&bytes)
+    }
+
+    pub fn write(&self, w: impl Write) -> Result<(), CaptureError> {
+        let mut w = Writer { w };
+
+        w.bytes_raw(&MAGIC)?;
+        w.u32(VERSION)?;
+
+        w.u32(self.commands.len() as u32)?;
+        for cmd in &self.commands {
+            w.command(cmd)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read(bytes: &[u8]) -> Result<Self, CaptureError> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        if r.take(4)? != MAGIC {
+            return Err(CaptureError::BadMagic);
+        }
+
+        let version = r.u32()?;
+        if version != VERSION {
+            return Err(CaptureError::UnsupportedVersion(version));
+        }
+
+        let len = r.u32()? as usize;
+        // commands can be encoded in a single byte, so the file size doesn't bound the allocation
+        // well; let the vector grow instead
+        let mut commands = Vec::new();
+        for _ in 0..len {
+            commands.push(r.command()?);
+        }
+
+        Ok(Self { commands })
+    }
+}
+
+mod tag {
+    pub const MAKE_BUFFER: u8 = 0;
+    pub const MAKE_IMAGE: u8 = 1;
+    pub const MAKE_SHADER: u8 = 2;
+    pub const MAKE_PIPELINE: u8 = 3;
+    pub const MAKE_PASS: u8 = 4;
+    pub const DESTROY_BUFFER: u8 = 5;
+    pub const DESTROY_IMAGE: u8 = 6;
+    pub const DESTROY_SHADER: u8 = 7;
+    pub const DESTROY_PIPELINE: u8 = 8;
+    pub const DESTROY_PASS: u8 = 9;
+    pub const UPDATE_BUFFER: u8 = 10;
+    pub const APPEND_BUFFER: u8 = 11;
+    pub const UPDATE_IMAGE: u8 = 12;
+    pub const BEGIN_DEFAULT_PASS: u8 = 13;
+    pub const BEGIN_PASS: u8 = 14;
+    pub const APPLY_VIEWPORT: u8 = 15;
+    pub const APPLY_SCISSOR_RECT: u8 = 16;
+    pub const APPLY_PIPELINE: u8 = 17;
+    pub const APPLY_BINDINGS: u8 = 18;
+    pub const APPLY_UNIFORMS: u8 = 19;
+    pub const DRAW: u8 = 20;
+    pub const END_PASS: u8 = 21;
+    pub const COMMIT: u8 = 22;
+    pub const ALLOC_BUFFER: u8 = 23;
+    pub const ALLOC_IMAGE: u8 = 24;
+    pub const ALLOC_SHADER: u8 = 25;
+    pub const ALLOC_PIPELINE: u8 = 26;
+    pub const ALLOC_PASS: u8 = 27;
+    pub const FAIL_BUFFER: u8 = 28;
+    pub const FAIL_IMAGE: u8 = 29;
+    pub const FAIL_SHADER: u8 = 30;
+    pub const FAIL_PIPELINE: u8 = 31;
+    pub const FAIL_PASS: u8 = 32;
+    pub const UNINIT_BUFFER: u8 = 33;
+    pub const UNINIT_IMAGE: u8 = 34;
+    pub const UNINIT_SHADER: u8 = 35;
+    pub const UNINIT_PIPELINE: u8 = 36;
+    pub const UNINIT_PASS: u8 = 37;
+    pub const DEALLOC_BUFFER: u8 = 38;
+    pub const DEALLOC_IMAGE: u8 = 39;
+    pub const DEALLOC_SHADER: u8 = 40;
+    pub const DEALLOC_PIPELINE: u8 = 41;
+    pub const DEALLOC_PASS: u8 = 42;
+    pub const PUSH_DEBUG_GROUP: u8 = 43;
+    pub const POP_DEBUG_GROUP: u8 = 44;
+}
+
+struct Writer<W: Write> {
+    w: W,
+}
+
+impl<W: Write> Writer<W> {
+    fn bytes_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.w.write_all(bytes)
+    }
+
+    fn u8(&mut self, x: u8) -> io::Result<()> {
+        self.w.write_all(&[x])
+    }
+
+    fn u32(&mut self, x: u32) -> io::Result<()> {
+        self.w.write_all(&x.to_le_bytes())
+    }
+
+    fn i32(&mut self, x: i32) -> io::Result<()> {
+        self.w.write_all(&x.to_le_bytes())
+    }
+
+    fn bool(&mut self, x: bool) -> io::Result<()> {
+        self.u8(x as u8)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.u32(bytes.len() as u32)?;
+        self.w.write_all(bytes)
+    }
+
+    fn opt_bytes(&mut self, bytes: Option<&[u8]>) -> io::Result<()> {
+        match bytes {
+            Some(bytes) => {
+                self.u8(1)?;
+                self.bytes(bytes)
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn opt_cstring(&mut self, s: &Option<CString>) -> io::Result<()> {
+        self.opt_bytes(s.as_ref().map(|s| s.as_bytes()))
+    }
+
+    fn u64(&mut self, x: u64) -> io::Result<()> {
+        self.w.write_all(&x.to_le_bytes())
+    }
+
+    fn f32(&mut self, x: f32) -> io::Result<()> {
+        self.w.write_all(&x.to_le_bytes())
+    }
+
+    /// Writes a descriptor field by field
+    fn field(&mut self, x: &impl Field) -> io::Result<()> {
+        x.write(self)
+    }
+
+    fn image_data(&mut self, data: &OwnedImageData) -> io::Result<()> {
+        self.u32(data.subimages.len() as u32)?;
+        for (face, mip, bytes) in &data.subimages {
+            self.u8(*face)?;
+            self.u8(*mip)?;
+            self.bytes(bytes)?;
+        }
+        Ok(())
+    }
+
+    fn rect(&mut self, x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) -> io::Result<()> {
+        self.i32(x)?;
+        self.i32(y)?;
+        self.i32(w)?;
+        self.i32(h)?;
+        self.bool(origin_top_left)
+    }
+
+    fn command(&mut self, cmd: &Command) -> io::Result<()> {
+        match cmd {
+            Command::MakeBuffer { id, desc } => {
+                self.u8(tag::MAKE_BUFFER)?;
+                self.u32(*id)?;
+                self.field(&desc.desc)?;
+                self.opt_bytes(desc.data.as_deref())?;
+                self.opt_cstring(&desc.label)
+            }
+            Command::MakeImage { id, desc } => {
+                self.u8(tag::MAKE_IMAGE)?;
+                self.u32(*id)?;
+                self.field(&desc.desc)?;
+                self.image_data(&desc.data)?;
+                self.opt_cstring(&desc.label)
+            }
+            Command::MakeShader { id, desc } => {
+                self.u8(tag::MAKE_SHADER)?;
+                self.u32(*id)?;
+                self.field(&desc.desc)?;
+                self.u32(desc.strings.len() as u32)?;
+                for s in &desc.strings {
+                    self.opt_cstring(s)?;
+                }
+                self.opt_bytes(desc.vs_bytecode.as_deref())?;
+                self.opt_bytes(desc.fs_bytecode.as_deref())
+            }
+            Command::MakePipeline { id, desc } => {
+                self.u8(tag::MAKE_PIPELINE)?;
+                self.u32(*id)?;
+                self.field(&desc.desc)?;
+                self.opt_cstring(&desc.label)
+            }
+            Command::MakePass { id, desc } => {
+                self.u8(tag::MAKE_PASS)?;
+                self.u32(*id)?;
+                self.field(&desc.desc)?;
+                self.opt_cstring(&desc.label)
+            }
+            Command::DestroyBuffer(id) => {
+                self.u8(tag::DESTROY_BUFFER)?;
+                self.u32(*id)
+            }
+            Command::DestroyImage(id) => {
+                self.u8(tag::DESTROY_IMAGE)?;
+                self.u32(*id)
+            }
+            Command::DestroyShader(id) => {
+                self.u8(tag::DESTROY_SHADER)?;
+                self.u32(*id)
+            }
+            Command::DestroyPipeline(id) => {
+                self.u8(tag::DESTROY_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::DestroyPass(id) => {
+                self.u8(tag::DESTROY_PASS)?;
+                self.u32(*id)
+            }
+            Command::UpdateBuffer { id, data } => {
+                self.u8(tag::UPDATE_BUFFER)?;
+                self.u32(*id)?;
+                self.bytes(data)
+            }
+            Command::AppendBuffer { id, data } => {
+                self.u8(tag::APPEND_BUFFER)?;
+                self.u32(*id)?;
+                self.bytes(data)
+            }
+            Command::UpdateImage { id, data } => {
+                self.u8(tag::UPDATE_IMAGE)?;
+                self.u32(*id)?;
+                self.image_data(data)
+            }
+            Command::BeginDefaultPass { action, w, h } => {
+                self.u8(tag::BEGIN_DEFAULT_PASS)?;
+                self.field(action)?;
+                self.i32(*w)?;
+                self.i32(*h)
+            }
+            Command::BeginPass { id, action } => {
+                self.u8(tag::BEGIN_PASS)?;
+                self.u32(*id)?;
+                self.field(action)
+            }
+            Command::ApplyViewport {
+                x,
+                y,
+                w,
+                h,
+                origin_top_left,
+            } => {
+                self.u8(tag::APPLY_VIEWPORT)?;
+                self.rect(*x, *y, *w, *h, *origin_top_left)
+            }
+            Command::ApplyScissorRect {
+                x,
+                y,
+                w,
+                h,
+                origin_top_left,
+            } => {
+                self.u8(tag::APPLY_SCISSOR_RECT)?;
+                self.rect(*x, *y, *w, *h, *origin_top_left)
+            }
+            Command::ApplyPipeline(id) => {
+                self.u8(tag::APPLY_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::ApplyBindings(bind) => {
+                self.u8(tag::APPLY_BINDINGS)?;
+                self.field(bind)
+            }
+            Command::ApplyUniforms {
+                stage,
+                ub_index,
+                data,
+            } => {
+                self.u8(tag::APPLY_UNIFORMS)?;
+                self.u32(*stage as u32)?;
+                self.i32(*ub_index)?;
+                self.bytes(data)
+            }
+            Command::Draw {
+                base_elem,
+                n_elems,
+                n_instances,
+            } => {
+                self.u8(tag::DRAW)?;
+                self.i32(*base_elem)?;
+                self.i32(*n_elems)?;
+                self.i32(*n_instances)
+            }
+            Command::EndPass => self.u8(tag::END_PASS),
+            Command::Commit => self.u8(tag::COMMIT),
+            Command::AllocBuffer(id) => {
+                self.u8(tag::ALLOC_BUFFER)?;
+                self.u32(*id)
+            }
+            Command::AllocImage(id) => {
+                self.u8(tag::ALLOC_IMAGE)?;
+                self.u32(*id)
+            }
+            Command::AllocShader(id) => {
+                self.u8(tag::ALLOC_SHADER)?;
+                self.u32(*id)
+            }
+            Command::AllocPipeline(id) => {
+                self.u8(tag::ALLOC_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::AllocPass(id) => {
+                self.u8(tag::ALLOC_PASS)?;
+                self.u32(*id)
+            }
+            Command::FailBuffer(id) => {
+                self.u8(tag::FAIL_BUFFER)?;
+                self.u32(*id)
+            }
+            Command::FailImage(id) => {
+                self.u8(tag::FAIL_IMAGE)?;
+                self.u32(*id)
+            }
+            Command::FailShader(id) => {
+                self.u8(tag::FAIL_SHADER)?;
+                self.u32(*id)
+            }
+            Command::FailPipeline(id) => {
+                self.u8(tag::FAIL_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::FailPass(id) => {
+                self.u8(tag::FAIL_PASS)?;
+                self.u32(*id)
+            }
+            Command::UninitBuffer(id) => {
+                self.u8(tag::UNINIT_BUFFER)?;
+                self.u32(*id)
+            }
+            Command::UninitImage(id) => {
+                self.u8(tag::UNINIT_IMAGE)?;
+                self.u32(*id)
+            }
+            Command::UninitShader(id) => {
+                self.u8(tag::UNINIT_SHADER)?;
+                self.u32(*id)
+            }
+            Command::UninitPipeline(id) => {
+                self.u8(tag::UNINIT_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::UninitPass(id) => {
+                self.u8(tag::UNINIT_PASS)?;
+                self.u32(*id)
+            }
+            Command::DeallocBuffer(id) => {
+                self.u8(tag::DEALLOC_BUFFER)?;
+                self.u32(*id)
+            }
+            Command::DeallocImage(id) => {
+                self.u8(tag::DEALLOC_IMAGE)?;
+                self.u32(*id)
+            }
+            Command::DeallocShader(id) => {
+                self.u8(tag::DEALLOC_SHADER)?;
+                self.u32(*id)
+            }
+            Command::DeallocPipeline(id) => {
+                self.u8(tag::DEALLOC_PIPELINE)?;
+                self.u32(*id)
+            }
+            Command::DeallocPass(id) => {
+                self.u8(tag::DEALLOC_PASS)?;
+                self.u32(*id)
+            }
+            Command::PushDebugGroup(name) => {
+                self.u8(tag::PUSH_DEBUG_GROUP)?;
+                self.bytes(name.as_bytes())
+            }
+            Command::PopDebugGroup => self.u8(tag::POP_DEBUG_GROUP),
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CaptureError> {
+        if self.bytes.len() - self.pos < n {
+            return Err(CaptureError::Corrupted("unexpected end of file"));
+        }
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, CaptureError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, CaptureError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn i32(&mut self) -> Result<i32, CaptureError> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn u64(&mut self) -> Result<u64, CaptureError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn f32(&mut self) -> Result<f32, CaptureError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn bool(&mut self) -> Result<bool, CaptureError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CaptureError::Corrupted("invalid boolean")),
+        }
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, CaptureError> {
+        let len = self.u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn opt_bytes(&mut self) -> Result<Option<Vec<u8>>, CaptureError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.bytes()?)),
+            _ => Err(CaptureError::Corrupted("invalid option tag")),
+        }
+    }
+
+    fn opt_cstring(&mut self) -> Result<Option<CString>, CaptureError> {
+        match self.opt_bytes()? {
+            Some(bytes) => CString::new(bytes)
+                .map(Some)
+                .map_err(|_| CaptureError::Corrupted("string with null byte")),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a descriptor written by [`Writer::field`]
+    fn field<T: Field>(&mut self) -> Result<T, CaptureError> {
+        T::read(self)
+    }
+
+    fn image_data(&mut self) -> Result<OwnedImageData, CaptureError> {
+        let len = self.u32()? as usize;
+        let mut subimages = Vec::with_capacity(len.min(6 * 16));
+        for _ in 0..len {
+            let face = self.u8()?;
+            let mip = self.u8()?;
+            if face as usize >= 6 || mip as usize >= 16 {
+                return Err(CaptureError::Corrupted("subimage index out of range"));
+            }
+            subimages.push((face, mip, self.bytes()?));
+        }
+        Ok(OwnedImageData { subimages })
+    }
+
+    fn command(&mut self) -> Result<Command, CaptureError> {
+        Ok(match self.u8()? {
+            tag::MAKE_BUFFER => Command::MakeBuffer {
+                id: self.u32()?,
+                desc: Box::new(OwnedBufferDesc {
+                    desc: self.field()?,
+                    data: self.opt_bytes()?,
+                    label: self.opt_cstring()?,
+                }),
+            },
+            tag::MAKE_IMAGE => Command::MakeImage {
+                id: self.u32()?,
+                desc: Box::new(OwnedImageDesc {
+                    desc: self.field()?,
+                    data: self.image_data()?,
+                    label: self.opt_cstring()?,
+                }),
+            },
+            tag::MAKE_SHADER => {
+                let id = self.u32()?;
+                let desc = self.field()?;
+                let len = self.u32()? as usize;
+                let mut strings = Vec::with_capacity(len.min(256));
+                for _ in 0..len {
+                    strings.push(self.opt_cstring()?);
+                }
+                Command::MakeShader {
+                    id,
+                    desc: Box::new(OwnedShaderDesc {
+                        desc,
+                        strings,
+                        vs_bytecode: self.opt_bytes()?,
+                        fs_bytecode: self.opt_bytes()?,
+                    }),
+                }
+            }
+            tag::MAKE_PIPELINE => Command::MakePipeline {
+                id: self.u32()?,
+                desc: Box::new(OwnedPipelineDesc {
+                    desc: self.field()?,
+                    label: self.opt_cstring()?,
+                }),
+            },
+            tag::MAKE_PASS => Command::MakePass {
+                id: self.u32()?,
+                desc: Box::new(OwnedPassDesc {
+                    desc: self.field()?,
+                    label: self.opt_cstring()?,
+                }),
+            },
+            tag::DESTROY_BUFFER => Command::DestroyBuffer(self.u32()?),
+            tag::DESTROY_IMAGE => Command::DestroyImage(self.u32()?),
+            tag::DESTROY_SHADER => Command::DestroyShader(self.u32()?),
+            tag::DESTROY_PIPELINE => Command::DestroyPipeline(self.u32()?),
+            tag::DESTROY_PASS => Command::DestroyPass(self.u32()?),
+            tag::UPDATE_BUFFER => Command::UpdateBuffer {
+                id: self.u32()?,
+                data: self.bytes()?,
+            },
+            tag::APPEND_BUFFER => Command::AppendBuffer {
+                id: self.u32()?,
+                data: self.bytes()?,
+            },
+            tag::UPDATE_IMAGE => Command::UpdateImage {
+                id: self.u32()?,
+                data: self.image_data()?,
+            },
+            tag::BEGIN_DEFAULT_PASS => Command::BeginDefaultPass {
+                action: self.field()?,
+                w: self.i32()?,
+                h: self.i32()?,
+            },
+            tag::BEGIN_PASS => Command::BeginPass {
+                id: self.u32()?,
+                action: self.field()?,
+            },
+            tag::APPLY_VIEWPORT => Command::ApplyViewport {
+                x: self.i32()?,
+                y: self.i32()?,
+                w: self.i32()?,
+                h: self.i32()?,
+                origin_top_left: self.bool()?,
+            },
+            tag::APPLY_SCISSOR_RECT => Command::ApplyScissorRect {
+                x: self.i32()?,
+                y: self.i32()?,
+                w: self.i32()?,
+                h: self.i32()?,
+                origin_top_left: self.bool()?,
+            },
+            tag::APPLY_PIPELINE => Command::ApplyPipeline(self.u32()?),
+            tag::APPLY_BINDINGS => Command::ApplyBindings(self.field()?),
+            tag::APPLY_UNIFORMS => Command::ApplyUniforms {
+                stage: match self.u32()? {
+                    x if x == ShaderStage::Vs as u32 => ShaderStage::Vs,
+                    x if x == ShaderStage::Fs as u32 => ShaderStage::Fs,
+                    _ => return Err(CaptureError::Corrupted("invalid shader stage")),
+                },
+                ub_index: self.i32()?,
+                data: self.bytes()?,
+            },
+            tag::DRAW => Command::Draw {
+                base_elem: self.i32()?,
+                n_elems: self.i32()?,
+                n_instances: self.i32()?,
+            },
+            tag::END_PASS => Command::EndPass,
+            tag::COMMIT => Command::Commit,
+            tag::ALLOC_BUFFER => Command::AllocBuffer(self.u32()?),
+            tag::ALLOC_IMAGE => Command::AllocImage(self.u32()?),
+            tag::ALLOC_SHADER => Command::AllocShader(self.u32()?),
+            tag::ALLOC_PIPELINE => Command::AllocPipeline(self.u32()?),
+            tag::ALLOC_PASS => Command::AllocPass(self.u32()?),
+            tag::FAIL_BUFFER => Command::FailBuffer(self.u32()?),
+            tag::FAIL_IMAGE => Command::FailImage(self.u32()?),
+            tag::FAIL_SHADER => Command::FailShader(self.u32()?),
+            tag::FAIL_PIPELINE => Command::FailPipeline(self.u32()?),
+            tag::FAIL_PASS => Command::FailPass(self.u32()?),
+            tag::UNINIT_BUFFER => Command::UninitBuffer(self.u32()?),
+            tag::UNINIT_IMAGE => Command::UninitImage(self.u32()?),
+            tag::UNINIT_SHADER => Command::UninitShader(self.u32()?),
+            tag::UNINIT_PIPELINE => Command::UninitPipeline(self.u32()?),
+            tag::UNINIT_PASS => Command::UninitPass(self.u32()?),
+            tag::DEALLOC_BUFFER => Command::DeallocBuffer(self.u32()?),
+            tag::DEALLOC_IMAGE => Command::DeallocImage(self.u32()?),
+            tag::DEALLOC_SHADER => Command::DeallocShader(self.u32()?),
+            tag::DEALLOC_PIPELINE => Command::DeallocPipeline(self.u32()?),
+            tag::DEALLOC_PASS => Command::DeallocPass(self.u32()?),
+            tag::PUSH_DEBUG_GROUP => Command::PushDebugGroup(
+                String::from_utf8(self.bytes()?)
+                    .map_err(|_| CaptureError::Corrupted("non UTF-8 string"))?,
+            ),
+            tag::POP_DEBUG_GROUP => Command::PopDebugGroup,
+            _ => return Err(CaptureError::Corrupted("unknown command")),
+        })
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Replay
+
+/// Target of [`Capture::replay`]
+pub trait Backend {
+    fn issue(&mut self, cmd: &Command);
+}
+
+/// Headless backend that collects the commands
+impl Backend for Vec<Command> {
+    fn issue(&mut self, cmd: &Command) {
+        self.push(cmd.clone());
+    }
+}
+
+/// Backend that calls `rokol::gfx`
+///
+/// Resource IDs in the commands are mapped to the resources created on replay. Resources are
+/// destroyed on drop.
+#[derive(Debug, Default)]
+pub struct GfxBackend {
+    buffers: HashMap<u32, Buffer>,
+    images: HashMap<u32, Image>,
+    shaders: HashMap<u32, Shader>,
+    pipelines: HashMap<u32, Pipeline>,
+    passes: HashMap<u32, Pass>,
+}
+
+fn map_id<T: Copy + Default>(map: &HashMap<u32, T>, id: u32) -> T {
+    if id == 0 {
+        return T::default();
+    }
+    map.get(&id).copied().unwrap_or_else(|| {
+        log::warn!("rokol replay: unknown resource ID {}", id);
+        T::default()
+    })
+}
+
+/// Initializes the resource if it's allocated on replay, creates it otherwise
+fn make<T: BakedResource<Id = T> + Copy>(map: &mut HashMap<u32, T>, id: u32, desc: &T::Desc) {
+    match map.get(&id) {
+        Some(x) => T::init(*x, desc),
+        None => {
+            map.insert(id, T::create(desc));
+        }
+    }
+}
+
+impl GfxBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn map_bindings(&self, bind: &Bindings) -> Bindings {
+        let mut bind = *bind;
+        for buf in bind.vertex_buffers.iter_mut() {
+            *buf = self::map_id(&self.buffers, buf.id);
+        }
+        bind.index_buffer = self::map_id(&self.buffers, bind.index_buffer.id);
+        for img in bind.vs_images.iter_mut().chain(bind.fs_images.iter_mut()) {
+            *img = self::map_id(&self.images, img.id);
+        }
+        bind
+    }
+
+    /// Destroys resources created on replay
+    pub fn clear(&mut self) {
+        if !unsafe { ffi::sg_isvalid() } {
+            return;
+        }
+
+        for (_, x) in self.passes.drain() {
+            Pass::destroy(x);
+        }
+        for (_, x) in self.pipelines.drain() {
+            Pipeline::destroy(x);
+        }
+        for (_, x) in self.shaders.drain() {
+            Shader::destroy(x);
+        }
+        for (_, x) in self.images.drain() {
+            Image::destroy(x);
+        }
+        for (_, x) in self.buffers.drain() {
+            Buffer::destroy(x);
+        }
+    }
+}
+
+impl Drop for GfxBackend {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl Backend for GfxBackend {
+    fn issue(&mut self, cmd: &Command) {
+        match cmd {
+            // pool exhausted at recording time
+            Command::MakeBuffer { id: 0, .. }
+            | Command::MakeImage { id: 0, .. }
+            | Command::MakeShader { id: 0, .. }
+            | Command::MakePipeline { id: 0, .. }
+            | Command::MakePass { id: 0, .. } => {}
+            Command::MakeBuffer { id, desc } => {
+                self::make(&mut self.buffers, *id, &desc.to_desc());
+            }
+            Command::MakeImage { id, desc } => {
+                self::make(&mut self.images, *id, &desc.to_desc());
+            }
+            Command::MakeShader { id, desc } => {
+                self::make(&mut self.shaders, *id, &desc.to_desc());
+            }
+            Command::MakePipeline { id, desc } => {
+                let mut desc = desc.to_desc();
+                desc.shader = self::map_id(&self.shaders, desc.shader.id);
+                self::make(&mut self.pipelines, *id, &desc);
+            }
+            Command::MakePass { id, desc } => {
+                let mut desc = desc.to_desc();
+                for att in desc.color_attachments.iter_mut() {
+                    att.image = self::map_id(&self.images, att.image.id);
+                }
+                desc.depth_stencil_attachment.image =
+                    self::map_id(&self.images, desc.depth_stencil_attachment.image.id);
+                self::make(&mut self.passes, *id, &desc);
+            }
+            Command::DestroyBuffer(id) => {
+                if let Some(x) = self.buffers.remove(id) {
+                    Buffer::destroy(x);
+                }
+            }
+            Command::DestroyImage(id) => {
+                if let Some(x) = self.images.remove(id) {
+                    Image::destroy(x);
+                }
+            }
+            Command::DestroyShader(id) => {
+                if let Some(x) = self.shaders.remove(id) {
+                    Shader::destroy(x);
+                }
+            }
+            Command::DestroyPipeline(id) => {
+                if let Some(x) = self.pipelines.remove(id) {
+                    Pipeline::destroy(x);
+                }
+            }
+            Command::DestroyPass(id) => {
+                if let Some(x) = self.passes.remove(id) {
+                    Pass::destroy(x);
+                }
+            }
+            Command::UpdateBuffer { id, data } => unsafe {
+                rg::update_buffer(self::map_id(&self.buffers, *id), data);
+            },
+            Command::AppendBuffer { id, data } => {
+                rg::append_buffer(self::map_id(&self.buffers, *id), data);
+            }
+            Command::UpdateImage { id, data } => unsafe {
+                rg::update_image(self::map_id(&self.images, *id), &data.to_data());
+            },
+            Command::BeginDefaultPass { action, w, h } => unsafe {
+                ffi::sg_begin_default_pass(action, *w, *h);
+            },
+            Command::BeginPass { id, action } => unsafe {
+                ffi::sg_begin_pass(self::map_id(&self.passes, *id), action);
+            },
+            Command::ApplyViewport {
+                x,
+                y,
+                w,
+                h,
+                origin_top_left,
+            } => unsafe {
+                ffi::sg_apply_viewport(*x, *y, *w, *h, *origin_top_left);
+            },
+            Command::ApplyScissorRect {
+                x,
+                y,
+                w,
+                h,
+                origin_top_left,
+            } => unsafe {
+                ffi::sg_apply_scissor_rect(*x, *y, *w, *h, *origin_top_left);
+            },
+            Command::ApplyPipeline(id) => {
+                rg::apply_pipeline(self::map_id(&self.pipelines, *id));
+            }
+            Command::ApplyBindings(bind) => {
+                rg::apply_bindings(&self.map_bindings(bind));
+            }
+            Command::ApplyUniforms {
+                stage,
+                ub_index,
+                data,
+            } => {
+                rg::apply_uniforms(*stage, *ub_index as u32, data);
+            }
+            Command::Draw {
+                base_elem,
+                n_elems,
+                n_instances,
+            } => unsafe {
+                ffi::sg_draw(*base_elem, *n_elems, *n_instances);
+            },
+            Command::EndPass => rg::end_pass(),
+            Command::Commit => rg::commit(),
+            Command::AllocBuffer(id) => {
+                if *id != 0 {
+                    self.buffers.insert(*id, Buffer::alloc());
+                }
+            }
+            Command::AllocImage(id) => {
+                if *id != 0 {
+                    self.images.insert(*id, Image::alloc());
+                }
+            }
+            Command::AllocShader(id) => {
+                if *id != 0 {
+                    self.shaders.insert(*id, Shader::alloc());
+                }
+            }
+            Command::AllocPipeline(id) => {
+                if *id != 0 {
+                    self.pipelines.insert(*id, Pipeline::alloc());
+                }
+            }
+            Command::AllocPass(id) => {
+                if *id != 0 {
+                    self.passes.insert(*id, Pass::alloc());
+                }
+            }
+            Command::FailBuffer(id) => unsafe {
+                ffi::sg_fail_buffer(self::map_id(&self.buffers, *id));
+            },
+            Command::FailImage(id) => unsafe {
+                ffi::sg_fail_image(self::map_id(&self.images, *id));
+            },
+            Command::FailShader(id) => unsafe {
+                ffi::sg_fail_shader(self::map_id(&self.shaders, *id));
+            },
+            Command::FailPipeline(id) => unsafe {
+                ffi::sg_fail_pipeline(self::map_id(&self.pipelines, *id));
+            },
+            Command::FailPass(id) => unsafe {
+                ffi::sg_fail_pass(self::map_id(&self.passes, *id));
+            },
+            Command::UninitBuffer(id) => {
+                Buffer::uninit(self::map_id(&self.buffers, *id));
+            }
+            Command::UninitImage(id) => {
+                Image::uninit(self::map_id(&self.images, *id));
+            }
+            Command::UninitShader(id) => {
+                Shader::uninit(self::map_id(&self.shaders, *id));
+            }
+            Command::UninitPipeline(id) => {
+                Pipeline::uninit(self::map_id(&self.pipelines, *id));
+            }
+            Command::UninitPass(id) => {
+                Pass::uninit(self::map_id(&self.passes, *id));
+            }
+            Command::DeallocBuffer(id) => {
+                if let Some(x) = self.buffers.remove(id) {
+                    Buffer::dealloc(x);
+                }
+            }
+            Command::DeallocImage(id) => {
+                if let Some(x) = self.images.remove(id) {
+                    Image::dealloc(x);
+                }
+            }
+            Command::DeallocShader(id) => {
+                if let Some(x) = self.shaders.remove(id) {
+                    Shader::dealloc(x);
+                }
+            }
+            Command::DeallocPipeline(id) => {
+                if let Some(x) = self.pipelines.remove(id) {
+                    Pipeline::dealloc(x);
+                }
+            }
+            Command::DeallocPass(id) => {
+                if let Some(x) = self.passes.remove(id) {
+                    Pass::dealloc(x);
+                }
+            }
+            Command::PushDebugGroup(name) => {
+                let name = CString::new(name.as_str()).unwrap_or_default();
+                unsafe {
+                    ffi::sg_push_debug_group(name.as_ptr());
+                }
+            }
+            Command::PopDebugGroup => unsafe {
+                ffi::sg_pop_debug_group();
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capture_roundtrip() {
+        let mut recorder = Recorder::new();
+
+        let vertices = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        let label = CString::new("vbuf").unwrap();
+        recorder.make_buffer(
+            &BufferDesc {
+                size: vertices.len() as _,
+                data: vertices.as_ref().into(),
+                label: label.as_ptr(),
+                ..Default::default()
+            },
+            Buffer { id: 1 },
+        );
+
+        let vs = CString::new("void main() {}").unwrap();
+        let mut shd = ShaderDesc::default();
+        shd.vs.source = vs.as_ptr();
+        recorder.make_shader(&shd, Shader { id: 2 });
+
+        recorder.start_capture();
+
+        let mut bind = Bindings::default();
+        bind.vertex_buffers[0] = Buffer { id: 1 };
+        recorder.begin_default_pass(&Default::default(), 1280, 720);
+        recorder.apply_bindings(&bind);
+        recorder.apply_uniforms(ShaderStage::Fs, 0, &[1, 2, 3, 4]);
+        recorder.draw(0, 6, 1);
+        recorder.end_pass();
+        recorder.commit();
+
+        let capture = recorder.take_capture().unwrap();
+        assert_eq!(capture.commands.len(), 8);
+
+        match &capture.commands[0] {
+            Command::MakeBuffer { id: 1, desc } => {
+                assert_eq!(desc.data.as_deref(), Some(vertices.as_ref()));
+                assert_eq!(desc.label.as_ref(), Some(&label));
+                assert_eq!(desc.to_desc().size as usize, vertices.len());
+            }
+            cmd => panic!("unexpected command: {:?}", cmd),
+        }
+
+        let mut bytes = Vec::new();
+        capture.write(&mut bytes).unwrap();
+        let loaded = Capture::read(&bytes).unwrap();
+        assert_eq!(loaded, capture);
+
+        let mut replayed = Vec::new();
+        loaded.replay(&mut replayed);
+        assert_eq!(replayed, capture.commands);
+
+        match &replayed[1] {
+            Command::MakeShader { desc, .. } => {
+                let desc = desc.to_desc();
+                let src = unsafe { CStr::from_ptr(desc.vs.source) };
+                assert_eq!(src, vs.as_c_str());
+                assert!(desc.fs.source.is_null());
+            }
+            cmd => panic!("unexpected command: {:?}", cmd),
+        }
+
+        assert!(matches!(
+            Capture::read(&bytes[..bytes.len() - 1]),
+            Err(CaptureError::Corrupted(_))
+        ));
+    }
+
+    #[test]
+    fn capture_loader_and_contents() {
+        let mut recorder = Recorder::new();
+
+        recorder.make_buffer(&BufferDesc::default(), Buffer { id: 1 });
+        recorder.update_buffer(Buffer { id: 1 }, &[1, 2, 3, 4]);
+        // loaded across the capture
+        recorder.alloc_image(Image { id: 2 });
+        recorder.alloc_image(Image { id: 3 });
+        // exhausted pool
+        recorder.alloc_image(Image { id: 0 });
+
+        recorder.start_capture();
+        recorder.push_debug_group("load");
+        recorder.init_image(Image { id: 2 }, &ImageDesc::default());
+        recorder.fail_image(Image { id: 3 });
+        recorder.pop_debug_group();
+        recorder.commit();
+
+        let capture = recorder.take_capture().unwrap();
+        let cmds = &capture.commands;
+        assert_eq!(cmds.len(), 10);
+        assert!(matches!(cmds[0], Command::MakeBuffer { id: 1, .. }));
+        assert_eq!(cmds[1..3], [Command::AllocImage(2), Command::AllocImage(3)]);
+        // the last contents are restored in a setup frame
+        assert_eq!(
+            cmds[3..5],
+            [
+                Command::UpdateBuffer {
+                    id: 1,
+                    data: vec![1, 2, 3, 4],
+                },
+                Command::Commit,
+            ]
+        );
+        assert_eq!(cmds[5], Command::PushDebugGroup("load".to_string()));
+        assert!(matches!(cmds[6], Command::MakeImage { id: 2, .. }));
+        assert_eq!(
+            cmds[7..],
+            [
+                Command::FailImage(3),
+                Command::PopDebugGroup,
+                Command::Commit
+            ]
+        );
+
+        let mut bytes = Vec::new();
+        capture.write(&mut bytes).unwrap();
+        assert_eq!(Capture::read(&bytes).unwrap(), capture);
+
+        // uninitialized resources are still allocated
+        recorder.uninit_image(Image { id: 2 });
+        recorder.dealloc_image(Image { id: 3 });
+        recorder.destroy_buffer(Buffer { id: 1 });
+        recorder.start_capture();
+        recorder.commit();
+        assert_eq!(
+            recorder.take_capture().unwrap().commands,
+            vec![Command::AllocImage(2), Command::Commit]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_enum() {
+        let capture = Capture::new(vec![Command::BeginDefaultPass {
+            action: Default::default(),
+            w: 1280,
+            h: 720,
+        }]);
+
+        let mut bytes = Vec::new();
+        capture.write(&mut bytes).unwrap();
+        assert_eq!(Capture::read(&bytes).unwrap(), capture);
+
+        // magic, version, number of commands and tag, followed by `colors[0].action`
+        bytes[13..17].copy_from_slice(&0xdead_beefu32.to_le_bytes());
+        assert!(matches!(
+            Capture::read(&bytes),
+            Err(CaptureError::Corrupted(_))
+        ));
+    }
+}
@@ -0,0 +1,486 @@
+//! Field-wise encoding of the FFI descriptors in capture files
+//!
+//! Only the plain fields are written; pointers, native handles and canaries are read back as
+//! defaults. Enums and `bool`s are checked on read, so corrupted files can't make invalid values.
+
+use std::{
+    convert::TryInto,
+    io::{self, Write},
+};
+
+use rokol_ffi::gfx as ffi;
+
+use super::{CaptureError, Reader, Writer};
+
+/// Field of an FFI descriptor
+pub(super) trait Field: Sized {
+    fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()>;
+    fn read(r: &mut Reader) -> Result<Self, CaptureError>;
+}
+
+macro_rules! impl_prim {
+    ($($ty:ty => $method:ident),* $(,)?) => {
+        $(
+            impl Field for $ty {
+                fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()> {
+                    w.$method(*self)
+                }
+
+                fn read(r: &mut Reader) -> Result<Self, CaptureError> {
+                    r.$method()
+                }
+            }
+        )*
+    };
+}
+
+impl_prim!(u8 => u8, u32 => u32, i32 => i32, u64 => u64, f32 => f32, bool => bool);
+
+impl<T: Field, const N: usize> Field for [T; N] {
+    fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()> {
+        for x in self {
+            x.write(w)?;
+        }
+        Ok(())
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, CaptureError> {
+        let items = (0..N).map(|_| T::read(r)).collect::<Result<Vec<_>, _>>()?;
+        items
+            .try_into()
+            .map_err(|_| CaptureError::Corrupted("array length"))
+    }
+}
+
+/// Enums are written as `u32` and rejected on read if they're not one of the variants
+macro_rules! impl_enums {
+    ($($ty:ident { $($variant:ident),* $(,)? })*) => {
+        $(
+            impl Field for ffi::$ty {
+                fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()> {
+                    w.u32(*self as u32)
+                }
+
+                fn read(r: &mut Reader) -> Result<Self, CaptureError> {
+                    let x = r.u32()?;
+                    $(
+                        if x == ffi::$ty::$variant as u32 {
+                            return Ok(ffi::$ty::$variant);
+                        }
+                    )*
+                    Err(CaptureError::Corrupted(concat!("invalid `", stringify!($ty), "`")))
+                }
+            }
+        )*
+    };
+}
+
+/// Structs are written field by field
+macro_rules! impl_structs {
+    ($($ty:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl Field for ffi::$ty {
+                fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()> {
+                    $(
+                        self.$field.write(w)?;
+                    )*
+                    Ok(())
+                }
+
+                fn read(r: &mut Reader) -> Result<Self, CaptureError> {
+                    Ok(ffi::$ty {
+                        $(
+                            $field: Field::read(r)?,
+                        )*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+/// Structs with pointers, native handles or canaries. The other fields are read as defaults
+macro_rules! impl_partial_structs {
+    ($($ty:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl Field for ffi::$ty {
+                fn write<W: Write>(&self, w: &mut Writer<W>) -> io::Result<()> {
+                    $(
+                        self.$field.write(w)?;
+                    )*
+                    Ok(())
+                }
+
+                fn read(r: &mut Reader) -> Result<Self, CaptureError> {
+                    Ok(ffi::$ty {
+                        $(
+                            $field: Field::read(r)?,
+                        )*
+                        ..Default::default()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_enums! {
+    sg_buffer_type {
+        _SG_BUFFERTYPE_DEFAULT,
+        SG_BUFFERTYPE_VERTEXBUFFER,
+        SG_BUFFERTYPE_INDEXBUFFER,
+        _SG_BUFFERTYPE_NUM,
+        _SG_BUFFERTYPE_FORCE_U32,
+    }
+    sg_usage {
+        _SG_USAGE_DEFAULT,
+        SG_USAGE_IMMUTABLE,
+        SG_USAGE_DYNAMIC,
+        SG_USAGE_STREAM,
+        _SG_USAGE_NUM,
+        _SG_USAGE_FORCE_U32,
+    }
+    sg_image_type {
+        _SG_IMAGETYPE_DEFAULT,
+        SG_IMAGETYPE_2D,
+        SG_IMAGETYPE_CUBE,
+        SG_IMAGETYPE_3D,
+        SG_IMAGETYPE_ARRAY,
+        _SG_IMAGETYPE_NUM,
+        _SG_IMAGETYPE_FORCE_U32,
+    }
+    sg_pixel_format {
+        _SG_PIXELFORMAT_DEFAULT,
+        SG_PIXELFORMAT_NONE,
+        SG_PIXELFORMAT_R8,
+        SG_PIXELFORMAT_R8SN,
+        SG_PIXELFORMAT_R8UI,
+        SG_PIXELFORMAT_R8SI,
+        SG_PIXELFORMAT_R16,
+        SG_PIXELFORMAT_R16SN,
+        SG_PIXELFORMAT_R16UI,
+        SG_PIXELFORMAT_R16SI,
+        SG_PIXELFORMAT_R16F,
+        SG_PIXELFORMAT_RG8,
+        SG_PIXELFORMAT_RG8SN,
+        SG_PIXELFORMAT_RG8UI,
+        SG_PIXELFORMAT_RG8SI,
+        SG_PIXELFORMAT_R32UI,
+        SG_PIXELFORMAT_R32SI,
+        SG_PIXELFORMAT_R32F,
+        SG_PIXELFORMAT_RG16,
+        SG_PIXELFORMAT_RG16SN,
+        SG_PIXELFORMAT_RG16UI,
+        SG_PIXELFORMAT_RG16SI,
+        SG_PIXELFORMAT_RG16F,
+        SG_PIXELFORMAT_RGBA8,
+        SG_PIXELFORMAT_RGBA8SN,
+        SG_PIXELFORMAT_RGBA8UI,
+        SG_PIXELFORMAT_RGBA8SI,
+        SG_PIXELFORMAT_BGRA8,
+        SG_PIXELFORMAT_RGB10A2,
+        SG_PIXELFORMAT_RG11B10F,
+        SG_PIXELFORMAT_RG32UI,
+        SG_PIXELFORMAT_RG32SI,
+        SG_PIXELFORMAT_RG32F,
+        SG_PIXELFORMAT_RGBA16,
+        SG_PIXELFORMAT_RGBA16SN,
+        SG_PIXELFORMAT_RGBA16UI,
+        SG_PIXELFORMAT_RGBA16SI,
+        SG_PIXELFORMAT_RGBA16F,
+        SG_PIXELFORMAT_RGBA32UI,
+        SG_PIXELFORMAT_RGBA32SI,
+        SG_PIXELFORMAT_RGBA32F,
+        SG_PIXELFORMAT_DEPTH,
+        SG_PIXELFORMAT_DEPTH_STENCIL,
+        SG_PIXELFORMAT_BC1_RGBA,
+        SG_PIXELFORMAT_BC2_RGBA,
+        SG_PIXELFORMAT_BC3_RGBA,
+        SG_PIXELFORMAT_BC4_R,
+        SG_PIXELFORMAT_BC4_RSN,
+        SG_PIXELFORMAT_BC5_RG,
+        SG_PIXELFORMAT_BC5_RGSN,
+        SG_PIXELFORMAT_BC6H_RGBF,
+        SG_PIXELFORMAT_BC6H_RGBUF,
+        SG_PIXELFORMAT_BC7_RGBA,
+        SG_PIXELFORMAT_PVRTC_RGB_2BPP,
+        SG_PIXELFORMAT_PVRTC_RGB_4BPP,
+        SG_PIXELFORMAT_PVRTC_RGBA_2BPP,
+        SG_PIXELFORMAT_PVRTC_RGBA_4BPP,
+        SG_PIXELFORMAT_ETC2_RGB8,
+        SG_PIXELFORMAT_ETC2_RGB8A1,
+        SG_PIXELFORMAT_ETC2_RGBA8,
+        SG_PIXELFORMAT_ETC2_RG11,
+        SG_PIXELFORMAT_ETC2_RG11SN,
+        _SG_PIXELFORMAT_NUM,
+        _SG_PIXELFORMAT_FORCE_U32,
+    }
+    sg_filter {
+        _SG_FILTER_DEFAULT,
+        SG_FILTER_NEAREST,
+        SG_FILTER_LINEAR,
+        SG_FILTER_NEAREST_MIPMAP_NEAREST,
+        SG_FILTER_NEAREST_MIPMAP_LINEAR,
+        SG_FILTER_LINEAR_MIPMAP_NEAREST,
+        SG_FILTER_LINEAR_MIPMAP_LINEAR,
+        _SG_FILTER_NUM,
+        _SG_FILTER_FORCE_U32,
+    }
+    sg_wrap {
+        _SG_WRAP_DEFAULT,
+        SG_WRAP_REPEAT,
+        SG_WRAP_CLAMP_TO_EDGE,
+        SG_WRAP_CLAMP_TO_BORDER,
+        SG_WRAP_MIRRORED_REPEAT,
+        _SG_WRAP_NUM,
+        _SG_WRAP_FORCE_U32,
+    }
+    sg_border_color {
+        _SG_BORDERCOLOR_DEFAULT,
+        SG_BORDERCOLOR_TRANSPARENT_BLACK,
+        SG_BORDERCOLOR_OPAQUE_BLACK,
+        SG_BORDERCOLOR_OPAQUE_WHITE,
+        _SG_BORDERCOLOR_NUM,
+        _SG_BORDERCOLOR_FORCE_U32,
+    }
+    sg_uniform_type {
+        SG_UNIFORMTYPE_INVALID,
+        SG_UNIFORMTYPE_FLOAT,
+        SG_UNIFORMTYPE_FLOAT2,
+        SG_UNIFORMTYPE_FLOAT3,
+        SG_UNIFORMTYPE_FLOAT4,
+        SG_UNIFORMTYPE_MAT4,
+        _SG_UNIFORMTYPE_NUM,
+        _SG_UNIFORMTYPE_FORCE_U32,
+    }
+    sg_sampler_type {
+        _SG_SAMPLERTYPE_DEFAULT,
+        SG_SAMPLERTYPE_FLOAT,
+        SG_SAMPLERTYPE_SINT,
+        SG_SAMPLERTYPE_UINT,
+    }
+    sg_vertex_step {
+        _SG_VERTEXSTEP_DEFAULT,
+        SG_VERTEXSTEP_PER_VERTEX,
+        SG_VERTEXSTEP_PER_INSTANCE,
+        _SG_VERTEXSTEP_NUM,
+        _SG_VERTEXSTEP_FORCE_U32,
+    }
+    sg_vertex_format {
+        SG_VERTEXFORMAT_INVALID,
+        SG_VERTEXFORMAT_FLOAT,
+        SG_VERTEXFORMAT_FLOAT2,
+        SG_VERTEXFORMAT_FLOAT3,
+        SG_VERTEXFORMAT_FLOAT4,
+        SG_VERTEXFORMAT_BYTE4,
+        SG_VERTEXFORMAT_BYTE4N,
+        SG_VERTEXFORMAT_UBYTE4,
+        SG_VERTEXFORMAT_UBYTE4N,
+        SG_VERTEXFORMAT_SHORT2,
+        SG_VERTEXFORMAT_SHORT2N,
+        SG_VERTEXFORMAT_USHORT2N,
+        SG_VERTEXFORMAT_SHORT4,
+        SG_VERTEXFORMAT_SHORT4N,
+        SG_VERTEXFORMAT_USHORT4N,
+        SG_VERTEXFORMAT_UINT10_N2,
+        _SG_VERTEXFORMAT_NUM,
+        _SG_VERTEXFORMAT_FORCE_U32,
+    }
+    sg_compare_func {
+        _SG_COMPAREFUNC_DEFAULT,
+        SG_COMPAREFUNC_NEVER,
+        SG_COMPAREFUNC_LESS,
+        SG_COMPAREFUNC_EQUAL,
+        SG_COMPAREFUNC_LESS_EQUAL,
+        SG_COMPAREFUNC_GREATER,
+        SG_COMPAREFUNC_NOT_EQUAL,
+        SG_COMPAREFUNC_GREATER_EQUAL,
+        SG_COMPAREFUNC_ALWAYS,
+        _SG_COMPAREFUNC_NUM,
+        _SG_COMPAREFUNC_FORCE_U32,
+    }
+    sg_stencil_op {
+        _SG_STENCILOP_DEFAULT,
+        SG_STENCILOP_KEEP,
+        SG_STENCILOP_ZERO,
+        SG_STENCILOP_REPLACE,
+        SG_STENCILOP_INCR_CLAMP,
+        SG_STENCILOP_DECR_CLAMP,
+        SG_STENCILOP_INVERT,
+        SG_STENCILOP_INCR_WRAP,
+        SG_STENCILOP_DECR_WRAP,
+        _SG_STENCILOP_NUM,
+        _SG_STENCILOP_FORCE_U32,
+    }
+    sg_color_mask {
+        _SG_COLORMASK_DEFAULT,
+        SG_COLORMASK_NONE,
+        SG_COLORMASK_R,
+        SG_COLORMASK_G,
+        SG_COLORMASK_RG,
+        SG_COLORMASK_B,
+        SG_COLORMASK_RB,
+        SG_COLORMASK_GB,
+        SG_COLORMASK_RGB,
+        SG_COLORMASK_A,
+        SG_COLORMASK_RA,
+        SG_COLORMASK_GA,
+        SG_COLORMASK_RGA,
+        SG_COLORMASK_BA,
+        SG_COLORMASK_RBA,
+        SG_COLORMASK_GBA,
+        SG_COLORMASK_RGBA,
+        _SG_COLORMASK_FORCE_U32,
+    }
+    sg_blend_factor {
+        _SG_BLENDFACTOR_DEFAULT,
+        SG_BLENDFACTOR_ZERO,
+        SG_BLENDFACTOR_ONE,
+        SG_BLENDFACTOR_SRC_COLOR,
+        SG_BLENDFACTOR_ONE_MINUS_SRC_COLOR,
+        SG_BLENDFACTOR_SRC_ALPHA,
+        SG_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+        SG_BLENDFACTOR_DST_COLOR,
+        SG_BLENDFACTOR_ONE_MINUS_DST_COLOR,
+        SG_BLENDFACTOR_DST_ALPHA,
+        SG_BLENDFACTOR_ONE_MINUS_DST_ALPHA,
+        SG_BLENDFACTOR_SRC_ALPHA_SATURATED,
+        SG_BLENDFACTOR_BLEND_COLOR,
+        SG_BLENDFACTOR_ONE_MINUS_BLEND_COLOR,
+        SG_BLENDFACTOR_BLEND_ALPHA,
+        SG_BLENDFACTOR_ONE_MINUS_BLEND_ALPHA,
+        _SG_BLENDFACTOR_NUM,
+        _SG_BLENDFACTOR_FORCE_U32,
+    }
+    sg_blend_op {
+        _SG_BLENDOP_DEFAULT,
+        SG_BLENDOP_ADD,
+        SG_BLENDOP_SUBTRACT,
+        SG_BLENDOP_REVERSE_SUBTRACT,
+        _SG_BLENDOP_NUM,
+        _SG_BLENDOP_FORCE_U32,
+    }
+    sg_primitive_type {
+        _SG_PRIMITIVETYPE_DEFAULT,
+        SG_PRIMITIVETYPE_POINTS,
+        SG_PRIMITIVETYPE_LINES,
+        SG_PRIMITIVETYPE_LINE_STRIP,
+        SG_PRIMITIVETYPE_TRIANGLES,
+        SG_PRIMITIVETYPE_TRIANGLE_STRIP,
+        _SG_PRIMITIVETYPE_NUM,
+        _SG_PRIMITIVETYPE_FORCE_U32,
+    }
+    sg_index_type {
+        _SG_INDEXTYPE_DEFAULT,
+        SG_INDEXTYPE_NONE,
+        SG_INDEXTYPE_UINT16,
+        SG_INDEXTYPE_UINT32,
+        _SG_INDEXTYPE_NUM,
+        _SG_INDEXTYPE_FORCE_U32,
+    }
+    sg_cull_mode {
+        _SG_CULLMODE_DEFAULT,
+        SG_CULLMODE_NONE,
+        SG_CULLMODE_FRONT,
+        SG_CULLMODE_BACK,
+        _SG_CULLMODE_NUM,
+        _SG_CULLMODE_FORCE_U32,
+    }
+    sg_face_winding {
+        _SG_FACEWINDING_DEFAULT,
+        SG_FACEWINDING_CCW,
+        SG_FACEWINDING_CW,
+        _SG_FACEWINDING_NUM,
+        _SG_FACEWINDING_FORCE_U32,
+    }
+    sg_action {
+        _SG_ACTION_DEFAULT,
+        SG_ACTION_CLEAR,
+        SG_ACTION_LOAD,
+        SG_ACTION_DONTCARE,
+        _SG_ACTION_NUM,
+        _SG_ACTION_FORCE_U32,
+    }
+}
+
+impl_structs! {
+    sg_buffer { id }
+    sg_image { id }
+    sg_shader { id }
+    sg_color { r, g, b, a }
+    sg_shader_uniform_block_desc { size, uniforms }
+    sg_buffer_layout_desc { stride, step_func, step_rate }
+    sg_vertex_attr_desc { buffer_index, offset, format }
+    sg_layout_desc { buffers, attrs }
+    sg_stencil_face_state { compare, fail_op, depth_fail_op, pass_op }
+    sg_stencil_state { enabled, front, back, read_mask, write_mask, ref_ }
+    sg_depth_state { pixel_format, compare, write_enabled, bias, bias_slope_scale, bias_clamp }
+    sg_blend_state {
+        enabled,
+        src_factor_rgb,
+        dst_factor_rgb,
+        op_rgb,
+        src_factor_alpha,
+        dst_factor_alpha,
+        op_alpha,
+    }
+    sg_color_state { pixel_format, write_mask, blend }
+    sg_pass_attachment_desc { image, mip_level, slice }
+    sg_color_attachment_action { action, value }
+    sg_depth_attachment_action { action, value }
+    sg_stencil_attachment_action { action, value }
+}
+
+impl_partial_structs! {
+    sg_buffer_desc { size, type_, usage }
+    sg_image_desc {
+        type_,
+        render_target,
+        width,
+        height,
+        num_slices,
+        num_mipmaps,
+        usage,
+        pixel_format,
+        sample_count,
+        min_filter,
+        mag_filter,
+        wrap_u,
+        wrap_v,
+        wrap_w,
+        border_color,
+        max_anisotropy,
+        min_lod,
+        max_lod,
+    }
+    sg_shader_attr_desc { sem_index }
+    sg_shader_uniform_desc { type_, array_count }
+    sg_shader_image_desc { image_type, sampler_type }
+    sg_shader_stage_desc { uniform_blocks, images }
+    sg_shader_desc { attrs, vs, fs }
+    sg_pipeline_desc {
+        shader,
+        layout,
+        depth,
+        stencil,
+        color_count,
+        colors,
+        primitive_type,
+        index_type,
+        cull_mode,
+        face_winding,
+        sample_count,
+        blend_color,
+        alpha_to_coverage_enabled,
+    }
+    sg_pass_desc { color_attachments, depth_stencil_attachment }
+    sg_pass_action { colors, depth, stencil }
+    sg_bindings {
+        vertex_buffers,
+        vertex_buffer_offsets,
+        index_buffer,
+        index_buffer_offset,
+        vs_images,
+        fs_images,
+    }
+}
@@ -0,0 +1,267 @@
+/*!
+Registry of live resources (`registry` feature)
+
+Every resource created via [`BakedResource`](crate::gfx::BakedResource) is recorded with its label
+(and creation backtrace in debug builds) until it's destroyed. Leaked resources are logged on
+[`crate::gfx::shutdown`]:
+
+```no_run
+use rokol::gfx::{self as rg, registry};
+
+for count in registry::live_counts() {
+    log::info!("{:?}: {}/{}", count.kind, count.live, count.pool_size);
+}
+
+// logs resources that are not destroyed yet
+rg::shutdown();
+```
+*/
+
+use std::{
+    collections::BTreeMap,
+    ffi::CStr,
+    fmt,
+    os::raw::c_char,
+    sync::{Mutex, MutexGuard},
+};
+
+#[cfg(debug_assertions)]
+use std::backtrace::Backtrace;
+
+use rokol_ffi::gfx as ffi;
+
+/// Kind of [`BakedResource`](crate::gfx::BakedResource)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResourceKind {
+    Buffer,
+    Image,
+    Shader,
+    Pipeline,
+    Pass,
+}
+
+impl ResourceKind {
+    pub const ALL: [Self; 5] = [
+        Self::Buffer,
+        Self::Image,
+        Self::Shader,
+        Self::Pipeline,
+        Self::Pass,
+    ];
+
+    /// Pool size set up with `sokol_gfx.h` (requires [`crate::gfx::setup`])
+    pub fn pool_size(self) -> usize {
+        let desc = unsafe { ffi::sg_query_desc() };
+        let size = match self {
+            Self::Buffer => desc.buffer_pool_size,
+            Self::Image => desc.image_pool_size,
+            Self::Shader => desc.shader_pool_size,
+            Self::Pipeline => desc.pipeline_pool_size,
+            Self::Pass => desc.pass_pool_size,
+        };
+        size.max(0) as usize
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Buffer => "buffer",
+            Self::Image => "image",
+            Self::Shader => "shader",
+            Self::Pipeline => "pipeline",
+            Self::Pass => "pass",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Live resource recorded in the registry
+#[derive(Debug)]
+pub struct Entry {
+    pub kind: ResourceKind,
+    pub id: u32,
+    pub label: Option<String>,
+    /// Where the resource was allocated
+    #[cfg(debug_assertions)]
+    pub backtrace: Backtrace,
+}
+
+/// Number of live resources of a [`ResourceKind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveCount {
+    pub kind: ResourceKind,
+    pub live: usize,
+    pub pool_size: usize,
+}
+
+/// Live resources for each kind, sorted by ID
+type Registry = [BTreeMap<u32, Entry>; 5];
+
+static REGISTRY: Mutex<Registry> = Mutex::new([
+    BTreeMap::new(),
+    BTreeMap::new(),
+    BTreeMap::new(),
+    BTreeMap::new(),
+    BTreeMap::new(),
+]);
+
+fn lock() -> MutexGuard<'static, Registry> {
+    // the registry is only for debugging; don't let a panic poison it
+    REGISTRY.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn label_string(label: *const c_char) -> Option<String> {
+    if label.is_null() {
+        None
+    } else {
+        let label = unsafe { CStr::from_ptr(label) };
+        Some(label.to_string_lossy().into_owned())
+    }
+}
+
+/// Called on `alloc`
+pub(crate) fn on_alloc(kind: ResourceKind, id: u32) {
+    // `SG_INVALID_ID`: the pool is exhausted
+    if id == 0 {
+        return;
+    }
+
+    let entry = Entry {
+        kind,
+        id,
+        label: None,
+        #[cfg(debug_assertions)]
+        backtrace: Backtrace::force_capture(),
+    };
+
+    lock()[kind as usize].insert(id, entry);
+}
+
+/// Called on `init`
+pub(crate) fn on_init(kind: ResourceKind, id: u32, label: *const c_char) {
+    if let Some(entry) = lock()[kind as usize].get_mut(&id) {
+        entry.label = self::label_string(label);
+    }
+}
+
+/// Called on `create` (`alloc` + `init`)
+pub(crate) fn on_create(kind: ResourceKind, id: u32, label: *const c_char) {
+    self::on_alloc(kind, id);
+    self::on_init(kind, id, label);
+}
+
+/// Called on `destroy` or `dealloc`
+pub(crate) fn on_dealloc(kind: ResourceKind, id: u32) {
+    lock()[kind as usize].remove(&id);
+}
+
+/// Number of live resources of a kind
+pub fn live(kind: ResourceKind) -> usize {
+    lock()[kind as usize].len()
+}
+
+/// Live resource counts against the pool sizes (requires [`crate::gfx::setup`])
+pub fn live_counts() -> Vec<LiveCount> {
+    let reg = lock();
+    ResourceKind::ALL
+        .iter()
+        .map(|&kind| LiveCount {
+            kind,
+            live: reg[kind as usize].len(),
+            pool_size: kind.pool_size(),
+        })
+        .collect()
+}
+
+/// Visits live resources ordered by kind and ID
+pub fn visit_live(mut f: impl FnMut(&Entry)) {
+    let reg = lock();
+    for entry in reg.iter().flat_map(|entries| entries.values()) {
+        f(entry);
+    }
+}
+
+/// Logs live resources as leaks and forgets them. Returns the number of the leaked resources.
+///
+/// Called on [`crate::gfx::shutdown`].
+pub fn report_leaks() -> usize {
+    let mut reg = lock();
+
+    let n_leaks = reg.iter().map(|entries| entries.len()).sum::<usize>();
+    if n_leaks == 0 {
+        return 0;
+    }
+
+    log::warn!("rokol: {} gfx resource(s) leaked", n_leaks);
+    for entries in reg.iter_mut() {
+        for entry in entries.values() {
+            let label = entry.label.as_deref().unwrap_or("<no label>");
+
+            #[cfg(debug_assertions)]
+            log::warn!(
+                "leaked {} (id: {}, label: {}), allocated at:\n{}",
+                entry.kind,
+                entry.id,
+                label,
+                entry.backtrace
+            );
+
+            #[cfg(not(debug_assertions))]
+            log::warn!("leaked {} (id: {}, label: {})", entry.kind, entry.id, label);
+        }
+        entries.clear();
+    }
+
+    n_leaks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The registry is global, so the tests run one by one
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn create_destroy() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        self::report_leaks();
+
+        let label = std::ffi::CString::new("vbuf").unwrap();
+        on_create(ResourceKind::Buffer, 1, label.as_ptr());
+        on_create(ResourceKind::Image, 1, std::ptr::null());
+        assert_eq!(live(ResourceKind::Buffer), 1);
+        assert_eq!(live(ResourceKind::Image), 1);
+
+        let mut labels = Vec::new();
+        visit_live(|entry| labels.push((entry.kind, entry.label.clone())));
+        assert_eq!(
+            labels,
+            vec![
+                (ResourceKind::Buffer, Some("vbuf".to_string())),
+                (ResourceKind::Image, None),
+            ]
+        );
+
+        on_dealloc(ResourceKind::Buffer, 1);
+        on_dealloc(ResourceKind::Image, 1);
+        assert_eq!(report_leaks(), 0);
+    }
+
+    #[test]
+    fn count_leaks() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        self::report_leaks();
+
+        // `SG_INVALID_ID` is not recorded
+        on_alloc(ResourceKind::Shader, 0);
+        on_alloc(ResourceKind::Shader, 2);
+        on_init(ResourceKind::Shader, 2, std::ptr::null());
+        on_create(ResourceKind::Pass, 3, std::ptr::null());
+        on_dealloc(ResourceKind::Pass, 3);
+
+        assert_eq!(report_leaks(), 1);
+        assert_eq!(live(ResourceKind::Shader), 0);
+    }
+}
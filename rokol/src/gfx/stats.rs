@@ -0,0 +1,239 @@
+/*!
+Per-frame render statistics
+
+[`install`] a [`FrameStatsCollector`] after [`crate::gfx::setup`]. It counts `sokol_gfx.h` calls
+via [trace hooks](crate::gfx::trace) and finishes a frame on each [`crate::gfx::commit`]:
+
+```no_run
+use rokol::gfx::stats;
+
+// keep the last 120 frames
+let stats = stats::install(120);
+
+// .. render a frame and `commit` ..
+
+stats.with(|stats| {
+    if let Some(last) = stats.last() {
+        log::info!("{:?}", last);
+    }
+});
+```
+*/
+
+use std::collections::VecDeque;
+
+use crate::gfx::{
+    trace::{self, TraceHandle, Tracer},
+    Bindings, Buffer, Image, ImageData, Pass, Pipeline, ShaderStage,
+};
+
+use rokol_ffi::gfx as ffi;
+
+/// Counts of `sokol_gfx.h` calls in one frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FrameStats {
+    /// Frame index counted by the collector
+    pub frame: u64,
+    /// Number of [`crate::gfx::draw`] calls
+    pub n_draws: u32,
+    /// Sum of `n_instances` of draw calls
+    pub n_instances: u64,
+    /// Sum of `n_elems` of draw calls
+    pub n_elems: u64,
+    /// Number of [`crate::gfx::apply_pipeline`] calls
+    pub n_pipeline_switches: u32,
+    /// Number of [`crate::gfx::apply_bindings`] calls
+    pub n_bindings_switches: u32,
+    /// Number of [`crate::gfx::apply_uniforms`] calls
+    pub n_uniform_updates: u32,
+    /// Uploaded uniform data in bytes
+    pub uniform_bytes: u64,
+    /// Number of buffer updates and appends
+    pub n_buffer_updates: u32,
+    /// Uploaded buffer data in bytes (updates and appends)
+    pub buffer_update_bytes: u64,
+    /// Number of image updates
+    pub n_image_updates: u32,
+    /// Uploaded image data in bytes
+    pub image_update_bytes: u64,
+    /// Number of default and offscreen rendering passes
+    pub n_passes: u32,
+}
+
+/// Installs a [`FrameStatsCollector`] that remembers the last `history_len` frames
+pub fn install(history_len: usize) -> TraceHandle<FrameStatsCollector> {
+    trace::install(FrameStatsCollector::new(history_len))
+}
+
+/// [`Tracer`] that aggregates [`FrameStats`] between `commit` calls
+#[derive(Debug, Clone)]
+pub struct FrameStatsCollector {
+    /// Stats of the frame in progress
+    current: FrameStats,
+    /// Finished frames (oldest first)
+    history: VecDeque<FrameStats>,
+    history_len: usize,
+}
+
+impl FrameStatsCollector {
+    /// Creates a collector that remembers the last `history_len` frames
+    pub fn new(history_len: usize) -> Self {
+        Self {
+            current: FrameStats::default(),
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+        }
+    }
+
+    /// Stats of the frame in progress
+    pub fn current(&self) -> &FrameStats {
+        &self.current
+    }
+
+    /// Stats of the last committed frame
+    pub fn last(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    /// Committed frames (oldest first)
+    pub fn history(&self) -> impl Iterator<Item = &FrameStats> + '_ {
+        self.history.iter()
+    }
+
+    /// Maximum number of frames in the [`history`](Self::history)
+    pub fn history_len(&self) -> usize {
+        self.history_len
+    }
+
+    /// Component-wise maximum over the history
+    pub fn peak(&self) -> FrameStats {
+        self.history
+            .iter()
+            .fold(FrameStats::default(), |acc, s| FrameStats {
+                frame: acc.frame.max(s.frame),
+                n_draws: acc.n_draws.max(s.n_draws),
+                n_instances: acc.n_instances.max(s.n_instances),
+                n_elems: acc.n_elems.max(s.n_elems),
+                n_pipeline_switches: acc.n_pipeline_switches.max(s.n_pipeline_switches),
+                n_bindings_switches: acc.n_bindings_switches.max(s.n_bindings_switches),
+                n_uniform_updates: acc.n_uniform_updates.max(s.n_uniform_updates),
+                uniform_bytes: acc.uniform_bytes.max(s.uniform_bytes),
+                n_buffer_updates: acc.n_buffer_updates.max(s.n_buffer_updates),
+                buffer_update_bytes: acc.buffer_update_bytes.max(s.buffer_update_bytes),
+                n_image_updates: acc.n_image_updates.max(s.n_image_updates),
+                image_update_bytes: acc.image_update_bytes.max(s.image_update_bytes),
+                n_passes: acc.n_passes.max(s.n_passes),
+            })
+    }
+
+    /// Forgets the history and the frame in progress
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.current = FrameStats {
+            frame: self.current.frame,
+            ..Default::default()
+        };
+    }
+}
+
+impl Tracer for FrameStatsCollector {
+    fn update_buffer(&mut self, _buf: Buffer, data: &[u8]) {
+        self.current.n_buffer_updates += 1;
+        self.current.buffer_update_bytes += data.len() as u64;
+    }
+
+    fn append_buffer(&mut self, _buf: Buffer, data: &[u8], _offset: i32) {
+        self.current.n_buffer_updates += 1;
+        self.current.buffer_update_bytes += data.len() as u64;
+    }
+
+    fn update_image(&mut self, _img: Image, data: &ImageData) {
+        self.current.n_image_updates += 1;
+        self.current.image_update_bytes += data
+            .subimage
+            .iter()
+            .flat_map(|face| face.iter())
+            .map(|range| range.size)
+            .sum::<u64>();
+    }
+
+    fn begin_default_pass(&mut self, _pa: &ffi::sg_pass_action, _w: i32, _h: i32) {
+        self.current.n_passes += 1;
+    }
+
+    fn begin_pass(&mut self, _pass: Pass, _pa: &ffi::sg_pass_action) {
+        self.current.n_passes += 1;
+    }
+
+    fn apply_pipeline(&mut self, _pip: Pipeline) {
+        self.current.n_pipeline_switches += 1;
+    }
+
+    fn apply_bindings(&mut self, _bind: &Bindings) {
+        self.current.n_bindings_switches += 1;
+    }
+
+    fn apply_uniforms(&mut self, _stage: ShaderStage, _ub_index: i32, data: &[u8]) {
+        self.current.n_uniform_updates += 1;
+        self.current.uniform_bytes += data.len() as u64;
+    }
+
+    fn draw(&mut self, _base_elem: i32, n_elems: i32, n_instances: i32) {
+        self.current.n_draws += 1;
+        self.current.n_elems += n_elems.max(0) as u64;
+        self.current.n_instances += n_instances.max(0) as u64;
+    }
+
+    fn commit(&mut self) {
+        let next = FrameStats {
+            frame: self.current.frame + 1,
+            ..Default::default()
+        };
+        let finished = std::mem::replace(&mut self.current, next);
+
+        if self.history_len == 0 {
+            return;
+        }
+        if self.history.len() == self.history_len {
+            self.history.pop_front();
+        }
+        self.history.push_back(finished);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aggregate_frames() {
+        let mut stats = FrameStatsCollector::new(2);
+
+        for frame in 0..3 {
+            stats.begin_default_pass(&Default::default(), 640, 360);
+            stats.apply_pipeline(Pipeline::default());
+            stats.apply_bindings(&Bindings::default());
+            stats.apply_uniforms(ShaderStage::Vs, 0, &[0; 64]);
+            for _ in 0..=frame {
+                stats.draw(0, 6, 2);
+            }
+            stats.append_buffer(Buffer::default(), &[0; 16], 0);
+            stats.end_pass();
+            stats.commit();
+        }
+
+        assert_eq!(stats.history().count(), 2);
+
+        let last = stats.last().unwrap();
+        assert_eq!(last.frame, 2);
+        assert_eq!(last.n_draws, 3);
+        assert_eq!(last.n_elems, 18);
+        assert_eq!(last.n_instances, 6);
+        assert_eq!(last.n_passes, 1);
+        assert_eq!(last.uniform_bytes, 64);
+        assert_eq!(last.buffer_update_bytes, 16);
+
+        assert_eq!(stats.current().n_draws, 0);
+        assert_eq!(stats.peak().n_draws, 3);
+    }
+}
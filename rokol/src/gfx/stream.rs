@@ -0,0 +1,272 @@
+/*!
+Per-frame sub-allocator over [`append_buffer`](crate::gfx::append_buffer)
+
+[`StreamBuffer`] never lets `sokol_gfx.h` overflow the buffer (which would silently drop the
+following draw calls). Instead, allocations that don't fit are refused and the buffer is recreated
+with enough capacity on the first allocation of the next frame:
+
+```no_run
+use rokol::gfx::{self as rg, StreamBuffer};
+
+let mut stream = StreamBuffer::vertex(64 * 1024, "immediate");
+let mut bind = rg::Bindings::default();
+
+// every frame:
+let verts: &[[f32; 2]] = &[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+if let Some(range) = stream.alloc(verts) {
+    range.set_vertex(&mut bind, 0);
+    rg::apply_bindings(&bind);
+    rg::draw(0, range.len as u32, 1);
+}
+```
+*/
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{
+    self as rg, buffer, update, BakedResource, Bindings, Buffer, BufferType, Pod, ResourceUsage,
+};
+
+/// Sub-allocated range of a [`StreamBuffer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamRange {
+    /// The buffer at the allocation (the buffer can be recreated on next frame)
+    pub buf: Buffer,
+    /// Byte offset for [`Bindings::vertex_buffer_offsets`] or [`Bindings::index_buffer_offset`]
+    pub offset: i32,
+    /// Number of the allocated elements
+    pub len: usize,
+}
+
+impl StreamRange {
+    pub fn set_vertex(&self, bind: &mut Bindings, slot: usize) {
+        bind.vertex_buffers[slot] = self.buf;
+        bind.vertex_buffer_offsets[slot] = self.offset;
+    }
+
+    pub fn set_index(&self, bind: &mut Bindings) {
+        bind.index_buffer = self.buf;
+        bind.index_buffer_offset = self.offset;
+    }
+}
+
+/// [`ResourceUsage::Stream`] buffer that is sub-allocated every frame and grows on overflow
+#[derive(Debug)]
+pub struct StreamBuffer {
+    buf: Buffer,
+    ty: BufferType,
+    label: String,
+    usage: Usage,
+}
+
+/// Allocation bookkeeping of a [`StreamBuffer`], apart from `sokol_gfx.h`
+#[derive(Debug)]
+struct Usage {
+    /// Size of the buffer in bytes
+    capacity: usize,
+    /// Frame of the last allocation
+    frame: u64,
+    /// Bytes allocated in the frame
+    used: usize,
+    /// Bytes requested in the frame (including refused ones)
+    requested: usize,
+    /// Maximum bytes requested in a frame
+    peak: usize,
+    overflowed: bool,
+}
+
+impl Usage {
+    fn new(capacity: usize, frame: u64) -> Self {
+        Self {
+            capacity,
+            frame,
+            used: 0,
+            requested: 0,
+            peak: 0,
+            overflowed: false,
+        }
+    }
+
+    fn peak(&self) -> usize {
+        self.peak.max(self.requested)
+    }
+
+    /// Resets the allocation on a new frame. Returns the new capacity if the buffer overflowed in
+    /// the last frame and has to grow.
+    fn maintain(&mut self, frame: u64) -> Option<usize> {
+        if frame == self.frame {
+            return None;
+        }
+
+        let grow = if self.overflowed {
+            let capacity = self.requested.next_power_of_two().max(self.capacity * 2);
+            self.capacity = capacity;
+            Some(capacity)
+        } else {
+            None
+        };
+
+        self.peak = self.peak.max(self.requested);
+        self.frame = frame;
+        self.used = 0;
+        self.requested = 0;
+        self.overflowed = false;
+
+        grow
+    }
+
+    /// Requests `n_bytes`. Returns the aligned size if they fit in this frame.
+    fn request(&mut self, n_bytes: usize) -> Option<usize> {
+        // `sokol_gfx.h` aligns appended data to 4 bytes
+        let n_aligned = (n_bytes + 3) & !3;
+        self.requested += n_aligned;
+
+        if self.overflowed || self.used + n_aligned > self.capacity {
+            self.overflowed = true;
+            None
+        } else {
+            Some(n_aligned)
+        }
+    }
+}
+
+impl Drop for StreamBuffer {
+    fn drop(&mut self) {
+        if unsafe { ffi::sg_isvalid() } {
+            Buffer::destroy(self.buf);
+        }
+    }
+}
+
+impl StreamBuffer {
+    /// Creates a stream buffer of `capacity` bytes
+    pub fn new(ty: BufferType, capacity: usize, label: &str) -> Self {
+        Self {
+            buf: Self::create(ty, capacity, label),
+            ty,
+            label: label.to_string(),
+            usage: Usage::new(capacity, update::frame()),
+        }
+    }
+
+    pub fn vertex(capacity: usize, label: &str) -> Self {
+        Self::new(BufferType::Vertex, capacity, label)
+    }
+
+    pub fn index(capacity: usize, label: &str) -> Self {
+        Self::new(BufferType::Index, capacity, label)
+    }
+
+    fn create(ty: BufferType, capacity: usize, label: &str) -> Buffer {
+        let desc =
+            unsafe { rg::buf_desc(std::ptr::null(), capacity, ty, ResourceUsage::Stream, label) };
+        Buffer::create(&desc)
+    }
+
+    /// Current buffer. It can be recreated on the first allocation in a frame.
+    pub fn buffer(&self) -> Buffer {
+        self.buf
+    }
+
+    /// Size of the buffer in bytes
+    pub fn capacity(&self) -> usize {
+        self.usage.capacity
+    }
+
+    /// Bytes allocated in this frame
+    pub fn used(&self) -> usize {
+        self.usage.used
+    }
+
+    /// Maximum bytes requested in a frame so far
+    pub fn peak_usage(&self) -> usize {
+        self.usage.peak()
+    }
+
+    /// If any allocation was refused in this frame
+    pub fn overflowed(&self) -> bool {
+        self.usage.overflowed
+    }
+
+    /// Resets the allocation on a new frame and grows the buffer if it overflowed
+    fn maintain(&mut self) {
+        let old_capacity = self.usage.capacity;
+        if let Some(capacity) = self.usage.maintain(update::frame()) {
+            log::info!(
+                "rokol: growing stream buffer `{}`: {} -> {} bytes",
+                self.label,
+                old_capacity,
+                capacity
+            );
+            Buffer::destroy(self.buf);
+            self.buf = Self::create(self.ty, capacity, &self.label);
+        }
+    }
+
+    /// Appends `items` to the buffer. Returns `None` if they don't fit in this frame (then the
+    /// buffer grows next frame).
+    pub fn alloc<T: Pod>(&mut self, items: &[T]) -> Option<StreamRange> {
+        self.maintain();
+
+        let bytes = buffer::as_bytes(items);
+        let n_aligned = self.usage.request(bytes.len())?;
+
+        let offset = match update::append_buffer(self.buf, bytes) {
+            Ok(offset) => offset,
+            Err(err) => {
+                log::warn!("rokol: stream buffer `{}`: {}", self.label, err);
+                return None;
+            }
+        };
+
+        if unsafe { ffi::sg_query_buffer_overflow(self.buf) } {
+            // should not happen, but don't let the following draws be dropped silently
+            self.usage.overflowed = true;
+            return None;
+        }
+
+        self.usage.used += n_aligned;
+        Some(StreamRange {
+            buf: self.buf,
+            offset,
+            len: items.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grow_on_overflow() {
+        let mut usage = Usage::new(16, 0);
+        assert_eq!(usage.maintain(0), None);
+
+        let n = usage.request(10).unwrap();
+        assert_eq!(n, 12, "allocations are aligned to 4 bytes");
+        usage.used += n;
+
+        // refused, and so is anything after it in this frame
+        assert_eq!(usage.request(8), None);
+        assert_eq!(usage.request(1), None);
+        assert!(usage.overflowed);
+        assert_eq!(usage.capacity, 16);
+        assert_eq!(usage.peak(), 24);
+
+        // grows on the next frame
+        assert_eq!(usage.maintain(1), Some(32));
+        assert_eq!(usage.capacity, 32);
+        assert!(!usage.overflowed);
+
+        let n = usage.request(24).unwrap();
+        usage.used += n;
+        assert_eq!(usage.used, 24);
+        assert_eq!(usage.peak(), 24);
+
+        // doesn't grow without overflow
+        assert_eq!(usage.maintain(2), None);
+        assert_eq!(usage.capacity, 32);
+        assert_eq!(usage.used, 0);
+    }
+}
@@ -0,0 +1,372 @@
+/*!
+Trace hooks ([`TraceHooks`]) as a Rust trait
+
+Implement [`Tracer`] and [`install`] it to observe every `sokol_gfx.h` call, including calls made
+through the raw FFI. Installed tracers are kept in a chain: hooks installed before the first
+tracer are called first, and then the tracers in order of installation. Tracers can be uninstalled
+in any order.
+
+Trace hooks can be installed only after [`crate::gfx::setup`].
+*/
+
+use std::{
+    cell::RefCell,
+    ffi::{c_void, CStr},
+    marker::PhantomData,
+    os::raw::{c_char, c_int},
+};
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{
+    Bindings, Buffer, BufferDesc, Image, ImageData, ImageDesc, Pass, PassDesc, Pipeline,
+    PipelineDesc, Shader, ShaderDesc, ShaderStage, TraceHooks,
+};
+
+/// Error notification from `sokol_gfx.h` (the `err_*` trace hooks)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceError {
+    BufferPoolExhausted,
+    ImagePoolExhausted,
+    ShaderPoolExhausted,
+    PipelinePoolExhausted,
+    PassPoolExhausted,
+    ContextMismatch,
+    PassInvalid,
+    DrawInvalid,
+    BindingsInvalid,
+}
+
+/// Rust callbacks for [`TraceHooks`]
+///
+/// Every method does nothing by default. Arguments are the same as the corresponding `sokol_gfx.h`
+/// function.
+#[allow(unused_variables)]
+pub trait Tracer {
+    fn reset_state_cache(&mut self) {}
+
+    fn make_buffer(&mut self, desc: &BufferDesc, result: Buffer) {}
+    fn make_image(&mut self, desc: &ImageDesc, result: Image) {}
+    fn make_shader(&mut self, desc: &ShaderDesc, result: Shader) {}
+    fn make_pipeline(&mut self, desc: &PipelineDesc, result: Pipeline) {}
+    fn make_pass(&mut self, desc: &PassDesc, result: Pass) {}
+
+    fn destroy_buffer(&mut self, buf: Buffer) {}
+    fn destroy_image(&mut self, img: Image) {}
+    fn destroy_shader(&mut self, shd: Shader) {}
+    fn destroy_pipeline(&mut self, pip: Pipeline) {}
+    fn destroy_pass(&mut self, pass: Pass) {}
+
+    fn update_buffer(&mut self, buf: Buffer, data: &[u8]) {}
+    fn update_image(&mut self, img: Image, data: &ImageData) {}
+    /// `offset` is the return value of `sg_append_buffer`
+    fn append_buffer(&mut self, buf: Buffer, data: &[u8], offset: i32) {}
+
+    fn begin_default_pass(&mut self, pa: &ffi::sg_pass_action, w: i32, h: i32) {}
+    fn begin_pass(&mut self, pass: Pass, pa: &ffi::sg_pass_action) {}
+    fn apply_viewport(&mut self, x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {}
+    fn apply_scissor_rect(&mut self, x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {}
+    fn apply_pipeline(&mut self, pip: Pipeline) {}
+    fn apply_bindings(&mut self, bind: &Bindings) {}
+    fn apply_uniforms(&mut self, stage: ShaderStage, ub_index: i32, data: &[u8]) {}
+    fn draw(&mut self, base_elem: i32, n_elems: i32, n_instances: i32) {}
+    fn end_pass(&mut self) {}
+    fn commit(&mut self) {}
+
+    fn alloc_buffer(&mut self, result: Buffer) {}
+    fn alloc_image(&mut self, result: Image) {}
+    fn alloc_shader(&mut self, result: Shader) {}
+    fn alloc_pipeline(&mut self, result: Pipeline) {}
+    fn alloc_pass(&mut self, result: Pass) {}
+
+    fn dealloc_buffer(&mut self, buf: Buffer) {}
+    fn dealloc_image(&mut self, img: Image) {}
+    fn dealloc_shader(&mut self, shd: Shader) {}
+    fn dealloc_pipeline(&mut self, pip: Pipeline) {}
+    fn dealloc_pass(&mut self, pass: Pass) {}
+
+    fn init_buffer(&mut self, buf: Buffer, desc: &BufferDesc) {}
+    fn init_image(&mut self, img: Image, desc: &ImageDesc) {}
+    fn init_shader(&mut self, shd: Shader, desc: &ShaderDesc) {}
+    fn init_pipeline(&mut self, pip: Pipeline, desc: &PipelineDesc) {}
+    fn init_pass(&mut self, pass: Pass, desc: &PassDesc) {}
+
+    fn uninit_buffer(&mut self, buf: Buffer) {}
+    fn uninit_image(&mut self, img: Image) {}
+    fn uninit_shader(&mut self, shd: Shader) {}
+    fn uninit_pipeline(&mut self, pip: Pipeline) {}
+    fn uninit_pass(&mut self, pass: Pass) {}
+
+    fn fail_buffer(&mut self, buf: Buffer) {}
+    fn fail_image(&mut self, img: Image) {}
+    fn fail_shader(&mut self, shd: Shader) {}
+    fn fail_pipeline(&mut self, pip: Pipeline) {}
+    fn fail_pass(&mut self, pass: Pass) {}
+
+    fn push_debug_group(&mut self, name: &str) {}
+    fn pop_debug_group(&mut self) {}
+
+    fn error(&mut self, err: TraceError) {}
+}
+
+/// Installs raw trace hooks and returns the previous ones
+///
+/// Hooks of [`install`]ed tracers are replaced, so restore the returned hooks before any
+/// [`TraceHandle`] is dropped.
+pub fn install_trace_hooks(hooks: &TraceHooks) -> TraceHooks {
+    unsafe { ffi::sg_install_trace_hooks(hooks) }
+}
+
+/// Installs a [`Tracer`] after the current tracers
+///
+/// The tracer is uninstalled when the returned handle is dropped. Handles can be dropped in any
+/// order.
+pub fn install<T: Tracer>(tracer: T) -> TraceHandle<T> {
+    let tracer = Box::new(RefCell::new(tracer));
+    let user_data = tracer.as_ref() as *const RefCell<T> as *mut c_void;
+    self::register(tracer::make_hooks::<T>(user_data));
+
+    TraceHandle {
+        tracer,
+        _not_send: PhantomData,
+    }
+}
+
+/// Installed [`Tracer`]. Uninstalls the tracer on drop
+///
+/// Trace hooks are called on the thread that uses `sokol_gfx.h`, so the handle can't be sent to
+/// other threads.
+///
+/// The tracer is mutated by the hooks, so it's only accessed through closures. Calls to
+/// `sokol_gfx.h` made inside the closures are not traced by this tracer.
+#[derive(Debug)]
+pub struct TraceHandle<T: Tracer> {
+    /// Give fixed memory location
+    tracer: Box<RefCell<T>>,
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl<T: Tracer> TraceHandle<T> {
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.tracer.borrow())
+    }
+
+    pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.tracer.borrow_mut())
+    }
+
+    fn user_data(&self) -> *mut c_void {
+        self.tracer.as_ref() as *const RefCell<T> as *mut c_void
+    }
+}
+
+impl<T: Tracer> Drop for TraceHandle<T> {
+    fn drop(&mut self) {
+        self::unregister(self.user_data());
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Registry
+
+/// Hooks of the installed tracers. `sokol_gfx.h` only sees the dispatcher hooks, so the order of
+/// uninstallation doesn't matter.
+#[derive(Debug, Default)]
+struct Chain {
+    /// Hooks installed before the first tracer. They're called before the tracers
+    base: TraceHooks,
+    /// Called in order of installation
+    tracers: Vec<TraceHooks>,
+}
+
+thread_local! {
+    static CHAIN: RefCell<Chain> = RefCell::new(Chain::default());
+}
+
+/// Adds hooks to the chain. They're identified by their `user_data`.
+fn register(hooks: TraceHooks) {
+    CHAIN.with(|chain| {
+        let mut chain = chain.borrow_mut();
+        if chain.tracers.is_empty() {
+            chain.base = self::install_trace_hooks(&dispatch::make_hooks());
+        }
+        chain.tracers.push(hooks);
+    });
+}
+
+/// Removes hooks from the chain. The base hooks are restored when the chain gets empty.
+fn unregister(user_data: *mut c_void) {
+    CHAIN.with(|chain| {
+        let mut chain = chain.borrow_mut();
+        let len = chain.tracers.len();
+        chain.tracers.retain(|hooks| hooks.user_data != user_data);
+        if len == chain.tracers.len() || !chain.tracers.is_empty() {
+            return;
+        }
+
+        let base = std::mem::take(&mut chain.base);
+        // `sokol_gfx.h` can't be called after `sg_shutdown`
+        if unsafe { ffi::sg_isvalid() } {
+            self::install_trace_hooks(&base);
+        }
+    });
+}
+
+// --------------------------------------------------------------------------------
+// C callbacks
+
+unsafe fn slice<'a>(range: *const ffi::sg_range) -> &'a [u8] {
+    let range = &*range;
+    if range.ptr.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(range.ptr as *const u8, range.size as usize)
+    }
+}
+
+unsafe fn str<'a>(name: *const c_char) -> &'a str {
+    if name.is_null() {
+        ""
+    } else {
+        CStr::from_ptr(name)
+            .to_str()
+            .unwrap_or("<non UTF-8 string>")
+    }
+}
+
+/// Generates C callbacks of [`Tracer`]s and the dispatcher that calls the chain
+macro_rules! impl_hooks {
+    ($(
+        fn $hook:ident($($arg:ident: $ty:ty),*) => |$me:ident| $call:expr;
+    )*) => {
+        mod tracer {
+            use super::*;
+
+            $(
+                pub unsafe extern "C" fn $hook<T: Tracer>($($arg: $ty,)* user_data: *mut c_void) {
+                    let cell = &*(user_data as *const RefCell<T>);
+                    // the user is accessing the tracer via `TraceHandle::with*`
+                    if let Ok(mut me) = cell.try_borrow_mut() {
+                        let $me = &mut *me;
+                        $call;
+                    }
+                }
+            )*
+
+            pub fn make_hooks<T: Tracer>(user_data: *mut c_void) -> TraceHooks {
+                TraceHooks {
+                    user_data,
+                    $($hook: Some($hook::<T>),)*
+                }
+            }
+        }
+
+        mod dispatch {
+            use super::*;
+
+            $(
+                pub unsafe extern "C" fn $hook($($arg: $ty,)* _user_data: *mut c_void) {
+                    let base = CHAIN.with(|c| {
+                        let base = &c.borrow().base;
+                        (base.$hook, base.user_data)
+                    });
+                    if let (Some(f), user_data) = base {
+                        f($($arg,)* user_data);
+                    }
+
+                    // look up by index every time, since hooks can uninstall tracers
+                    let mut i = 0;
+                    while let Some(hook) =
+                        CHAIN.with(|c| c.borrow().tracers.get(i).map(|h| (h.$hook, h.user_data)))
+                    {
+                        if let (Some(f), user_data) = hook {
+                            f($($arg,)* user_data);
+                        }
+                        i += 1;
+                    }
+                }
+            )*
+
+            pub fn make_hooks() -> TraceHooks {
+                TraceHooks {
+                    user_data: std::ptr::null_mut(),
+                    $($hook: Some($hook),)*
+                }
+            }
+        }
+    };
+}
+
+impl_hooks! {
+    fn reset_state_cache() => |me| me.reset_state_cache();
+
+    fn make_buffer(desc: *const ffi::sg_buffer_desc, result: Buffer) => |me| me.make_buffer(&*desc, result);
+    fn make_image(desc: *const ffi::sg_image_desc, result: Image) => |me| me.make_image(&*desc, result);
+    fn make_shader(desc: *const ffi::sg_shader_desc, result: Shader) => |me| me.make_shader(&*desc, result);
+    fn make_pipeline(desc: *const ffi::sg_pipeline_desc, result: Pipeline) => |me| me.make_pipeline(&*desc, result);
+    fn make_pass(desc: *const ffi::sg_pass_desc, result: Pass) => |me| me.make_pass(&*desc, result);
+
+    fn destroy_buffer(buf: Buffer) => |me| me.destroy_buffer(buf);
+    fn destroy_image(img: Image) => |me| me.destroy_image(img);
+    fn destroy_shader(shd: Shader) => |me| me.destroy_shader(shd);
+    fn destroy_pipeline(pip: Pipeline) => |me| me.destroy_pipeline(pip);
+    fn destroy_pass(pass: Pass) => |me| me.destroy_pass(pass);
+
+    fn update_buffer(buf: Buffer, data: *const ffi::sg_range) => |me| me.update_buffer(buf, self::slice(data));
+    fn update_image(img: Image, data: *const ffi::sg_image_data) => |me| me.update_image(img, &*data);
+    fn append_buffer(buf: Buffer, data: *const ffi::sg_range, result: c_int) => |me| me.append_buffer(buf, self::slice(data), result);
+
+    fn begin_default_pass(pa: *const ffi::sg_pass_action, w: c_int, h: c_int) => |me| me.begin_default_pass(&*pa, w, h);
+    fn begin_pass(pass: Pass, pa: *const ffi::sg_pass_action) => |me| me.begin_pass(pass, &*pa);
+    fn apply_viewport(x: c_int, y: c_int, w: c_int, h: c_int, origin_top_left: bool) => |me| me.apply_viewport(x, y, w, h, origin_top_left);
+    fn apply_scissor_rect(x: c_int, y: c_int, w: c_int, h: c_int, origin_top_left: bool) => |me| me.apply_scissor_rect(x, y, w, h, origin_top_left);
+    fn apply_pipeline(pip: Pipeline) => |me| me.apply_pipeline(pip);
+    fn apply_bindings(bind: *const ffi::sg_bindings) => |me| me.apply_bindings(&*bind);
+    fn apply_uniforms(stage: ffi::sg_shader_stage, ub_index: c_int, data: *const ffi::sg_range) => |me| me.apply_uniforms(ShaderStage::from_ffi(stage), ub_index, self::slice(data));
+    fn draw(base_elem: c_int, n_elems: c_int, n_instances: c_int) => |me| me.draw(base_elem, n_elems, n_instances);
+    fn end_pass() => |me| me.end_pass();
+    fn commit() => |me| me.commit();
+
+    fn alloc_buffer(result: Buffer) => |me| me.alloc_buffer(result);
+    fn alloc_image(result: Image) => |me| me.alloc_image(result);
+    fn alloc_shader(result: Shader) => |me| me.alloc_shader(result);
+    fn alloc_pipeline(result: Pipeline) => |me| me.alloc_pipeline(result);
+    fn alloc_pass(result: Pass) => |me| me.alloc_pass(result);
+
+    fn dealloc_buffer(buf: Buffer) => |me| me.dealloc_buffer(buf);
+    fn dealloc_image(img: Image) => |me| me.dealloc_image(img);
+    fn dealloc_shader(shd: Shader) => |me| me.dealloc_shader(shd);
+    fn dealloc_pipeline(pip: Pipeline) => |me| me.dealloc_pipeline(pip);
+    fn dealloc_pass(pass: Pass) => |me| me.dealloc_pass(pass);
+
+    fn init_buffer(buf: Buffer, desc: *const ffi::sg_buffer_desc) => |me| me.init_buffer(buf, &*desc);
+    fn init_image(img: Image, desc: *const ffi::sg_image_desc) => |me| me.init_image(img, &*desc);
+    fn init_shader(shd: Shader, desc: *const ffi::sg_shader_desc) => |me| me.init_shader(shd, &*desc);
+    fn init_pipeline(pip: Pipeline, desc: *const ffi::sg_pipeline_desc) => |me| me.init_pipeline(pip, &*desc);
+    fn init_pass(pass: Pass, desc: *const ffi::sg_pass_desc) => |me| me.init_pass(pass, &*desc);
+
+    fn uninit_buffer(buf: Buffer) => |me| me.uninit_buffer(buf);
+    fn uninit_image(img: Image) => |me| me.uninit_image(img);
+    fn uninit_shader(shd: Shader) => |me| me.uninit_shader(shd);
+    fn uninit_pipeline(pip: Pipeline) => |me| me.uninit_pipeline(pip);
+    fn uninit_pass(pass: Pass) => |me| me.uninit_pass(pass);
+
+    fn fail_buffer(buf: Buffer) => |me| me.fail_buffer(buf);
+    fn fail_image(img: Image) => |me| me.fail_image(img);
+    fn fail_shader(shd: Shader) => |me| me.fail_shader(shd);
+    fn fail_pipeline(pip: Pipeline) => |me| me.fail_pipeline(pip);
+    fn fail_pass(pass: Pass) => |me| me.fail_pass(pass);
+
+    fn push_debug_group(name: *const c_char) => |me| me.push_debug_group(self::str(name));
+    fn pop_debug_group() => |me| me.pop_debug_group();
+
+    fn err_buffer_pool_exhausted() => |me| me.error(TraceError::BufferPoolExhausted);
+    fn err_image_pool_exhausted() => |me| me.error(TraceError::ImagePoolExhausted);
+    fn err_shader_pool_exhausted() => |me| me.error(TraceError::ShaderPoolExhausted);
+    fn err_pipeline_pool_exhausted() => |me| me.error(TraceError::PipelinePoolExhausted);
+    fn err_pass_pool_exhausted() => |me| me.error(TraceError::PassPoolExhausted);
+    fn err_context_mismatch() => |me| me.error(TraceError::ContextMismatch);
+    fn err_pass_invalid() => |me| me.error(TraceError::PassInvalid);
+    fn err_draw_invalid() => |me| me.error(TraceError::DrawInvalid);
+    fn err_bindings_invalid() => |me| me.error(TraceError::BindingsInvalid);
+}
@@ -0,0 +1,372 @@
+/*!
+Safe buffer and image updates that respect Sokol's once-per-frame rule
+
+`sokol_gfx.h` allows only one `update` per frame and resource, and `update` and `append` can't be
+mixed in a frame. This module remembers resources written in the current frame (until
+[`crate::gfx::commit`]) and returns [`UpdateError`] instead of hitting a Sokol validation error.
+The `*_or_queue` variants defer the update to the next frame instead, merging updates to the same
+resource (the last one wins).
+
+Updates made via [`crate::gfx::update_buffer`] or `rokol_ffi` are not tracked.
+*/
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use rokol_ffi::gfx as ffi;
+
+use crate::gfx::{self as rg, Buffer, Image, ImageData};
+
+static FRAME: AtomicU64 = AtomicU64::new(0);
+
+/// Number of [`crate::gfx::commit`] calls
+pub fn frame() -> u64 {
+    FRAME.load(Ordering::Relaxed)
+}
+
+/// [`ImageData`] with owned data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OwnedImageData {
+    /// `(face, mip_level, data)`
+    pub subimages: Vec<(u8, u8, Vec<u8>)>,
+}
+
+impl OwnedImageData {
+    /// # Safety
+    ///
+    /// Pointers in the `data` have to be valid
+    pub unsafe fn new(data: &ImageData) -> Self {
+        let mut subimages = Vec::new();
+        for (face, mips) in data.subimage.iter().enumerate() {
+            for (mip, range) in mips.iter().enumerate() {
+                if !range.ptr.is_null() {
+                    let data =
+                        std::slice::from_raw_parts(range.ptr as *const u8, range.size as usize);
+                    subimages.push((face as u8, mip as u8, data.to_vec()));
+                }
+            }
+        }
+        Self { subimages }
+    }
+
+    /// Image data that borrows data from `self`
+    pub fn to_data(&self) -> ImageData {
+        let mut data = ImageData::default();
+        for (face, mip, bytes) in &self.subimages {
+            data.subimage[*face as usize][*mip as usize] = bytes.as_slice().into();
+        }
+        data
+    }
+}
+
+/// How a buffer was written in the current frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stamp {
+    Updated,
+    Appended,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Buffers written in the current frame
+    buffers: BTreeMap<u32, Stamp>,
+    /// Images updated in the current frame
+    images: BTreeSet<u32>,
+    /// Buffer updates deferred to the next frame
+    queued_buffers: BTreeMap<u32, Vec<u8>>,
+    /// Image updates deferred to the next frame
+    queued_images: BTreeMap<u32, OwnedImageData>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State::new());
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Violation of the once-per-frame rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateError {
+    /// The resource was already updated in this frame
+    UpdatedTwice,
+    /// The buffer was appended to in this frame
+    UpdateAfterAppend,
+    /// The buffer was updated in this frame
+    AppendAfterUpdate,
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::UpdatedTwice => "resource can be updated only once per frame",
+            Self::UpdateAfterAppend => "buffer can't be updated after append in the same frame",
+            Self::AppendAfterUpdate => "buffer can't be appended to after update in the same frame",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for UpdateError {}
+
+/// Result of `*_or_queue` functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateStatus {
+    /// Updated in this frame
+    Applied,
+    /// Will be updated after the next [`crate::gfx::commit`]
+    Queued,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            buffers: BTreeMap::new(),
+            images: BTreeSet::new(),
+            queued_buffers: BTreeMap::new(),
+            queued_images: BTreeMap::new(),
+        }
+    }
+
+    fn check_update_buffer(&self, buf: Buffer) -> Result<(), UpdateError> {
+        match self.buffers.get(&buf.id) {
+            None => Ok(()),
+            Some(Stamp::Updated) => Err(UpdateError::UpdatedTwice),
+            Some(Stamp::Appended) => Err(UpdateError::UpdateAfterAppend),
+        }
+    }
+
+    fn check_update_image(&self, img: Image) -> Result<(), UpdateError> {
+        if self.images.contains(&img.id) {
+            Err(UpdateError::UpdatedTwice)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Updates the buffer with `apply` and stamps it
+    fn update_buffer(&mut self, buf: Buffer, data: &[u8], apply: impl FnOnce(Buffer, &[u8])) {
+        apply(buf, data);
+        self.buffers.insert(buf.id, Stamp::Updated);
+    }
+
+    /// Updates the image with `apply` and stamps it
+    fn update_image(
+        &mut self,
+        img: Image,
+        data: &ImageData,
+        apply: impl FnOnce(Image, &ImageData),
+    ) {
+        apply(img, data);
+        self.images.insert(img.id);
+    }
+
+    fn update_buffer_or_queue(
+        &mut self,
+        buf: Buffer,
+        data: &[u8],
+        apply: impl FnOnce(Buffer, &[u8]),
+    ) -> UpdateStatus {
+        if self.check_update_buffer(buf).is_ok() {
+            self.update_buffer(buf, data, apply);
+            UpdateStatus::Applied
+        } else {
+            self.queued_buffers.insert(buf.id, data.to_vec());
+            UpdateStatus::Queued
+        }
+    }
+
+    fn update_image_or_queue(
+        &mut self,
+        img: Image,
+        data: &ImageData,
+        apply: impl FnOnce(Image, &ImageData),
+    ) -> UpdateStatus {
+        if self.check_update_image(img).is_ok() {
+            self.update_image(img, data, apply);
+            UpdateStatus::Applied
+        } else {
+            let data = unsafe { OwnedImageData::new(data) };
+            self.queued_images.insert(img.id, data);
+            UpdateStatus::Queued
+        }
+    }
+
+    /// Starts a new frame and applies queued updates to resources that are still alive
+    fn flush(
+        &mut self,
+        buf_alive: impl Fn(Buffer) -> bool,
+        img_alive: impl Fn(Image) -> bool,
+        mut apply_buf: impl FnMut(Buffer, &[u8]),
+        mut apply_img: impl FnMut(Image, &ImageData),
+    ) {
+        self.buffers.clear();
+        self.images.clear();
+
+        let buffers = std::mem::take(&mut self.queued_buffers);
+        for (id, data) in buffers {
+            let buf = Buffer { id };
+            // the buffer may be destroyed while waiting
+            if buf_alive(buf) {
+                self.update_buffer(buf, &data, &mut apply_buf);
+            }
+        }
+
+        let images = std::mem::take(&mut self.queued_images);
+        for (id, data) in images {
+            let img = Image { id };
+            if img_alive(img) {
+                self.update_image(img, &data.to_data(), &mut apply_img);
+            }
+        }
+    }
+}
+
+fn sg_update_buffer(buf: Buffer, data: &[u8]) {
+    unsafe {
+        rg::update_buffer(buf, data);
+    }
+}
+
+fn sg_update_image(img: Image, data: &ImageData) {
+    unsafe {
+        rg::update_image(img, data);
+    }
+}
+
+/// Overwrites a [`ResourceUsage::Dynamic`](crate::gfx::ResourceUsage::Dynamic) or
+/// [`ResourceUsage::Stream`](crate::gfx::ResourceUsage::Stream) buffer
+pub fn update_buffer(buf: Buffer, data: &[u8]) -> Result<(), UpdateError> {
+    let mut state = lock();
+    state.check_update_buffer(buf)?;
+    state.update_buffer(buf, data, self::sg_update_buffer);
+    Ok(())
+}
+
+/// Appends data to a buffer and returns the byte offset to it. Can be called multiple times per
+/// frame unless the buffer is updated.
+pub fn append_buffer(buf: Buffer, data: &[u8]) -> Result<i32, UpdateError> {
+    let mut state = lock();
+    if state.buffers.get(&buf.id) == Some(&Stamp::Updated) {
+        return Err(UpdateError::AppendAfterUpdate);
+    }
+    state.buffers.insert(buf.id, Stamp::Appended);
+    Ok(rg::append_buffer(buf, data))
+}
+
+/// Overwrites a dynamic or stream image
+pub fn update_image(img: Image, data: &ImageData) -> Result<(), UpdateError> {
+    let mut state = lock();
+    state.check_update_image(img)?;
+    state.update_image(img, data, self::sg_update_image);
+    Ok(())
+}
+
+/// Updates the buffer now or after the next commit if it's already written in this frame
+pub fn update_buffer_or_queue(buf: Buffer, data: &[u8]) -> UpdateStatus {
+    lock().update_buffer_or_queue(buf, data, self::sg_update_buffer)
+}
+
+/// Updates the image now or after the next commit if it's already updated in this frame
+pub fn update_image_or_queue(img: Image, data: &ImageData) -> UpdateStatus {
+    lock().update_image_or_queue(img, data, self::sg_update_image)
+}
+
+/// Called on [`crate::gfx::commit`]. Starts a new frame and applies queued updates.
+pub(crate) fn on_commit() {
+    FRAME.fetch_add(1, Ordering::Relaxed);
+
+    const VALID: ffi::sg_resource_state = ffi::sg_resource_state::SG_RESOURCESTATE_VALID;
+    lock().flush(
+        |buf| unsafe { ffi::sg_query_buffer_state(buf) } == VALID,
+        |img| unsafe { ffi::sg_query_image_state(img) } == VALID,
+        self::sg_update_buffer,
+        self::sg_update_image,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn queue_second_update() {
+        let mut state = State::new();
+        // updates that would be sent to `sokol_gfx.h`
+        let mut applied_bufs = Vec::new();
+        let mut applied_imgs = Vec::new();
+
+        let buf = Buffer { id: 1 };
+        let img = Image { id: 2 };
+
+        let status = state.update_buffer_or_queue(buf, &[1, 2], |b, d| {
+            applied_bufs.push((b.id, d.to_vec()));
+        });
+        assert_eq!(status, UpdateStatus::Applied);
+        assert_eq!(
+            state.check_update_buffer(buf),
+            Err(UpdateError::UpdatedTwice)
+        );
+        let status = state.update_buffer_or_queue(buf, &[3], |_, _| unreachable!());
+        assert_eq!(status, UpdateStatus::Queued);
+        // the last one wins
+        let status = state.update_buffer_or_queue(buf, &[4, 5], |_, _| unreachable!());
+        assert_eq!(status, UpdateStatus::Queued);
+
+        let pixels = [0u8; 4];
+        let mut data = ImageData::default();
+        data.subimage[0][0] = pixels.as_slice().into();
+        let status = state.update_image_or_queue(img, &data, |i, _| applied_imgs.push(i.id));
+        assert_eq!(status, UpdateStatus::Applied);
+        let status = state.update_image_or_queue(img, &data, |_, _| unreachable!());
+        assert_eq!(status, UpdateStatus::Queued);
+
+        assert_eq!(state.queued_buffers.get(&buf.id), Some(&vec![4, 5]));
+        assert_eq!(
+            state.queued_images.get(&img.id).unwrap().subimages,
+            vec![(0, 0, pixels.to_vec())]
+        );
+
+        // flushed on commit, so the buffer is already updated in the new frame
+        state.flush(
+            |_| true,
+            |_| true,
+            |b, d| applied_bufs.push((b.id, d.to_vec())),
+            |i, d| {
+                let data = unsafe { OwnedImageData::new(d) };
+                assert_eq!(data.subimages, vec![(0, 0, pixels.to_vec())]);
+                applied_imgs.push(i.id);
+            },
+        );
+        assert!(state.queued_buffers.is_empty());
+        assert!(state.queued_images.is_empty());
+        assert_eq!(applied_bufs, vec![(1, vec![1, 2]), (1, vec![4, 5])]);
+        assert_eq!(applied_imgs, vec![2, 2]);
+        assert_eq!(
+            state.check_update_buffer(buf),
+            Err(UpdateError::UpdatedTwice)
+        );
+        assert_eq!(
+            state.check_update_image(img),
+            Err(UpdateError::UpdatedTwice)
+        );
+
+        // queued updates to destroyed resources are dropped
+        let status = state.update_buffer_or_queue(buf, &[7], |_, _| unreachable!());
+        assert_eq!(status, UpdateStatus::Queued);
+        state.flush(
+            |_| false,
+            |_| false,
+            |_, _| unreachable!(),
+            |_, _| unreachable!(),
+        );
+        assert!(state.queued_buffers.is_empty());
+        assert_eq!(state.check_update_buffer(buf), Ok(()));
+    }
+}
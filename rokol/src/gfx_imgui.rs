@@ -0,0 +1,184 @@
+/*!
+`rokol::gfx` inspector with [`sokol_gfx_imgui.h`] (`impl-gfx-imgui` feature)
+
+[`GfxImGui`] shows live buffers, images, shaders, pipelines and passes, the calls captured in the
+last frame and the backend capabilities as Dear ImGui windows:
+
+```no_run
+use rokol::{gfx_imgui::GfxImGui, imgui::imgui};
+
+# let mut imgui = imgui::Context::create();
+// after `rokol::gfx::setup`:
+let mut inspector = GfxImGui::setup();
+
+// every frame:
+let ui = imgui.new_frame();
+ui.main_menu_bar(|| inspector.menu(ui));
+inspector.draw(ui);
+```
+
+The inspector records `sokol_gfx.h` calls with its own trace hooks, outside of the chain of
+[`install`]ed tracers. Set it up before installing tracers and drop it after uninstalling them.
+
+[`sokol_gfx_imgui.h`]: https://github.com/floooh/sokol/blob/master/util/sokol_gfx_imgui.h
+[`install`]: crate::gfx::trace::install
+*/
+
+use std::ptr::NonNull;
+
+use rokol_ffi::gfx_imgui as ffi;
+
+/// Windows of the inspector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Window {
+    Buffers,
+    Images,
+    Shaders,
+    Pipelines,
+    Passes,
+    /// Calls captured in the last frame
+    Capture,
+    Capabilities,
+}
+
+impl Window {
+    pub const ALL: [Self; 7] = [
+        Self::Buffers,
+        Self::Images,
+        Self::Shaders,
+        Self::Pipelines,
+        Self::Passes,
+        Self::Capture,
+        Self::Capabilities,
+    ];
+
+    /// Label in [`GfxImGui::menu`]
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Buffers => "Buffers",
+            Self::Images => "Images",
+            Self::Shaders => "Shaders",
+            Self::Pipelines => "Pipelines",
+            Self::Passes => "Passes",
+            Self::Capture => "Calls",
+            Self::Capabilities => "Capabilities",
+        }
+    }
+
+    pub fn to_ffi(self) -> ffi::rokol_sg_imgui_window {
+        use ffi::rokol_sg_imgui_window::*;
+
+        match self {
+            Self::Buffers => ROKOL_SG_IMGUI_WINDOW_BUFFERS,
+            Self::Images => ROKOL_SG_IMGUI_WINDOW_IMAGES,
+            Self::Shaders => ROKOL_SG_IMGUI_WINDOW_SHADERS,
+            Self::Pipelines => ROKOL_SG_IMGUI_WINDOW_PIPELINES,
+            Self::Passes => ROKOL_SG_IMGUI_WINDOW_PASSES,
+            Self::Capture => ROKOL_SG_IMGUI_WINDOW_CAPTURE,
+            Self::Capabilities => ROKOL_SG_IMGUI_WINDOW_CAPABILITIES,
+        }
+    }
+}
+
+/// `sg_imgui_t`. Discarded on drop
+///
+/// Drawing methods take [`imgui::Ui`] so that they're called inside an imgui frame.
+#[derive(Debug)]
+pub struct GfxImGui {
+    ctx: NonNull<ffi::sg_imgui_t>,
+}
+
+impl Drop for GfxImGui {
+    fn drop(&mut self) {
+        unsafe {
+            // the trace hooks are gone with `sokol_gfx.h`
+            if rokol_ffi::gfx::sg_isvalid() {
+                ffi::sg_imgui_discard(self.ctx.as_ptr());
+            }
+            ffi::rokol_sg_imgui_dealloc(self.ctx.as_ptr());
+        }
+    }
+}
+
+impl GfxImGui {
+    /// Sets up the inspector. Call after [`crate::gfx::setup`]; all the windows are closed.
+    pub fn setup() -> Self {
+        let ctx = unsafe { ffi::rokol_sg_imgui_alloc() };
+        let ctx = NonNull::new(ctx).expect("rokol: failed to allocate `sg_imgui_t`");
+        unsafe {
+            ffi::sg_imgui_init(ctx.as_ptr());
+        }
+        Self { ctx }
+    }
+
+    pub fn raw(&self) -> *mut ffi::sg_imgui_t {
+        self.ctx.as_ptr()
+    }
+
+    pub fn is_open(&self, window: Window) -> bool {
+        unsafe { *ffi::rokol_sg_imgui_open(self.raw(), window.to_ffi()) }
+    }
+
+    /// The `open` flag of a window
+    pub fn open_mut(&mut self, window: Window) -> &mut bool {
+        unsafe { &mut *ffi::rokol_sg_imgui_open(self.raw(), window.to_ffi()) }
+    }
+
+    pub fn set_open(&mut self, window: Window, is_open: bool) {
+        *self.open_mut(window) = is_open;
+    }
+
+    pub fn toggle(&mut self, window: Window) {
+        let open = self.open_mut(window);
+        *open = !*open;
+    }
+
+    /// Draws the open windows
+    pub fn draw(&mut self, _ui: &imgui::Ui) {
+        unsafe {
+            ffi::sg_imgui_draw(self.raw());
+        }
+    }
+
+    /// Draws a window if it's open
+    pub fn draw_window(&mut self, window: Window, _ui: &imgui::Ui) {
+        let ctx = self.raw();
+        unsafe {
+            match window {
+                Window::Buffers => ffi::sg_imgui_draw_buffers_window(ctx),
+                Window::Images => ffi::sg_imgui_draw_images_window(ctx),
+                Window::Shaders => ffi::sg_imgui_draw_shaders_window(ctx),
+                Window::Pipelines => ffi::sg_imgui_draw_pipelines_window(ctx),
+                Window::Passes => ffi::sg_imgui_draw_passes_window(ctx),
+                Window::Capture => ffi::sg_imgui_draw_capture_window(ctx),
+                Window::Capabilities => ffi::sg_imgui_draw_capabilities_window(ctx),
+            }
+        }
+    }
+
+    /// Draws the content of a window into the current imgui window
+    pub fn draw_content(&mut self, window: Window, _ui: &imgui::Ui) {
+        let ctx = self.raw();
+        unsafe {
+            match window {
+                Window::Buffers => ffi::sg_imgui_draw_buffers_content(ctx),
+                Window::Images => ffi::sg_imgui_draw_images_content(ctx),
+                Window::Shaders => ffi::sg_imgui_draw_shaders_content(ctx),
+                Window::Pipelines => ffi::sg_imgui_draw_pipelines_content(ctx),
+                Window::Passes => ffi::sg_imgui_draw_passes_content(ctx),
+                Window::Capture => ffi::sg_imgui_draw_capture_content(ctx),
+                Window::Capabilities => ffi::sg_imgui_draw_capabilities_content(ctx),
+            }
+        }
+    }
+
+    /// Adds a `sokol-gfx` menu with toggles of the windows (call inside a menu bar)
+    pub fn menu(&mut self, ui: &imgui::Ui) {
+        ui.menu("sokol-gfx", || {
+            for window in Window::ALL {
+                ui.menu_item_config(window.title())
+                    .build_with_ref(self.open_mut(window));
+            }
+        });
+    }
+}
@@ -13,10 +13,17 @@ use crate::{app, gfx};
 /// Creates [`crate::gfx::SetupDesc`] considering [`crate::app`].
 pub fn app_desc() -> rokol_ffi::gfx::sg_desc {
     let mut desc: rokol_ffi::gfx::sg_desc = Default::default();
-    desc.context = unsafe { rokol_ffi::glue::sapp_sgcontext() };
+    desc.context = self::context_desc();
     desc
 }
 
+/// For [`crate::gfx::GfxConfig::to_desc`]
+///
+/// Creates [`crate::gfx::SetupContextDesc`] considering [`crate::app`].
+pub fn context_desc() -> rokol_ffi::gfx::sg_context_desc {
+    unsafe { rokol_ffi::glue::sapp_sgcontext() }
+}
+
 /// Any error upcasted to [`Box`]
 pub type Error = Box<dyn std::error::Error>;
 
@@ -52,6 +59,8 @@ pub struct Rokol {
     pub n_max_dropped_files: u32,
     pub max_dropped_file_path_len_in_bytes: u32,
     // missing fields from Sokol: html5, ios, gl
+    /// Used by [`run_delayed`] to set up `rokol::gfx`
+    pub gfx: gfx::GfxConfig,
 }
 
 impl Default for Rokol {
@@ -71,10 +80,64 @@ impl Default for Rokol {
             enable_drag_and_drop: false,
             n_max_dropped_files: 1,
             max_dropped_file_path_len_in_bytes: 2048,
+            gfx: Default::default(),
         }
     }
 }
 
+#[cfg(feature = "impl-args")]
+impl Rokol {
+    /// Creates [`Rokol`] from startup arguments ([`crate::args`], set up with the command line
+    /// arguments if it's not set up yet). See [`Rokol::apply_arg`] for the keys.
+    pub fn from_args() -> Self {
+        if !crate::args::is_valid() {
+            crate::args::setup_env();
+        }
+
+        let mut rokol = Self::default();
+        for (key, value) in crate::args::iter() {
+            rokol.apply_arg(&key, &value);
+        }
+        rokol
+    }
+
+    /// Applies a startup argument if the key is one of:
+    ///
+    /// * `width` | `w`, `height` | `h`, `title`
+    /// * `msaa_sample_count` | `msaa`, `swap_interval`
+    /// * `is_full_screen` | `fullscreen`, `use_high_dpi` | `high_dpi`
+    ///
+    /// Returns `false` for other keys. Invalid values are logged and ignored.
+    pub fn apply_arg(&mut self, key: &str, value: &str) -> bool {
+        fn parse<T: std::str::FromStr>(key: &str, value: &str, dst: &mut T) {
+            match value.parse() {
+                Ok(x) => *dst = x,
+                Err(_) => log::warn!("rokol: invalid argument `{}={}`", key, value),
+            }
+        }
+
+        fn parse_bool(key: &str, value: &str, dst: &mut bool) {
+            match crate::args::parse_bool(value) {
+                Some(x) => *dst = x,
+                None => log::warn!("rokol: invalid argument `{}={}`", key, value),
+            }
+        }
+
+        match key {
+            "width" | "w" => parse(key, value, &mut self.w),
+            "height" | "h" => parse(key, value, &mut self.h),
+            "title" => self.title = value.to_string(),
+            "msaa_sample_count" | "msaa" => parse(key, value, &mut self.msaa_sample_count),
+            "swap_interval" => parse(key, value, &mut self.swap_interval),
+            "is_full_screen" | "fullscreen" => parse_bool(key, value, &mut self.is_full_screen),
+            "use_high_dpi" | "high_dpi" => parse_bool(key, value, &mut self.use_high_dpi),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
 impl Rokol {
     /// Runs rokol application. WARNING: you can't use `rokol::gfx` until you setup `rokol::gfx`
     /// (c.f. [`run_delayed`]).
@@ -88,6 +151,9 @@ impl Rokol {
         #[cfg(rokol_gfx = "d3d11")]
         log::info!("Rokol renderer: D3D11");
 
+        #[cfg(rokol_gfx = "dummy")]
+        log::info!("Rokol renderer: dummy");
+
         let title_cstring = CString::new(self.title.as_bytes())?;
 
         let mut desc = {
@@ -99,6 +165,7 @@ impl Rokol {
 
             desc.window_title = title_cstring.as_ptr() as *mut _;
 
+            desc.sample_count = self.msaa_sample_count as i32;
             desc.swap_interval = self.swap_interval as i32;
 
             desc.high_dpi = self.use_high_dpi;
@@ -137,7 +204,11 @@ impl Rokol {
 
 /// Runs a rokol application. It will postpone generation of our application until we setup
 /// `rokol::gfx` so that we can use `rokol::gfx` when we create our application.
+///
+/// Fails if [`Rokol::gfx`] is invalid.
 pub fn run_delayed<A: app::RApp, G: FnOnce(&Rokol) -> A>(desc: Rokol, app_gen: G) -> Result {
+    desc.gfx.validate()?;
+
     let mut runner = DelayedApp {
         desc: desc.clone(),
         app: None,
@@ -155,11 +226,72 @@ struct DelayedApp<A: app::RApp, G: FnOnce(&Rokol) -> A> {
 
 impl<A: app::RApp, G: FnOnce(&Rokol) -> A> app::RApp for DelayedApp<A, G> {
     fn init(&mut self) {
-        gfx::setup(&mut self::app_desc());
+        // validated in `run_delayed`
+        let desc = self.desc.gfx.to_desc(self::context_desc()).unwrap();
+        gfx::setup(&desc);
         self.app = Some(self.app_gen.take().unwrap()(&self.desc));
     }
 
     fn frame(&mut self) {
         self.app.as_mut().unwrap().frame();
     }
+
+    fn event(&mut self, ev: &app::Event) {
+        if let Some(app) = self.app.as_mut() {
+            app.event(ev);
+        }
+    }
+
+    fn app_event(&mut self, ev: &app::AppEvent) {
+        if let Some(app) = self.app.as_mut() {
+            app.app_event(ev);
+        }
+    }
+
+    fn cleanup(&mut self) {
+        // drop the app while `rokol::gfx` is still alive
+        if let Some(mut app) = self.app.take() {
+            app.cleanup();
+        }
+
+        // the app may not shut down `rokol::gfx` by itself
+        if unsafe { rokol_ffi::gfx::sg_isvalid() } {
+            gfx::shutdown();
+        }
+    }
+
+    #[cfg(feature = "impl-audio")]
+    fn audio_stream(
+        &mut self,
+    ) -> Option<(crate::audio::AudioDesc, Box<dyn crate::audio::AudioStream>)> {
+        self.app.as_mut().and_then(|app| app.audio_stream())
+    }
+}
+
+#[cfg(all(test, feature = "impl-args"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply_args() {
+        let mut rokol = Rokol::default();
+        for (key, value) in [
+            ("w", "1920"),
+            ("height", "1080"),
+            ("title", "Game"),
+            ("msaa", "4"),
+            ("fullscreen", "yes"),
+            ("high_dpi", "maybe"),
+        ] {
+            assert!(rokol.apply_arg(key, value));
+        }
+        assert!(!rokol.apply_arg("level", "1"));
+
+        assert_eq!([rokol.w, rokol.h], [1920, 1080]);
+        assert_eq!(rokol.title, "Game");
+        assert_eq!(rokol.msaa_sample_count, 4);
+        assert!(rokol.is_full_screen);
+        // invalid values are ignored
+        assert!(!rokol.use_high_dpi);
+    }
 }
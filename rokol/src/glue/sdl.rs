@@ -108,6 +108,13 @@ impl ResourceSettings {
             rokol_ffi::gfx::sg_setup(&desc as *const _);
         }
     }
+
+    /// Sets up `rokol::gfx` with the config merged with the context
+    pub fn init_gfx_with(&self, config: &rg::GfxConfig) -> Result<(), rg::GfxConfigError> {
+        let desc = config.to_desc(self.create_context())?;
+        rg::setup(&desc);
+        Ok(())
+    }
 }
 
 /// Set of SDL objects
@@ -153,6 +160,8 @@ pub struct Init {
     pub use_high_dpi: bool,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub settings: ResourceSettings,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gfx: rg::GfxConfig,
 }
 
 impl Default for Init {
@@ -163,6 +172,7 @@ impl Default for Init {
             h: 720,
             use_high_dpi: false,
             settings: Default::default(),
+            gfx: Default::default(),
         }
     }
 }
@@ -177,6 +187,8 @@ impl Init {
         &self,
         mut f: impl FnMut(&mut sdl2::video::WindowBuilder),
     ) -> Result<WindowHandle, String> {
+        self.gfx.validate().map_err(|e| e.to_string())?;
+
         // initialize SDL2 with selected graphics backend
         let sdl = sdl2::init()?;
         let vid = sdl.video()?;
@@ -201,7 +213,9 @@ impl Init {
         let gcx = win.gl_create_context()?;
 
         // initialize rokol with selected graphics backend
-        self.settings.init_gfx();
+        self.settings
+            .init_gfx_with(&self.gfx)
+            .map_err(|e| e.to_string())?;
 
         Ok(WindowHandle { sdl, vid, win, gcx })
     }
@@ -0,0 +1,309 @@
+/*!
+Dear ImGui renderer with [`imgui-rs`] (`imgui` feature)
+
+[`Renderer`] uploads the draw lists into stream buffers and draws them with scissor rects. Texture
+IDs are [`Image`] IDs (see [`texture_id`]); the font atlas is uploaded on creation:
+
+```no_run
+use rokol::{gfx as rg, imgui::{platform, Renderer}};
+
+let mut imgui = imgui::Context::create();
+let mut renderer = Renderer::new(&mut imgui);
+
+// every frame:
+platform::prepare_frame(imgui.io_mut(), [1280.0, 720.0], 1.0, std::time::Duration::from_millis(16));
+let ui = imgui.new_frame();
+ui.window("Debug").build(|| ui.text("Hello"));
+let draw_data = imgui.render();
+
+rg::begin_default_pass(&rg::PassAction::clear([0.0, 0.0, 0.0, 1.0]), 1280, 720);
+renderer.render(draw_data);
+rg::end_pass();
+rg::commit();
+```
+
+[`platform`] feeds `rokol::app` (or SDL) events into imgui's IO.
+
+The built-in shader is for the `glcore33` backend. Use [`Renderer::with_shader`] for other
+backends.
+
+[`imgui-rs`]: https://github.com/imgui-rs/imgui-rs
+*/
+
+pub mod platform;
+
+pub use imgui;
+
+use imgui::{internal::RawWrapper, BackendFlags, DrawCmd, DrawCmdParams, DrawData, TextureId};
+
+use crate as rokol;
+
+use crate::{
+    gfx::{
+        self as rg, BakedResource, Bindings, BlendFactor, Image, LayoutDesc, Shader, StreamBuffer,
+    },
+    ui::UiPipeline,
+};
+
+/// Vertex of the imgui shader (same layout as [`imgui::DrawVert`])
+#[derive(LayoutDesc, Debug, Clone, Copy, Default, PartialEq)]
+#[repr(C)]
+pub struct ImGuiVertex {
+    pub pos: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [u8; 4],
+}
+
+// `#[repr(C)]` with the same fields as `ImGuiVertex`
+unsafe impl rg::Pod for imgui::DrawVert {}
+
+/// [`TextureId`] of an [`Image`] for `imgui::Image` and friends
+pub fn texture_id(img: Image) -> TextureId {
+    TextureId::new(img.id as usize)
+}
+
+/// [`Image`] of a [`TextureId`] made with [`texture_id`]
+pub fn texture_image(id: TextureId) -> Image {
+    Image { id: id.id() as u32 }
+}
+
+/// Column-major orthographic projection of the imgui display rectangle
+fn projection(pos: [f32; 2], size: [f32; 2]) -> [f32; 16] {
+    let (l, r) = (pos[0], pos[0] + size[0]);
+    let (t, b) = (pos[1], pos[1] + size[1]);
+    #[rustfmt::skip]
+    let m = [
+        2.0 / (r - l), 0.0, 0.0, 0.0,
+        0.0, 2.0 / (t - b), 0.0, 0.0,
+        0.0, 0.0, -1.0, 0.0,
+        (r + l) / (l - r), (t + b) / (b - t), 0.0, 1.0,
+    ];
+    m
+}
+
+/// Creates the built-in imgui shader (`glcore33`)
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+pub fn imgui_shader() -> Shader {
+    crate::ui::shader("rokol-imgui-shader")
+}
+
+/// Draws [`DrawData`] with stream vertex and index buffers
+#[derive(Debug)]
+pub struct Renderer {
+    pip: UiPipeline,
+    vbuf: StreamBuffer,
+    ibuf: StreamBuffer,
+    font_img: Image,
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if unsafe { rokol::ffi::gfx::sg_isvalid() } {
+            Image::destroy(self.font_img);
+        }
+    }
+}
+
+impl Renderer {
+    /// Creates a renderer with the built-in shader and uploads the font atlas
+    #[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+    pub fn new(ctx: &mut imgui::Context) -> Self {
+        Self::with_shader(ctx, self::imgui_shader())
+    }
+
+    /// Creates a renderer with a shader compatible with [`ImGuiVertex`], a `mat4` uniform block in
+    /// the vertex stage and one 2D image in the fragment stage. The shader is destroyed with the
+    /// renderer.
+    pub fn with_shader(ctx: &mut imgui::Context, shd: Shader) -> Self {
+        assert_eq!(
+            std::mem::size_of::<ImGuiVertex>(),
+            std::mem::size_of::<imgui::DrawVert>()
+        );
+
+        let pip = UiPipeline::create::<ImGuiVertex, imgui::DrawIdx>(
+            shd,
+            rg::BlendState {
+                src_factor_rgb: BlendFactor::SrcAlpha.to_ffi(),
+                dst_factor_rgb: BlendFactor::OneMinusSrcAlpha.to_ffi(),
+                ..Default::default()
+            },
+            "rokol-imgui-pipeline",
+        );
+
+        let vbuf = StreamBuffer::vertex(
+            std::mem::size_of::<ImGuiVertex>() * 16 * 1024,
+            "rokol-imgui-vertices",
+        );
+        let ibuf = StreamBuffer::index(
+            std::mem::size_of::<imgui::DrawIdx>() * 32 * 1024,
+            "rokol-imgui-indices",
+        );
+
+        ctx.set_renderer_name(Some(format!("rokol {}", env!("CARGO_PKG_VERSION"))));
+        ctx.io_mut().backend_flags |= BackendFlags::RENDERER_HAS_VTX_OFFSET;
+
+        let mut renderer = Self {
+            pip,
+            vbuf,
+            ibuf,
+            font_img: Image::default(),
+        };
+        renderer.reload_font_texture(ctx);
+        renderer
+    }
+
+    /// The font atlas texture
+    pub fn font_image(&self) -> Image {
+        self.font_img
+    }
+
+    /// Uploads the font atlas again (call after adding fonts)
+    pub fn reload_font_texture(&mut self, ctx: &mut imgui::Context) {
+        if self.font_img.id != 0 {
+            Image::destroy(self.font_img);
+        }
+
+        let fonts = ctx.fonts();
+        let tex = fonts.build_rgba32_texture();
+
+        let mut desc = rg::ImageDesc {
+            type_: rg::ImageType::Dim2.to_ffi(),
+            width: tex.width as i32,
+            height: tex.height as i32,
+            pixel_format: rg::PixelFormat::Rgba8.to_ffi(),
+            min_filter: rg::Filter::Linear.to_ffi(),
+            mag_filter: rg::Filter::Linear.to_ffi(),
+            wrap_u: rg::Wrap::ClampToEdge.to_ffi(),
+            wrap_v: rg::Wrap::ClampToEdge.to_ffi(),
+            label: rg::intern_label("rokol-imgui-font"),
+            ..Default::default()
+        };
+        desc.data.subimage[0][0] = tex.data.into();
+
+        self.font_img = Image::create(&desc);
+        fonts.tex_id = self::texture_id(self.font_img);
+    }
+
+    /// Draws the draw lists. Must be called inside a rendering pass covering the framebuffer.
+    pub fn render(&mut self, draw_data: &DrawData) {
+        let [fb_w, fb_h] = [
+            draw_data.display_size[0] * draw_data.framebuffer_scale[0],
+            draw_data.display_size[1] * draw_data.framebuffer_scale[1],
+        ];
+        if fb_w <= 0.0 || fb_h <= 0.0 {
+            return;
+        }
+
+        let transform = self::projection(draw_data.display_pos, draw_data.display_size);
+        self.pip.apply(&transform);
+
+        let clip_off = draw_data.display_pos;
+        let clip_scale = draw_data.framebuffer_scale;
+
+        for draw_list in draw_data.draw_lists() {
+            let (vs, is) = match (
+                self.vbuf.alloc(draw_list.vtx_buffer()),
+                self.ibuf.alloc(draw_list.idx_buffer()),
+            ) {
+                (Some(vs), Some(is)) => (vs, is),
+                _ => {
+                    log::warn!(
+                        "rokol: imgui draw lists overflowed (the buffers grow on next frame)"
+                    );
+                    break;
+                }
+            };
+
+            let mut bind = Bindings::default();
+            vs.set_vertex(&mut bind, 0);
+            is.set_index(&mut bind);
+            // force applying the bindings on the first command
+            let mut last = (TextureId::new(usize::MAX), usize::MAX);
+
+            for cmd in draw_list.commands() {
+                match cmd {
+                    DrawCmd::Elements {
+                        count,
+                        cmd_params:
+                            DrawCmdParams {
+                                clip_rect,
+                                texture_id,
+                                vtx_offset,
+                                idx_offset,
+                            },
+                    } => {
+                        let x0 = ((clip_rect[0] - clip_off[0]) * clip_scale[0]).max(0.0);
+                        let y0 = ((clip_rect[1] - clip_off[1]) * clip_scale[1]).max(0.0);
+                        let x1 = ((clip_rect[2] - clip_off[0]) * clip_scale[0]).min(fb_w);
+                        let y1 = ((clip_rect[3] - clip_off[1]) * clip_scale[1]).min(fb_h);
+                        if x1 <= x0 || y1 <= y0 {
+                            continue;
+                        }
+                        rg::scissor_f(x0, y0, x1 - x0, y1 - y0);
+
+                        if last != (texture_id, vtx_offset) {
+                            bind.fs_images[0] = self::texture_image(texture_id);
+                            bind.vertex_buffer_offsets[0] = vs.offset
+                                + (vtx_offset * std::mem::size_of::<ImGuiVertex>()) as i32;
+                            rg::apply_bindings(&bind);
+                            last = (texture_id, vtx_offset);
+                        }
+
+                        rg::draw(idx_offset as u32, count as u32, 1);
+                    }
+                    DrawCmd::ResetRenderState => {
+                        self.pip.apply(&transform);
+                        last = (TextureId::new(usize::MAX), usize::MAX);
+                    }
+                    DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+                        callback(draw_list.raw(), raw_cmd);
+                    },
+                }
+            }
+        }
+
+        rg::scissor(0, 0, fb_w as u32, fb_h as u32);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Column-major matrix times a 2D point
+    fn transform(m: &[f32; 16], p: [f32; 2]) -> [f32; 2] {
+        [
+            m[0] * p[0] + m[4] * p[1] + m[12],
+            m[1] * p[0] + m[5] * p[1] + m[13],
+        ]
+    }
+
+    #[test]
+    fn projection() {
+        let m = self::projection([10.0, 20.0], [100.0, 50.0]);
+        let cases = [
+            ([10.0, 20.0], [-1.0, 1.0]),
+            ([110.0, 70.0], [1.0, -1.0]),
+            ([60.0, 45.0], [0.0, 0.0]),
+        ];
+        for (p, ndc) in cases {
+            let [x, y] = self::transform(&m, p);
+            assert!(
+                (x - ndc[0]).abs() < 1e-5 && (y - ndc[1]).abs() < 1e-5,
+                "{:?}",
+                p
+            );
+        }
+        assert_eq!(m[15], 1.0);
+    }
+
+    #[test]
+    fn texture_id_roundtrip() {
+        for id in [0, 1, 0x0001_0002, u32::MAX] {
+            let img = Image { id };
+            let tex_id = self::texture_id(img);
+            assert_eq!(tex_id.id(), id as usize);
+            assert_eq!(self::texture_image(tex_id), img);
+        }
+    }
+}
@@ -0,0 +1,551 @@
+/*!
+Platform adapters that feed window events into imgui's [`Io`]
+
+Call [`prepare_frame`] before `imgui::Context::new_frame` and pass every event to
+[`handle_event`] (`rokol::app`) or [`handle_sdl_event`] (`sdl2`). They return `true` if imgui
+wants to capture the event, so that the game can skip it.
+*/
+
+use std::time::Duration;
+
+use imgui::{Io, Key, MouseButton};
+
+/// Updates the display size (in logical pixels), the framebuffer scale (DPI scale) and the delta
+/// time
+pub fn prepare_frame(io: &mut Io, size: [f32; 2], fb_scale: f32, dt: Duration) {
+    io.display_size = size;
+    io.display_framebuffer_scale = [fb_scale, fb_scale];
+    // imgui asserts positive delta time
+    io.update_delta_time(dt.max(Duration::from_micros(1)));
+}
+
+/// [`prepare_frame`] with the `rokol::app` window
+#[cfg(feature = "impl-app")]
+pub fn prepare_frame_app(io: &mut Io) {
+    let scale = crate::app::dpi_scale().max(1e-3);
+    let [w, h] = crate::app::size_f();
+    let dt = Duration::from_secs_f64(crate::app::frame_duration().max(0.0));
+    self::prepare_frame(io, [w / scale, h / scale], scale, dt);
+}
+
+#[cfg(any(feature = "impl-app", feature = "sdl2"))]
+fn add_focus_event(io: &mut Io, focused: bool) {
+    use imgui::internal::RawCast;
+
+    // not wrapped by `imgui` 0.11
+    unsafe {
+        imgui::sys::ImGuiIO_AddFocusEvent(io.raw_mut(), focused);
+    }
+}
+
+fn add_mods(io: &mut Io, ctrl: bool, shift: bool, alt: bool, super_: bool) {
+    io.add_key_event(Key::ModCtrl, ctrl);
+    io.add_key_event(Key::ModShift, shift);
+    io.add_key_event(Key::ModAlt, alt);
+    io.add_key_event(Key::ModSuper, super_);
+}
+
+// --------------------------------------------------------------------------------
+// `rokol::app`
+
+/// Feeds a `rokol::app` event. Returns `true` if imgui wants to capture it.
+#[cfg(feature = "impl-app")]
+pub fn handle_event(io: &mut Io, ev: &crate::app::Event) -> bool {
+    let scale = crate::app::dpi_scale().max(1e-3);
+    self::handle_event_scaled(io, ev, scale)
+}
+
+/// [`handle_event`] with the DPI scale of the window
+#[cfg(feature = "impl-app")]
+fn handle_event_scaled(io: &mut Io, ev: &crate::app::Event, scale: f32) -> bool {
+    use crate::app::{EventType, Mod, Mouse};
+
+    let mods = Mod::from_bits_truncate(ev.modifiers);
+
+    match EventType::from_ffi(ev.type_) {
+        EventType::KeyDown | EventType::KeyUp => {
+            let down = EventType::from_ffi(ev.type_) == EventType::KeyDown;
+            self::add_mods(
+                io,
+                mods.contains(Mod::CONTROL),
+                mods.contains(Mod::SHIFT),
+                mods.contains(Mod::ALT),
+                mods.contains(Mod::SUPER),
+            );
+            if let Some(key) = self::imgui_key(crate::app::Key::from_ffi(ev.key_code)) {
+                io.add_key_event(key, down);
+            }
+            io.want_capture_keyboard
+        }
+        EventType::Char => {
+            if let Some(c) = char::from_u32(ev.char_code) {
+                if !c.is_control() {
+                    io.add_input_character(c);
+                }
+            }
+            io.want_capture_keyboard
+        }
+        EventType::MouseDown | EventType::MouseUp => {
+            let down = EventType::from_ffi(ev.type_) == EventType::MouseDown;
+            io.add_mouse_pos_event([ev.mouse_x / scale, ev.mouse_y / scale]);
+            let button = match Mouse::from_ffi(ev.mouse_button) {
+                Mouse::Left => MouseButton::Left,
+                Mouse::Right => MouseButton::Right,
+                Mouse::Middle => MouseButton::Middle,
+                Mouse::Invalid => return false,
+            };
+            io.add_mouse_button_event(button, down);
+            io.want_capture_mouse
+        }
+        EventType::MouseMove => {
+            io.add_mouse_pos_event([ev.mouse_x / scale, ev.mouse_y / scale]);
+            io.want_capture_mouse
+        }
+        EventType::MouseScroll => {
+            io.add_mouse_wheel_event([ev.scroll_x, ev.scroll_y]);
+            io.want_capture_mouse
+        }
+        EventType::MouseLeave => {
+            io.add_mouse_pos_event([f32::MIN, f32::MIN]);
+            false
+        }
+        EventType::Focused => {
+            self::add_focus_event(io, true);
+            false
+        }
+        EventType::Unocused => {
+            // release keys held while the window loses focus
+            self::add_mods(io, false, false, false, false);
+            self::add_focus_event(io, false);
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Maps a `rokol::app` key to an imgui key
+#[cfg(feature = "impl-app")]
+pub fn imgui_key(key: crate::app::Key) -> Option<Key> {
+    use crate::app::Key as K;
+
+    Some(match key {
+        K::Tab => Key::Tab,
+        K::Left => Key::LeftArrow,
+        K::Right => Key::RightArrow,
+        K::Up => Key::UpArrow,
+        K::Down => Key::DownArrow,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Backspace => Key::Backspace,
+        K::Space => Key::Space,
+        K::Enter => Key::Enter,
+        K::Escape => Key::Escape,
+        K::LeftControl => Key::LeftCtrl,
+        K::LeftShift => Key::LeftShift,
+        K::LeftAlt => Key::LeftAlt,
+        K::LeftSuper => Key::LeftSuper,
+        K::RightControl => Key::RightCtrl,
+        K::RightShift => Key::RightShift,
+        K::RightAlt => Key::RightAlt,
+        K::RightSuper => Key::RightSuper,
+        K::Menu => Key::Menu,
+        K::Kbd0 => Key::Alpha0,
+        K::Kbd1 => Key::Alpha1,
+        K::Kbd2 => Key::Alpha2,
+        K::Kbd3 => Key::Alpha3,
+        K::Kbd4 => Key::Alpha4,
+        K::Kbd5 => Key::Alpha5,
+        K::Kbd6 => Key::Alpha6,
+        K::Kbd7 => Key::Alpha7,
+        K::Kbd8 => Key::Alpha8,
+        K::Kbd9 => Key::Alpha9,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::Apostrophe => Key::Apostrophe,
+        K::Comma => Key::Comma,
+        K::Minus => Key::Minus,
+        K::Period => Key::Period,
+        K::Slash => Key::Slash,
+        K::Semicolon => Key::Semicolon,
+        K::Equal => Key::Equal,
+        K::LeftBracket => Key::LeftBracket,
+        K::Backslash => Key::Backslash,
+        K::RightBracket => Key::RightBracket,
+        K::GraveAccent => Key::GraveAccent,
+        K::CapsLock => Key::CapsLock,
+        K::ScrollLock => Key::ScrollLock,
+        K::NumLock => Key::NumLock,
+        K::PrintScreen => Key::PrintScreen,
+        K::Pause => Key::Pause,
+        K::KP0 => Key::Keypad0,
+        K::KP1 => Key::Keypad1,
+        K::KP2 => Key::Keypad2,
+        K::KP3 => Key::Keypad3,
+        K::KP4 => Key::Keypad4,
+        K::KP5 => Key::Keypad5,
+        K::KP6 => Key::Keypad6,
+        K::KP7 => Key::Keypad7,
+        K::KP8 => Key::Keypad8,
+        K::KP9 => Key::Keypad9,
+        K::KPDecimal => Key::KeypadDecimal,
+        K::KPDivide => Key::KeypadDivide,
+        K::KPMultiply => Key::KeypadMultiply,
+        K::KPSubtract => Key::KeypadSubtract,
+        K::KPAdd => Key::KeypadAdd,
+        K::KPEnter => Key::KeypadEnter,
+        K::KPEqual => Key::KeypadEqual,
+        _ => return None,
+    })
+}
+
+// --------------------------------------------------------------------------------
+// SDL
+
+/// Feeds an SDL event. Mouse positions are expected in logical pixels. Returns `true` if imgui
+/// wants to capture it.
+#[cfg(feature = "sdl2")]
+pub fn handle_sdl_event(io: &mut Io, ev: &sdl2::event::Event) -> bool {
+    use sdl2::{
+        event::{Event, WindowEvent},
+        keyboard::Mod,
+        mouse::MouseButton as SdlMouse,
+    };
+
+    match ev {
+        Event::KeyDown {
+            keycode, keymod, ..
+        }
+        | Event::KeyUp {
+            keycode, keymod, ..
+        } => {
+            let down = matches!(ev, Event::KeyDown { .. });
+            self::add_mods(
+                io,
+                keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+                keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+                keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+                keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+            );
+            if let Some(key) = keycode.and_then(self::imgui_key_sdl) {
+                io.add_key_event(key, down);
+            }
+            io.want_capture_keyboard
+        }
+        Event::TextInput { text, .. } => {
+            text.chars().for_each(|c| io.add_input_character(c));
+            io.want_capture_keyboard
+        }
+        Event::MouseButtonDown { mouse_btn, .. } | Event::MouseButtonUp { mouse_btn, .. } => {
+            let down = matches!(ev, Event::MouseButtonDown { .. });
+            let button = match mouse_btn {
+                SdlMouse::Left => MouseButton::Left,
+                SdlMouse::Right => MouseButton::Right,
+                SdlMouse::Middle => MouseButton::Middle,
+                SdlMouse::X1 => MouseButton::Extra1,
+                SdlMouse::X2 => MouseButton::Extra2,
+                SdlMouse::Unknown => return false,
+            };
+            io.add_mouse_button_event(button, down);
+            io.want_capture_mouse
+        }
+        Event::MouseMotion { x, y, .. } => {
+            io.add_mouse_pos_event([*x as f32, *y as f32]);
+            io.want_capture_mouse
+        }
+        Event::MouseWheel { x, y, .. } => {
+            io.add_mouse_wheel_event([*x as f32, *y as f32]);
+            io.want_capture_mouse
+        }
+        Event::Window { win_event, .. } => {
+            match win_event {
+                WindowEvent::Leave => io.add_mouse_pos_event([f32::MIN, f32::MIN]),
+                WindowEvent::FocusGained => self::add_focus_event(io, true),
+                WindowEvent::FocusLost => {
+                    // release keys held while the window loses focus
+                    self::add_mods(io, false, false, false, false);
+                    self::add_focus_event(io, false);
+                }
+                _ => {}
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Maps an SDL keycode to an imgui key
+#[cfg(feature = "sdl2")]
+pub fn imgui_key_sdl(key: sdl2::keyboard::Keycode) -> Option<Key> {
+    use sdl2::keyboard::Keycode as K;
+
+    Some(match key {
+        K::Tab => Key::Tab,
+        K::Left => Key::LeftArrow,
+        K::Right => Key::RightArrow,
+        K::Up => Key::UpArrow,
+        K::Down => Key::DownArrow,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Backspace => Key::Backspace,
+        K::Space => Key::Space,
+        K::Return => Key::Enter,
+        K::Escape => Key::Escape,
+        K::LCtrl => Key::LeftCtrl,
+        K::LShift => Key::LeftShift,
+        K::LAlt => Key::LeftAlt,
+        K::LGui => Key::LeftSuper,
+        K::RCtrl => Key::RightCtrl,
+        K::RShift => Key::RightShift,
+        K::RAlt => Key::RightAlt,
+        K::RGui => Key::RightSuper,
+        K::Application => Key::Menu,
+        K::Num0 => Key::Alpha0,
+        K::Num1 => Key::Alpha1,
+        K::Num2 => Key::Alpha2,
+        K::Num3 => Key::Alpha3,
+        K::Num4 => Key::Alpha4,
+        K::Num5 => Key::Alpha5,
+        K::Num6 => Key::Alpha6,
+        K::Num7 => Key::Alpha7,
+        K::Num8 => Key::Alpha8,
+        K::Num9 => Key::Alpha9,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::Quote => Key::Apostrophe,
+        K::Comma => Key::Comma,
+        K::Minus => Key::Minus,
+        K::Period => Key::Period,
+        K::Slash => Key::Slash,
+        K::Semicolon => Key::Semicolon,
+        K::Equals => Key::Equal,
+        K::LeftBracket => Key::LeftBracket,
+        K::Backslash => Key::Backslash,
+        K::RightBracket => Key::RightBracket,
+        K::Backquote => Key::GraveAccent,
+        K::CapsLock => Key::CapsLock,
+        K::ScrollLock => Key::ScrollLock,
+        K::NumLockClear => Key::NumLock,
+        K::PrintScreen => Key::PrintScreen,
+        K::Pause => Key::Pause,
+        K::Kp0 => Key::Keypad0,
+        K::Kp1 => Key::Keypad1,
+        K::Kp2 => Key::Keypad2,
+        K::Kp3 => Key::Keypad3,
+        K::Kp4 => Key::Keypad4,
+        K::Kp5 => Key::Keypad5,
+        K::Kp6 => Key::Keypad6,
+        K::Kp7 => Key::Keypad7,
+        K::Kp8 => Key::Keypad8,
+        K::Kp9 => Key::Keypad9,
+        K::KpPeriod => Key::KeypadDecimal,
+        K::KpDivide => Key::KeypadDivide,
+        K::KpMultiply => Key::KeypadMultiply,
+        K::KpMinus => Key::KeypadSubtract,
+        K::KpPlus => Key::KeypadAdd,
+        K::KpEnter => Key::KeypadEnter,
+        K::KpEquals => Key::KeypadEqual,
+        _ => return None,
+    })
+}
+
+#[cfg(all(test, any(feature = "impl-app", feature = "sdl2")))]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Runs `f` with a fresh context. imgui allows only one context at a time, so the tests are
+    /// serialized.
+    fn with_context(f: impl FnOnce(&mut imgui::Context)) {
+        static LOCK: Mutex<()> = Mutex::new(());
+        let _guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+        let mut ctx = imgui::Context::create();
+        ctx.set_ini_filename(None);
+        ctx.fonts().build_rgba32_texture();
+        f(&mut ctx);
+    }
+
+    /// Runs a frame so that the queued input events are applied to [`Io`]
+    fn frame(ctx: &mut imgui::Context) {
+        self::prepare_frame(ctx.io_mut(), [640.0, 360.0], 1.0, Duration::from_millis(16));
+        ctx.new_frame();
+        ctx.render();
+    }
+
+    #[cfg(feature = "impl-app")]
+    #[test]
+    fn app_events() {
+        use crate::app::{Event, EventType, Key, Mod};
+
+        self::with_context(|ctx| {
+            let ctrl_down = Event {
+                type_: EventType::KeyDown.to_ffi(),
+                key_code: Key::LeftControl.to_ffi(),
+                modifiers: Mod::CONTROL.bits(),
+                ..Default::default()
+            };
+            self::handle_event_scaled(ctx.io_mut(), &ctrl_down, 2.0);
+            self::frame(ctx);
+            assert!(ctx.io().key_ctrl);
+
+            let unfocused = Event {
+                type_: EventType::Unocused.to_ffi(),
+                ..Default::default()
+            };
+            self::handle_event_scaled(ctx.io_mut(), &unfocused, 2.0);
+            self::frame(ctx);
+            assert!(!ctx.io().key_ctrl);
+
+            let mouse_move = Event {
+                type_: EventType::MouseMove.to_ffi(),
+                mouse_x: 200.0,
+                mouse_y: 100.0,
+                ..Default::default()
+            };
+            self::handle_event_scaled(ctx.io_mut(), &mouse_move, 2.0);
+            self::frame(ctx);
+            assert_eq!(ctx.io().mouse_pos, [100.0, 50.0]);
+
+            let mouse_leave = Event {
+                type_: EventType::MouseLeave.to_ffi(),
+                ..Default::default()
+            };
+            self::handle_event_scaled(ctx.io_mut(), &mouse_leave, 2.0);
+            self::frame(ctx);
+            assert_eq!(ctx.io().mouse_pos, [f32::MIN, f32::MIN]);
+        });
+    }
+
+    #[cfg(feature = "sdl2")]
+    #[test]
+    fn sdl_events() {
+        use sdl2::{
+            event::{Event, WindowEvent},
+            keyboard::{Keycode, Mod},
+            mouse::MouseState,
+        };
+
+        let window = |win_event| Event::Window {
+            timestamp: 0,
+            window_id: 0,
+            win_event,
+        };
+
+        self::with_context(|ctx| {
+            let ctrl_down = Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(Keycode::LCtrl),
+                scancode: None,
+                keymod: Mod::LCTRLMOD,
+                repeat: false,
+            };
+            self::handle_sdl_event(ctx.io_mut(), &ctrl_down);
+            self::frame(ctx);
+            assert!(ctx.io().key_ctrl);
+
+            self::handle_sdl_event(ctx.io_mut(), &window(WindowEvent::FocusLost));
+            self::frame(ctx);
+            assert!(!ctx.io().key_ctrl);
+
+            let mouse_move = Event::MouseMotion {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(0),
+                x: 100,
+                y: 50,
+                xrel: 0,
+                yrel: 0,
+            };
+            self::handle_sdl_event(ctx.io_mut(), &mouse_move);
+            self::frame(ctx);
+            assert_eq!(ctx.io().mouse_pos, [100.0, 50.0]);
+
+            self::handle_sdl_event(ctx.io_mut(), &window(WindowEvent::Leave));
+            self::frame(ctx);
+            assert_eq!(ctx.io().mouse_pos, [f32::MIN, f32::MIN]);
+        });
+    }
+}
@@ -0,0 +1,528 @@
+/*!
+Per-frame input states (`impl-app` feature)
+
+[`Input`] consumes window events and answers "is the key down" or "was it pressed in this frame":
+
+```no_run
+use rokol::{app::{self, Key}, input::Input};
+
+struct Game {
+    input: Input,
+}
+
+impl app::RApp for Game {
+    fn event(&mut self, ev: &app::Event) {
+        self.input.handle_event(ev);
+    }
+
+    fn frame(&mut self) {
+        if self.input.is_pressed(Key::Space) {
+            // jump
+        }
+        self.input.end_frame();
+    }
+}
+```
+
+With SDL, feed events to [`Input::handle_sdl_event`] instead. Mouse and touch positions are in the
+coordinates of the event source: framebuffer pixels with `rokol::app` and window coordinates with
+SDL.
+
+For rebindable controls, see [`action::ActionMap`].
+*/
+
+pub mod action;
+
+use std::{collections::HashSet, hash::Hash};
+
+use crate::app::{self, AppEvent, Key, Mod, Mouse, TouchPhase};
+
+/// Down states and the edges in the current frame
+#[derive(Debug, Clone)]
+struct Buttons<T: Hash + Eq> {
+    down: HashSet<T>,
+    pressed: HashSet<T>,
+    released: HashSet<T>,
+}
+
+impl<T: Hash + Eq> Default for Buttons<T> {
+    fn default() -> Self {
+        Self {
+            down: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Copy> Buttons<T> {
+    /// Key repeats are not pressed again
+    fn press(&mut self, x: T) {
+        if self.down.insert(x) {
+            self.pressed.insert(x);
+        }
+    }
+
+    fn release(&mut self, x: T) {
+        if self.down.remove(&x) {
+            self.released.insert(x);
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.released.extend(self.down.drain());
+    }
+
+    fn clear_edges(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+}
+
+/// Active touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    /// Platform identifier of the touch (unique while it's active)
+    pub id: usize,
+    pub pos: [f32; 2],
+}
+
+/// Keyboard, mouse and touch states
+#[derive(Debug, Clone, Default)]
+pub struct Input {
+    keys: Buttons<Key>,
+    buttons: Buttons<Mouse>,
+    mods: Mod,
+    mouse_pos: [f32; 2],
+    mouse_delta: [f32; 2],
+    wheel: [f32; 2],
+    touches: Vec<Touch>,
+    /// Buttons of all the SDL game controllers
+    #[cfg(feature = "sdl2")]
+    pads: Buttons<sdl2::controller::Button>,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears the pressed/released states, the mouse delta and the wheel. Call at the end of each
+    /// frame.
+    pub fn end_frame(&mut self) {
+        self.keys.clear_edges();
+        self.buttons.clear_edges();
+        #[cfg(feature = "sdl2")]
+        self.pads.clear_edges();
+        self.mouse_delta = [0.0, 0.0];
+        self.wheel = [0.0, 0.0];
+    }
+
+    /// Feeds a `rokol::app` event
+    pub fn handle_event(&mut self, ev: &app::Event) {
+        if let Some(ev) = AppEvent::from_ffi(ev) {
+            self.handle_app_event(&ev);
+        }
+    }
+
+    /// Feeds a typed `rokol::app` event
+    pub fn handle_app_event(&mut self, ev: &AppEvent) {
+        match ev {
+            AppEvent::KeyDown { key, mods, .. } => {
+                self.mods = *mods;
+                self.keys.press(*key);
+            }
+            AppEvent::KeyUp { key, mods } => {
+                self.mods = *mods;
+                self.keys.release(*key);
+            }
+            AppEvent::MouseDown { button, pos, mods } => {
+                self.mods = *mods;
+                self.mouse_pos = *pos;
+                self.buttons.press(*button);
+            }
+            AppEvent::MouseUp { button, pos, mods } => {
+                self.mods = *mods;
+                self.mouse_pos = *pos;
+                self.buttons.release(*button);
+            }
+            AppEvent::MouseMove { pos, delta } => {
+                self.mouse_pos = *pos;
+                self.mouse_delta[0] += delta[0];
+                self.mouse_delta[1] += delta[1];
+            }
+            AppEvent::MouseScroll { delta, mods } => {
+                self.mods = *mods;
+                self.wheel[0] += delta[0];
+                self.wheel[1] += delta[1];
+            }
+            AppEvent::Touches(phase, points) => {
+                for p in points.iter().filter(|p| p.changed) {
+                    let touch = Touch {
+                        id: p.identifier,
+                        pos: [p.pos_x, p.pos_y],
+                    };
+                    match phase {
+                        TouchPhase::Began | TouchPhase::Moved => self.set_touch(touch),
+                        TouchPhase::Ended | TouchPhase::Cancelled => self.remove_touch(touch.id),
+                    }
+                }
+            }
+            AppEvent::Unfocused | AppEvent::Iconified | AppEvent::Suspended => {
+                self.release_all();
+            }
+            _ => {}
+        }
+    }
+
+    fn set_touch(&mut self, touch: Touch) {
+        match self.touches.iter_mut().find(|t| t.id == touch.id) {
+            Some(t) => *t = touch,
+            None => self.touches.push(touch),
+        }
+    }
+
+    fn remove_touch(&mut self, id: usize) {
+        self.touches.retain(|t| t.id != id);
+    }
+
+    /// Releases everything, e.g. when the window loses focus and the release events never come
+    pub fn release_all(&mut self) {
+        self.keys.release_all();
+        self.buttons.release_all();
+        #[cfg(feature = "sdl2")]
+        self.pads.release_all();
+        self.mods = Mod::empty();
+        self.touches.clear();
+    }
+
+    pub fn is_down(&self, key: Key) -> bool {
+        self.keys.down.contains(&key)
+    }
+
+    /// If the key went down in this frame
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.keys.pressed.contains(&key)
+    }
+
+    /// If the key went up in this frame
+    pub fn is_released(&self, key: Key) -> bool {
+        self.keys.released.contains(&key)
+    }
+
+    pub fn is_mouse_down(&self, button: Mouse) -> bool {
+        self.buttons.down.contains(&button)
+    }
+
+    pub fn is_mouse_pressed(&self, button: Mouse) -> bool {
+        self.buttons.pressed.contains(&button)
+    }
+
+    pub fn is_mouse_released(&self, button: Mouse) -> bool {
+        self.buttons.released.contains(&button)
+    }
+
+    /// Modifier keys at the last key or mouse event
+    pub fn mods(&self) -> Mod {
+        self.mods
+    }
+
+    pub fn mouse_pos(&self) -> [f32; 2] {
+        self.mouse_pos
+    }
+
+    /// Mouse movement in this frame
+    pub fn mouse_delta(&self) -> [f32; 2] {
+        self.mouse_delta
+    }
+
+    /// Scroll amount in this frame
+    pub fn wheel(&self) -> [f32; 2] {
+        self.wheel
+    }
+
+    /// Active touches in the order they began
+    pub fn touches(&self) -> &[Touch] {
+        &self.touches
+    }
+}
+
+// --------------------------------------------------------------------------------
+// SDL
+
+#[cfg(feature = "sdl2")]
+impl Input {
+    /// Feeds an SDL event
+    pub fn handle_sdl_event(&mut self, ev: &sdl2::event::Event) {
+        use sdl2::event::{Event, WindowEvent};
+
+        match ev {
+            Event::KeyDown {
+                keycode, keymod, ..
+            } => {
+                self.mods = self::mods_sdl(*keymod);
+                if let Some(key) = keycode.and_then(self::key_sdl) {
+                    self.keys.press(key);
+                }
+            }
+            Event::KeyUp {
+                keycode, keymod, ..
+            } => {
+                self.mods = self::mods_sdl(*keymod);
+                if let Some(key) = keycode.and_then(self::key_sdl) {
+                    self.keys.release(key);
+                }
+            }
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_pos = [*x as f32, *y as f32];
+                if let Some(button) = self::mouse_sdl(*mouse_btn) {
+                    self.buttons.press(button);
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.mouse_pos = [*x as f32, *y as f32];
+                if let Some(button) = self::mouse_sdl(*mouse_btn) {
+                    self.buttons.release(button);
+                }
+            }
+            Event::MouseMotion {
+                x, y, xrel, yrel, ..
+            } => {
+                self.mouse_pos = [*x as f32, *y as f32];
+                self.mouse_delta[0] += *xrel as f32;
+                self.mouse_delta[1] += *yrel as f32;
+            }
+            Event::MouseWheel { x, y, .. } => {
+                self.wheel[0] += *x as f32;
+                self.wheel[1] += *y as f32;
+            }
+            Event::FingerDown {
+                finger_id, x, y, ..
+            }
+            | Event::FingerMotion {
+                finger_id, x, y, ..
+            } => {
+                // normalized to `0.0..=1.0`
+                self.set_touch(Touch {
+                    id: *finger_id as usize,
+                    pos: [*x, *y],
+                });
+            }
+            Event::FingerUp { finger_id, .. } => {
+                self.remove_touch(*finger_id as usize);
+            }
+            Event::ControllerButtonDown { button, .. } => {
+                self.pads.press(*button);
+            }
+            Event::ControllerButtonUp { button, .. } => {
+                self.pads.release(*button);
+            }
+            Event::Window {
+                win_event: WindowEvent::FocusLost | WindowEvent::Minimized,
+                ..
+            } => {
+                self.release_all();
+            }
+            _ => {}
+        }
+    }
+
+    /// If the button is down on any game controller
+    pub fn is_pad_down(&self, button: sdl2::controller::Button) -> bool {
+        self.pads.down.contains(&button)
+    }
+
+    pub fn is_pad_pressed(&self, button: sdl2::controller::Button) -> bool {
+        self.pads.pressed.contains(&button)
+    }
+
+    pub fn is_pad_released(&self, button: sdl2::controller::Button) -> bool {
+        self.pads.released.contains(&button)
+    }
+}
+
+/// Maps SDL modifier keys to [`Mod`]
+#[cfg(feature = "sdl2")]
+pub fn mods_sdl(keymod: sdl2::keyboard::Mod) -> Mod {
+    use sdl2::keyboard::Mod as M;
+
+    let mut mods = Mod::empty();
+    mods.set(Mod::SHIFT, keymod.intersects(M::LSHIFTMOD | M::RSHIFTMOD));
+    mods.set(Mod::CONTROL, keymod.intersects(M::LCTRLMOD | M::RCTRLMOD));
+    mods.set(Mod::ALT, keymod.intersects(M::LALTMOD | M::RALTMOD));
+    mods.set(Mod::SUPER, keymod.intersects(M::LGUIMOD | M::RGUIMOD));
+    mods
+}
+
+/// Maps an SDL mouse button to [`Mouse`] (the extra buttons are not supported)
+#[cfg(feature = "sdl2")]
+pub fn mouse_sdl(button: sdl2::mouse::MouseButton) -> Option<Mouse> {
+    use sdl2::mouse::MouseButton as M;
+
+    Some(match button {
+        M::Left => Mouse::Left,
+        M::Right => Mouse::Right,
+        M::Middle => Mouse::Middle,
+        _ => return None,
+    })
+}
+
+/// Maps an SDL keycode to [`Key`]
+#[cfg(feature = "sdl2")]
+pub fn key_sdl(key: sdl2::keyboard::Keycode) -> Option<Key> {
+    use sdl2::keyboard::Keycode as K;
+
+    Some(match key {
+        K::Space => Key::Space,
+        K::Quote => Key::Apostrophe,
+        K::Comma => Key::Comma,
+        K::Minus => Key::Minus,
+        K::Period => Key::Period,
+        K::Slash => Key::Slash,
+        K::Num0 => Key::Kbd0,
+        K::Num1 => Key::Kbd1,
+        K::Num2 => Key::Kbd2,
+        K::Num3 => Key::Kbd3,
+        K::Num4 => Key::Kbd4,
+        K::Num5 => Key::Kbd5,
+        K::Num6 => Key::Kbd6,
+        K::Num7 => Key::Kbd7,
+        K::Num8 => Key::Kbd8,
+        K::Num9 => Key::Kbd9,
+        K::Semicolon => Key::Semicolon,
+        K::Equals => Key::Equal,
+        K::A => Key::A,
+        K::B => Key::B,
+        K::C => Key::C,
+        K::D => Key::D,
+        K::E => Key::E,
+        K::F => Key::F,
+        K::G => Key::G,
+        K::H => Key::H,
+        K::I => Key::I,
+        K::J => Key::J,
+        K::K => Key::K,
+        K::L => Key::L,
+        K::M => Key::M,
+        K::N => Key::N,
+        K::O => Key::O,
+        K::P => Key::P,
+        K::Q => Key::Q,
+        K::R => Key::R,
+        K::S => Key::S,
+        K::T => Key::T,
+        K::U => Key::U,
+        K::V => Key::V,
+        K::W => Key::W,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        K::LeftBracket => Key::LeftBracket,
+        K::Backslash => Key::Backslash,
+        K::RightBracket => Key::RightBracket,
+        K::Backquote => Key::GraveAccent,
+        K::Escape => Key::Escape,
+        K::Return => Key::Enter,
+        K::Tab => Key::Tab,
+        K::Backspace => Key::Backspace,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Right => Key::Right,
+        K::Left => Key::Left,
+        K::Down => Key::Down,
+        K::Up => Key::Up,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::CapsLock => Key::CapsLock,
+        K::ScrollLock => Key::ScrollLock,
+        K::NumLockClear => Key::NumLock,
+        K::PrintScreen => Key::PrintScreen,
+        K::Pause => Key::Pause,
+        K::F1 => Key::F1,
+        K::F2 => Key::F2,
+        K::F3 => Key::F3,
+        K::F4 => Key::F4,
+        K::F5 => Key::F5,
+        K::F6 => Key::F6,
+        K::F7 => Key::F7,
+        K::F8 => Key::F8,
+        K::F9 => Key::F9,
+        K::F10 => Key::F10,
+        K::F11 => Key::F11,
+        K::F12 => Key::F12,
+        K::F13 => Key::F13,
+        K::F14 => Key::F14,
+        K::F15 => Key::F15,
+        K::F16 => Key::F16,
+        K::F17 => Key::F17,
+        K::F18 => Key::F18,
+        K::F19 => Key::F19,
+        K::F20 => Key::F20,
+        K::F21 => Key::F21,
+        K::F22 => Key::F22,
+        K::F23 => Key::F23,
+        K::F24 => Key::F24,
+        K::Kp0 => Key::KP0,
+        K::Kp1 => Key::KP1,
+        K::Kp2 => Key::KP2,
+        K::Kp3 => Key::KP3,
+        K::Kp4 => Key::KP4,
+        K::Kp5 => Key::KP5,
+        K::Kp6 => Key::KP6,
+        K::Kp7 => Key::KP7,
+        K::Kp8 => Key::KP8,
+        K::Kp9 => Key::KP9,
+        K::KpPeriod => Key::KPDecimal,
+        K::KpDivide => Key::KPDivide,
+        K::KpMultiply => Key::KPMultiply,
+        K::KpMinus => Key::KPSubtract,
+        K::KpPlus => Key::KPAdd,
+        K::KpEnter => Key::KPEnter,
+        K::KpEquals => Key::KPEqual,
+        K::LShift => Key::LeftShift,
+        K::LCtrl => Key::LeftControl,
+        K::LAlt => Key::LeftAlt,
+        K::LGui => Key::LeftSuper,
+        K::RShift => Key::RightShift,
+        K::RCtrl => Key::RightControl,
+        K::RAlt => Key::RightAlt,
+        K::RGui => Key::RightSuper,
+        K::Application => Key::Menu,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edges() {
+        let mut input = Input::new();
+        let down = |repeat| AppEvent::KeyDown {
+            key: Key::Space,
+            repeat,
+            mods: Mod::SHIFT,
+        };
+
+        input.handle_app_event(&down(false));
+        input.handle_app_event(&down(true));
+        assert!(input.is_pressed(Key::Space) && input.is_down(Key::Space));
+        assert_eq!(input.mods(), Mod::SHIFT);
+
+        input.end_frame();
+        input.handle_app_event(&down(true));
+        assert!(!input.is_pressed(Key::Space) && input.is_down(Key::Space));
+
+        input.handle_app_event(&AppEvent::Unfocused);
+        assert!(input.is_released(Key::Space) && !input.is_down(Key::Space));
+        input.end_frame();
+        assert!(!input.is_released(Key::Space));
+    }
+}
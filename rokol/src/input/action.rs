@@ -0,0 +1,487 @@
+/*!
+Rebindable actions on top of [`Input`]
+
+[`ActionMap`] maps action names to [`Binding`]s and answers "was `jump` pressed in this frame":
+
+```no_run
+use rokol::{app::{Key, Mod}, input::{action::{ActionMap, Binding}, Input}};
+
+let mut actions = ActionMap::new();
+actions.bind("jump", "Space".parse().unwrap());
+actions.bind("save", Binding::key(Key::S).with_mods(Mod::CONTROL));
+
+# let input = Input::new();
+if actions.action_pressed(&input, "jump") {
+    // jump
+}
+```
+
+Bindings are written as modifiers and a trigger joined with `+`: `Ctrl+Shift+S`, `Mouse:Left` or
+`Pad:A` (`sdl2` feature). Names are case-insensitive when parsed (`ctrl+s` is `Ctrl+S`). With the
+`serde` feature, [`ActionMap`] is saved as a map from action names to lists of such strings.
+*/
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::{
+    app::{Key, Mod, Mouse},
+    input::Input,
+};
+
+/// Key or button of a [`Binding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    Key(Key),
+    Mouse(Mouse),
+    /// Button on any game controller
+    #[cfg(feature = "sdl2")]
+    Pad(sdl2::controller::Button),
+}
+
+impl Trigger {
+    pub fn is_down(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_down(key),
+            Self::Mouse(button) => input.is_mouse_down(button),
+            #[cfg(feature = "sdl2")]
+            Self::Pad(button) => input.is_pad_down(button),
+        }
+    }
+
+    pub fn is_pressed(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_pressed(key),
+            Self::Mouse(button) => input.is_mouse_pressed(button),
+            #[cfg(feature = "sdl2")]
+            Self::Pad(button) => input.is_pad_pressed(button),
+        }
+    }
+
+    pub fn is_released(self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.is_released(key),
+            Self::Mouse(button) => input.is_mouse_released(button),
+            #[cfg(feature = "sdl2")]
+            Self::Pad(button) => input.is_pad_released(button),
+        }
+    }
+}
+
+/// Names of the modifier keys in binding strings. The first one is used for display
+const MODS: [(Mod, &[&str]); 4] = [
+    (Mod::CONTROL, &["Ctrl", "Control"]),
+    (Mod::SHIFT, &["Shift"]),
+    (Mod::ALT, &["Alt"]),
+    (Mod::SUPER, &["Super", "Cmd"]),
+];
+
+/// Name of a game controller button in binding strings
+#[cfg(feature = "sdl2")]
+fn pad_button_name(button: sdl2::controller::Button) -> &'static str {
+    use sdl2::controller::Button as B;
+
+    match button {
+        B::A => "A",
+        B::B => "B",
+        B::X => "X",
+        B::Y => "Y",
+        B::Back => "Back",
+        B::Guide => "Guide",
+        B::Start => "Start",
+        B::LeftStick => "LeftStick",
+        B::RightStick => "RightStick",
+        B::LeftShoulder => "LeftShoulder",
+        B::RightShoulder => "RightShoulder",
+        B::DPadUp => "DPadUp",
+        B::DPadDown => "DPadDown",
+        B::DPadLeft => "DPadLeft",
+        B::DPadRight => "DPadRight",
+        B::Misc1 => "Misc1",
+        B::Paddle1 => "Paddle1",
+        B::Paddle2 => "Paddle2",
+        B::Paddle3 => "Paddle3",
+        B::Paddle4 => "Paddle4",
+        B::Touchpad => "Touchpad",
+    }
+}
+
+/// Inverse of [`pad_button_name`] ignoring ASCII case
+#[cfg(feature = "sdl2")]
+fn pad_button_from_name(name: &str) -> Option<sdl2::controller::Button> {
+    use sdl2::controller::Button as B;
+
+    Some(match name.to_ascii_lowercase().as_str() {
+        "a" => B::A,
+        "b" => B::B,
+        "x" => B::X,
+        "y" => B::Y,
+        "back" => B::Back,
+        "guide" => B::Guide,
+        "start" => B::Start,
+        "leftstick" => B::LeftStick,
+        "rightstick" => B::RightStick,
+        "leftshoulder" => B::LeftShoulder,
+        "rightshoulder" => B::RightShoulder,
+        "dpadup" => B::DPadUp,
+        "dpaddown" => B::DPadDown,
+        "dpadleft" => B::DPadLeft,
+        "dpadright" => B::DPadRight,
+        "misc1" => B::Misc1,
+        "paddle1" => B::Paddle1,
+        "paddle2" => B::Paddle2,
+        "paddle3" => B::Paddle3,
+        "paddle4" => B::Paddle4,
+        "touchpad" => B::Touchpad,
+        _ => return None,
+    })
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{}", key),
+            Self::Mouse(button) => write!(f, "Mouse:{}", button),
+            #[cfg(feature = "sdl2")]
+            Self::Pad(button) => write!(f, "Pad:{}", self::pad_button_name(*button)),
+        }
+    }
+}
+
+/// `str::strip_prefix` ignoring ASCII case
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Case-insensitive, like the modifiers
+impl FromStr for Trigger {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || ParseBindingError::UnknownTrigger(s.to_string());
+
+        if let Some(name) = self::strip_prefix_ignore_case(s, "Mouse:") {
+            return Mouse::from_name_ignore_case(name)
+                .map(Self::Mouse)
+                .ok_or_else(unknown);
+        }
+
+        #[cfg(feature = "sdl2")]
+        if let Some(name) = self::strip_prefix_ignore_case(s, "Pad:") {
+            return self::pad_button_from_name(name)
+                .map(Self::Pad)
+                .ok_or_else(unknown);
+        }
+
+        Key::from_name_ignore_case(s)
+            .map(Self::Key)
+            .ok_or_else(unknown)
+    }
+}
+
+/// Error when parsing a [`Binding`] from a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBindingError {
+    Empty,
+    UnknownModifier(String),
+    UnknownTrigger(String),
+}
+
+impl fmt::Display for ParseBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty binding"),
+            Self::UnknownModifier(name) => write!(f, "unknown modifier `{}`", name),
+            Self::UnknownTrigger(name) => write!(f, "unknown key or button `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseBindingError {}
+
+/// Chord of modifier keys and a [`Trigger`]
+///
+/// The modifiers have to be held, but extra modifiers are allowed: `Ctrl+S` also fires with
+/// `Ctrl+Shift+S`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub mods: Mod,
+    pub trigger: Trigger,
+}
+
+impl Binding {
+    pub fn key(key: Key) -> Self {
+        Self {
+            mods: Mod::empty(),
+            trigger: Trigger::Key(key),
+        }
+    }
+
+    pub fn mouse(button: Mouse) -> Self {
+        Self {
+            mods: Mod::empty(),
+            trigger: Trigger::Mouse(button),
+        }
+    }
+
+    #[cfg(feature = "sdl2")]
+    pub fn pad(button: sdl2::controller::Button) -> Self {
+        Self {
+            mods: Mod::empty(),
+            trigger: Trigger::Pad(button),
+        }
+    }
+
+    pub fn with_mods(mut self, mods: Mod) -> Self {
+        self.mods |= mods;
+        self
+    }
+
+    pub fn is_down(&self, input: &Input) -> bool {
+        self.trigger.is_down(input) && input.mods().contains(self.mods)
+    }
+
+    pub fn is_pressed(&self, input: &Input) -> bool {
+        self.trigger.is_pressed(input) && input.mods().contains(self.mods)
+    }
+
+    /// Modifiers are not checked, so that releasing them first doesn't swallow the release
+    pub fn is_released(&self, input: &Input) -> bool {
+        self.trigger.is_released(input)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (m, names) in MODS {
+            if self.mods.contains(m) {
+                write!(f, "{}+", names[0])?;
+            }
+        }
+        write!(f, "{}", self.trigger)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = ParseBindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseBindingError::Empty);
+        }
+
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+        let trigger = parts.pop().unwrap().parse::<Trigger>()?;
+
+        let mut mods = Mod::empty();
+        for part in parts {
+            let m = MODS
+                .iter()
+                .find(|(_, names)| names.iter().any(|n| n.eq_ignore_ascii_case(part)))
+                .map(|(m, _)| *m)
+                .ok_or_else(|| ParseBindingError::UnknownModifier(part.to_string()))?;
+            mods |= m;
+        }
+
+        Ok(Self { mods, trigger })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Binding {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Binding {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Action names mapped to [`Binding`]s. Any of the bindings fires the action
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding to the action (if it's not bound yet)
+    pub fn bind(&mut self, action: impl Into<String>, binding: Binding) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a binding from the action. Returns if it was bound
+    pub fn unbind(&mut self, action: &str, binding: Binding) -> bool {
+        let bindings = match self.actions.get_mut(action) {
+            Some(b) => b,
+            None => return false,
+        };
+        let len = bindings.len();
+        bindings.retain(|b| *b != binding);
+        len != bindings.len()
+    }
+
+    /// Removes all the bindings of the action
+    pub fn clear(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map(|b| b.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Action names in sorted order
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|s| s.as_str())
+    }
+
+    /// If any binding of the action is down. Unknown actions are never down
+    pub fn action_down(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.is_down(input))
+    }
+
+    pub fn action_pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.is_pressed(input))
+    }
+
+    pub fn action_released(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.is_released(input))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::AppEvent;
+
+    #[test]
+    fn binding_names() {
+        let b: Binding = "shift+ctrl+S".parse().unwrap();
+        assert_eq!(b, Binding::key(Key::S).with_mods(Mod::CONTROL | Mod::SHIFT));
+        assert_eq!(b.to_string(), "Ctrl+Shift+S");
+
+        let b: Binding = "Mouse:Left".parse().unwrap();
+        assert_eq!(b, Binding::mouse(Mouse::Left));
+        assert_eq!(b.to_string(), "Mouse:Left");
+
+        // triggers are case-insensitive like modifiers
+        assert_eq!(
+            "ctrl+s".parse(),
+            Ok(Binding::key(Key::S).with_mods(Mod::CONTROL))
+        );
+        assert_eq!("mouse:right".parse(), Ok(Binding::mouse(Mouse::Right)));
+        assert_eq!("ESCAPE".parse(), Ok(Binding::key(Key::Escape)));
+        assert_eq!(
+            "Ctrl+Sss".parse::<Binding>(),
+            Err(ParseBindingError::UnknownTrigger("Sss".to_string()))
+        );
+
+        assert_eq!("".parse::<Binding>(), Err(ParseBindingError::Empty));
+        assert_eq!(
+            "Hyper+A".parse::<Binding>(),
+            Err(ParseBindingError::UnknownModifier("Hyper".to_string()))
+        );
+    }
+
+    #[cfg(feature = "sdl2")]
+    #[test]
+    fn pad_names() {
+        use sdl2::controller::Button as B;
+
+        let buttons = [
+            B::A,
+            B::Back,
+            B::LeftStick,
+            B::RightShoulder,
+            B::DPadLeft,
+            B::Misc1,
+            B::Paddle4,
+            B::Touchpad,
+        ];
+        for button in buttons {
+            let b = Binding::pad(button);
+            assert_eq!(b.to_string().parse(), Ok(b));
+            assert_eq!(b.to_string().to_lowercase().parse(), Ok(b));
+        }
+
+        assert_eq!(Binding::pad(B::DPadUp).to_string(), "Pad:DPadUp");
+        assert_eq!(
+            "Pad:Paddle5".parse::<Binding>(),
+            Err(ParseBindingError::UnknownTrigger("Pad:Paddle5".to_string()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let mut actions = ActionMap::new();
+        actions.bind("jump", Binding::key(Key::Space));
+        actions.bind("jump", Binding::mouse(Mouse::Left));
+        actions.bind(
+            "save",
+            Binding::key(Key::S).with_mods(Mod::CONTROL | Mod::SHIFT),
+        );
+
+        let json = serde_json::to_string(&actions).unwrap();
+        assert_eq!(
+            json,
+            r#"{"jump":["Space","Mouse:Left"],"save":["Ctrl+Shift+S"]}"#
+        );
+        assert_eq!(serde_json::from_str::<ActionMap>(&json).unwrap(), actions);
+
+        // hand-written files may use any case
+        let parsed: ActionMap = serde_json::from_str(r#"{"save":["ctrl+shift+s"]}"#).unwrap();
+        assert_eq!(parsed.bindings("save"), actions.bindings("save"));
+
+        assert!(serde_json::from_str::<ActionMap>(r#"{"save":["Hyper+S"]}"#).is_err());
+    }
+
+    #[test]
+    fn actions() {
+        let mut actions = ActionMap::new();
+        actions.bind("save", Binding::key(Key::S).with_mods(Mod::CONTROL));
+
+        let mut input = Input::new();
+        input.handle_app_event(&AppEvent::KeyDown {
+            key: Key::S,
+            repeat: false,
+            mods: Mod::empty(),
+        });
+        assert!(!actions.action_pressed(&input, "save"));
+        assert!(!actions.action_pressed(&input, "load"));
+
+        input.handle_app_event(&AppEvent::KeyUp {
+            key: Key::S,
+            mods: Mod::empty(),
+        });
+        input.end_frame();
+        input.handle_app_event(&AppEvent::KeyDown {
+            key: Key::S,
+            repeat: false,
+            mods: Mod::CONTROL,
+        });
+        assert!(actions.action_pressed(&input, "save"));
+    }
+}
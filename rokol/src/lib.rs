@@ -11,13 +11,26 @@ Example settings:
 rokol = { features = ["sdl", "impl-gfx", "glcore33", "fontstash"] }
 ```
 
-* `impl-app`: implements `sokol_app.h` and enables `app` module
+* `impl-app`: implements `sokol_app.h` and enables `app` and `input` modules
 * `sdl2`: generates [`glue`] code for `sdl2`
-* `impl-gfx`: implements `sokol_gfx.h` and enables `gfx` module
+* `impl-gfx`: implements `sokol_gfx.h` and enables `gfx`, `batch` and `shapes` modules
   * `glcore33`: uses OpenGL backend
   * `metal`: uses Metal backend
   * `d3d11`: uses DirectX11 backend
+  * `dummy`: uses headless backend that renders nothing
+  * `registry`: tracks live resources and logs leaks on `gfx::shutdown`
+* `impl-time`: implements `sokol_time.h` and enables `time` module
+* `impl-audio`: implements `sokol_audio.h` and enables `audio` module
+  * `ogg`: decodes OGG files with `lewton`
+* `impl-fetch`: implements `sokol_fetch.h` and enables `fetch` module
+* `impl-args`: implements `sokol_args.h` and enables `args` module (and `Rokol::from_args`)
+* `impl-gl`: implements `sokol_gl.h` and enables `sgl` module
+* `impl-debugtext`: implements `sokol_debugtext.h` and enables `debugtext` module
+* `impl-shape`: implements `sokol_shape.h` and enables `shape` module
 * `fontstash`: implements `fontstash.h` and enables `fons` module
+* `imgui`: enables `imgui` module (Dear ImGui renderer with `imgui-rs`, requires `impl-gfx`)
+* `egui`: enables `egui` module (egui painter and input adapter, requires `impl-gfx`)
+* `impl-gfx-imgui`: implements `sokol_gfx_imgui.h` and enables `gfx_imgui` module (implies `imgui`)
 
 # Tips
 
@@ -71,6 +84,45 @@ macro_rules! ffi_enum {
                     )*
                 }
             }
+
+            /// Name of the variant
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        <Self>::$variant => stringify!($variant),
+                    )*
+                }
+            }
+
+            /// Variant of a [`name`](Self::name)
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(
+                        stringify!($variant) => Some(<Self>::$variant),
+                    )*
+                    _ => None,
+                }
+            }
+
+            /// Variant of a raw FFI value. Returns `None` for unknown values
+            pub fn from_u32(x: u32) -> Option<Self> {
+                $(
+                    if x == <Self>::$variant as u32 {
+                        return Some(<Self>::$variant);
+                    }
+                )*
+                None
+            }
+
+            /// [`from_name`](Self::from_name) ignoring ASCII case
+            pub fn from_name_ignore_case(name: &str) -> Option<Self> {
+                $(
+                    if name.eq_ignore_ascii_case(stringify!($variant)) {
+                        return Some(<Self>::$variant);
+                    }
+                )*
+                None
+            }
         }
 
         impl From<$Ffi> for $Enum {
@@ -90,11 +142,53 @@ macro_rules! ffi_enum {
 #[cfg(feature = "impl-app")]
 pub mod app;
 
+#[cfg(feature = "impl-app")]
+pub mod input;
+
 #[cfg(feature = "impl-gfx")]
 pub mod gfx;
 
 #[cfg(feature = "impl-gfx")]
 pub mod glue;
 
+#[cfg(feature = "impl-gfx")]
+pub mod batch;
+
+#[cfg(feature = "impl-gfx")]
+pub mod shapes;
+
+#[cfg(feature = "impl-time")]
+pub mod time;
+
+#[cfg(feature = "impl-audio")]
+pub mod audio;
+
+#[cfg(feature = "impl-fetch")]
+pub mod fetch;
+
+#[cfg(feature = "impl-args")]
+pub mod args;
+
+#[cfg(feature = "impl-gl")]
+pub mod sgl;
+
+#[cfg(feature = "impl-debugtext")]
+pub mod debugtext;
+
+#[cfg(feature = "impl-shape")]
+pub mod shape;
+
 #[cfg(all(feature = "impl-gfx", feature = "fontstash"))]
 pub mod fons;
+
+#[cfg(all(feature = "impl-gfx", feature = "imgui"))]
+pub mod imgui;
+
+#[cfg(all(feature = "impl-gfx", feature = "egui"))]
+pub mod egui;
+
+#[cfg(all(feature = "impl-gfx", any(feature = "imgui", feature = "egui")))]
+mod ui;
+
+#[cfg(feature = "impl-gfx-imgui")]
+pub mod gfx_imgui;
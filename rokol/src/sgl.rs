@@ -0,0 +1,476 @@
+/*!
+Immediate-mode drawing with [`sokol_gl.h`] (`impl-gl` feature)
+
+OpenGL 1.x style API for quick debug drawing on top of [`crate::gfx`]. Set up after
+[`crate::gfx::setup`] and call [`draw`] inside a rendering pass:
+
+```no_run
+use rokol::{gfx as rg, sgl};
+
+sgl::setup(&Default::default());
+
+// every frame:
+sgl::defaults();
+sgl::matrix_mode(sgl::MatrixMode::Projection);
+sgl::ortho(0.0, 1280.0, 720.0, 0.0, -1.0, 1.0);
+
+sgl::begin_triangles();
+sgl::v2f_c4b(100.0, 100.0, [255, 0, 0, 255]);
+sgl::v2f_c4b(200.0, 100.0, [0, 255, 0, 255]);
+sgl::v2f_c4b(150.0, 200.0, [0, 0, 255, 255]);
+sgl::end();
+
+rg::begin_default_pass(&rg::PassAction::clear([0.0, 0.0, 0.0, 1.0]), 1280, 720);
+sgl::draw();
+rg::end_pass();
+rg::commit();
+```
+
+Vertices and commands are recorded until [`draw`], so the recording can happen outside of the
+pass. Call [`error`] to see if the buffers overflowed.
+
+[`sokol_gl.h`]: https://github.com/floooh/sokol/blob/master/util/sokol_gl.h
+*/
+
+use std::{error::Error, fmt};
+
+use rokol_ffi::gl as ffi;
+
+use crate::gfx::{Image, PipelineDesc};
+
+/// `sgl_desc_t`. Zero fields are replaced with Sokol defaults (64K vertices, 16K commands)
+pub type SglDesc = ffi::sgl_desc_t;
+
+/// Pipeline object of `sokol_gl.h` (a set of `sokol_gfx.h` pipelines for each primitive type)
+pub type SglPipeline = ffi::sgl_pipeline;
+
+ffi_enum! {
+    /// Error state of `sokol_gl.h`, reset on [`draw`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SglError around ffi::sgl_error_t {
+        NoError = SGL_NO_ERROR,
+        VerticesFull = SGL_ERROR_VERTICES_FULL,
+        UniformsFull = SGL_ERROR_UNIFORMS_FULL,
+        CommandsFull = SGL_ERROR_COMMANDS_FULL,
+        StackOverflow = SGL_ERROR_STACK_OVERFLOW,
+        StackUnderflow = SGL_ERROR_STACK_UNDERFLOW,
+    }
+}
+
+impl fmt::Display for SglError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::NoError => "no error",
+            Self::VerticesFull => "vertex buffer is full (increase `max_vertices`)",
+            Self::UniformsFull => "uniform buffer is full (increase `max_commands`)",
+            Self::CommandsFull => "command buffer is full (increase `max_commands`)",
+            Self::StackOverflow => "matrix or pipeline stack overflow",
+            Self::StackUnderflow => "matrix or pipeline stack underflow",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for SglError {}
+
+/// Target of the matrix functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixMode {
+    ModelView,
+    Projection,
+    Texture,
+}
+
+// --------------------------------------------------------------------------------
+// Setup
+
+/// Sets up `sokol_gl.h`. Call after [`crate::gfx::setup`].
+pub fn setup(desc: &SglDesc) {
+    unsafe {
+        ffi::sgl_setup(desc);
+    }
+}
+
+/// Call before [`crate::gfx::shutdown`]
+pub fn shutdown() {
+    unsafe {
+        ffi::sgl_shutdown();
+    }
+}
+
+/// Returns the error recorded since the last [`draw`]
+pub fn error() -> Result<(), SglError> {
+    match SglError::from_ffi(unsafe { ffi::sgl_error() }) {
+        SglError::NoError => Ok(()),
+        err => Err(err),
+    }
+}
+
+/// Resets the render states, matrices and texture
+pub fn defaults() {
+    unsafe {
+        ffi::sgl_defaults();
+    }
+}
+
+pub fn rad(deg: f32) -> f32 {
+    deg.to_radians()
+}
+
+pub fn deg(rad: f32) -> f32 {
+    rad.to_degrees()
+}
+
+// --------------------------------------------------------------------------------
+// Pipelines
+
+/// Creates a pipeline from a template. Shader, vertex layout and primitive type are overwritten
+/// by `sokol_gl.h`; set blend, depth and cull states.
+pub fn make_pipeline(desc: &PipelineDesc) -> SglPipeline {
+    unsafe { ffi::sgl_make_pipeline(desc) }
+}
+
+pub fn destroy_pipeline(pip: SglPipeline) {
+    unsafe {
+        ffi::sgl_destroy_pipeline(pip);
+    }
+}
+
+pub fn default_pipeline() {
+    unsafe {
+        ffi::sgl_default_pipeline();
+    }
+}
+
+pub fn load_pipeline(pip: SglPipeline) {
+    unsafe {
+        ffi::sgl_load_pipeline(pip);
+    }
+}
+
+pub fn push_pipeline() {
+    unsafe {
+        ffi::sgl_push_pipeline();
+    }
+}
+
+pub fn pop_pipeline() {
+    unsafe {
+        ffi::sgl_pop_pipeline();
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Render states
+
+pub fn viewport(x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {
+    unsafe {
+        ffi::sgl_viewport(x, y, w, h, origin_top_left);
+    }
+}
+
+pub fn viewport_f(x: f32, y: f32, w: f32, h: f32, origin_top_left: bool) {
+    unsafe {
+        ffi::sgl_viewportf(x, y, w, h, origin_top_left);
+    }
+}
+
+pub fn scissor_rect(x: i32, y: i32, w: i32, h: i32, origin_top_left: bool) {
+    unsafe {
+        ffi::sgl_scissor_rect(x, y, w, h, origin_top_left);
+    }
+}
+
+pub fn scissor_rect_f(x: f32, y: f32, w: f32, h: f32, origin_top_left: bool) {
+    unsafe {
+        ffi::sgl_scissor_rectf(x, y, w, h, origin_top_left);
+    }
+}
+
+pub fn enable_texture() {
+    unsafe {
+        ffi::sgl_enable_texture();
+    }
+}
+
+pub fn disable_texture() {
+    unsafe {
+        ffi::sgl_disable_texture();
+    }
+}
+
+/// Sets the texture. It's used only while texturing is enabled with [`enable_texture`].
+pub fn texture(img: Image) {
+    unsafe {
+        ffi::sgl_texture(img);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Matrix stack
+
+pub fn matrix_mode(mode: MatrixMode) {
+    unsafe {
+        match mode {
+            MatrixMode::ModelView => ffi::sgl_matrix_mode_modelview(),
+            MatrixMode::Projection => ffi::sgl_matrix_mode_projection(),
+            MatrixMode::Texture => ffi::sgl_matrix_mode_texture(),
+        }
+    }
+}
+
+pub fn load_identity() {
+    unsafe {
+        ffi::sgl_load_identity();
+    }
+}
+
+/// Loads a column-major matrix
+pub fn load_matrix(m: &[f32; 16]) {
+    unsafe {
+        ffi::sgl_load_matrix(m.as_ptr());
+    }
+}
+
+/// Loads a row-major matrix
+pub fn load_transpose_matrix(m: &[f32; 16]) {
+    unsafe {
+        ffi::sgl_load_transpose_matrix(m.as_ptr());
+    }
+}
+
+/// Multiplies a column-major matrix
+pub fn mult_matrix(m: &[f32; 16]) {
+    unsafe {
+        ffi::sgl_mult_matrix(m.as_ptr());
+    }
+}
+
+/// Multiplies a row-major matrix
+pub fn mult_transpose_matrix(m: &[f32; 16]) {
+    unsafe {
+        ffi::sgl_mult_transpose_matrix(m.as_ptr());
+    }
+}
+
+pub fn rotate(angle_rad: f32, x: f32, y: f32, z: f32) {
+    unsafe {
+        ffi::sgl_rotate(angle_rad, x, y, z);
+    }
+}
+
+pub fn scale(x: f32, y: f32, z: f32) {
+    unsafe {
+        ffi::sgl_scale(x, y, z);
+    }
+}
+
+pub fn translate(x: f32, y: f32, z: f32) {
+    unsafe {
+        ffi::sgl_translate(x, y, z);
+    }
+}
+
+pub fn frustum(l: f32, r: f32, b: f32, t: f32, n: f32, f: f32) {
+    unsafe {
+        ffi::sgl_frustum(l, r, b, t, n, f);
+    }
+}
+
+pub fn ortho(l: f32, r: f32, b: f32, t: f32, n: f32, f: f32) {
+    unsafe {
+        ffi::sgl_ortho(l, r, b, t, n, f);
+    }
+}
+
+pub fn perspective(fov_y: f32, aspect: f32, z_near: f32, z_far: f32) {
+    unsafe {
+        ffi::sgl_perspective(fov_y, aspect, z_near, z_far);
+    }
+}
+
+pub fn lookat(eye: [f32; 3], center: [f32; 3], up: [f32; 3]) {
+    unsafe {
+        ffi::sgl_lookat(
+            eye[0], eye[1], eye[2], center[0], center[1], center[2], up[0], up[1], up[2],
+        );
+    }
+}
+
+pub fn push_matrix() {
+    unsafe {
+        ffi::sgl_push_matrix();
+    }
+}
+
+pub fn pop_matrix() {
+    unsafe {
+        ffi::sgl_pop_matrix();
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Current vertex attributes (valid inside and outside `begin_*`/`end`)
+
+pub fn t2f(u: f32, v: f32) {
+    unsafe {
+        ffi::sgl_t2f(u, v);
+    }
+}
+
+pub fn c4f(color: [f32; 4]) {
+    unsafe {
+        ffi::sgl_c4f(color[0], color[1], color[2], color[3]);
+    }
+}
+
+pub fn c4b(color: [u8; 4]) {
+    unsafe {
+        ffi::sgl_c4b(color[0], color[1], color[2], color[3]);
+    }
+}
+
+/// Sets the color as packed `0xAABBGGRR`
+pub fn c1i(rgba: u32) {
+    unsafe {
+        ffi::sgl_c1i(rgba);
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Primitives (each `begin_*`/`end` pair is one draw command)
+
+pub fn begin_points() {
+    unsafe {
+        ffi::sgl_begin_points();
+    }
+}
+
+pub fn begin_lines() {
+    unsafe {
+        ffi::sgl_begin_lines();
+    }
+}
+
+pub fn begin_line_strip() {
+    unsafe {
+        ffi::sgl_begin_line_strip();
+    }
+}
+
+pub fn begin_triangles() {
+    unsafe {
+        ffi::sgl_begin_triangles();
+    }
+}
+
+pub fn begin_triangle_strip() {
+    unsafe {
+        ffi::sgl_begin_triangle_strip();
+    }
+}
+
+pub fn begin_quads() {
+    unsafe {
+        ffi::sgl_begin_quads();
+    }
+}
+
+pub fn end() {
+    unsafe {
+        ffi::sgl_end();
+    }
+}
+
+// --------------------------------------------------------------------------------
+// Vertices (`v<pos>_t<uv>_c<color>`; omitted attributes are taken from the current state)
+
+#[inline]
+pub fn v2f(x: f32, y: f32) {
+    unsafe { ffi::sgl_v2f(x, y) }
+}
+
+#[inline]
+pub fn v3f(x: f32, y: f32, z: f32) {
+    unsafe { ffi::sgl_v3f(x, y, z) }
+}
+
+#[inline]
+pub fn v2f_t2f(x: f32, y: f32, u: f32, v: f32) {
+    unsafe { ffi::sgl_v2f_t2f(x, y, u, v) }
+}
+
+#[inline]
+pub fn v3f_t2f(x: f32, y: f32, z: f32, u: f32, v: f32) {
+    unsafe { ffi::sgl_v3f_t2f(x, y, z, u, v) }
+}
+
+#[inline]
+pub fn v2f_c4f(x: f32, y: f32, color: [f32; 4]) {
+    unsafe { ffi::sgl_v2f_c4f(x, y, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v2f_c4b(x: f32, y: f32, color: [u8; 4]) {
+    unsafe { ffi::sgl_v2f_c4b(x, y, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v2f_c1i(x: f32, y: f32, rgba: u32) {
+    unsafe { ffi::sgl_v2f_c1i(x, y, rgba) }
+}
+
+#[inline]
+pub fn v3f_c4f(x: f32, y: f32, z: f32, color: [f32; 4]) {
+    unsafe { ffi::sgl_v3f_c4f(x, y, z, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v3f_c4b(x: f32, y: f32, z: f32, color: [u8; 4]) {
+    unsafe { ffi::sgl_v3f_c4b(x, y, z, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v3f_c1i(x: f32, y: f32, z: f32, rgba: u32) {
+    unsafe { ffi::sgl_v3f_c1i(x, y, z, rgba) }
+}
+
+#[inline]
+pub fn v2f_t2f_c4f(x: f32, y: f32, u: f32, v: f32, color: [f32; 4]) {
+    unsafe { ffi::sgl_v2f_t2f_c4f(x, y, u, v, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v2f_t2f_c4b(x: f32, y: f32, u: f32, v: f32, color: [u8; 4]) {
+    unsafe { ffi::sgl_v2f_t2f_c4b(x, y, u, v, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v2f_t2f_c1i(x: f32, y: f32, u: f32, v: f32, rgba: u32) {
+    unsafe { ffi::sgl_v2f_t2f_c1i(x, y, u, v, rgba) }
+}
+
+#[inline]
+pub fn v3f_t2f_c4f(x: f32, y: f32, z: f32, u: f32, v: f32, color: [f32; 4]) {
+    unsafe { ffi::sgl_v3f_t2f_c4f(x, y, z, u, v, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v3f_t2f_c4b(x: f32, y: f32, z: f32, u: f32, v: f32, color: [u8; 4]) {
+    unsafe { ffi::sgl_v3f_t2f_c4b(x, y, z, u, v, color[0], color[1], color[2], color[3]) }
+}
+
+#[inline]
+pub fn v3f_t2f_c1i(x: f32, y: f32, z: f32, u: f32, v: f32, rgba: u32) {
+    unsafe { ffi::sgl_v3f_t2f_c1i(x, y, z, u, v, rgba) }
+}
+
+// --------------------------------------------------------------------------------
+// Rendering
+
+/// Draws everything recorded since the last call. Must be called inside a rendering pass.
+pub fn draw() {
+    unsafe {
+        ffi::sgl_draw();
+    }
+}
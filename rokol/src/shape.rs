@@ -0,0 +1,398 @@
+/*!
+Primitive meshes with [`sokol_shape.h`] (`impl-shape` feature)
+
+[`ShapeData`] builds planes, boxes, spheres, cylinders and tori into Rust `Vec`s. Each shape gets
+an [`ElementRange`] unless it's merged into the previous one:
+
+```no_run
+use rokol::{gfx as rg, shape::{self, Shape, ShapeData}};
+
+let mut data = ShapeData::new();
+let sphere = data
+    .push(Shape::Sphere(shape::SphereDesc {
+        radius: 0.5,
+        slices: 36,
+        stacks: 20,
+        random_colors: true,
+        ..Default::default()
+    }))
+    .unwrap();
+data.push(Shape::Box(shape::BoxDesc {
+    width: 1.0,
+    height: 1.0,
+    depth: 1.0,
+    ..Default::default()
+}))
+.unwrap();
+
+let bufs = data.to_buffers("gizmos");
+let pip_desc = rg::pipeline_desc::<shape::Vertex, u16>(rg::Shader::default());
+
+// inside a rendering pass:
+let mut bind = rg::Bindings::default();
+bufs.set_to(&mut bind, 0);
+rg::apply_bindings(&bind);
+bufs.draw(0, 1);
+```
+
+Zero-initialized parameters are replaced with Sokol defaults (and a zero transform is the identity).
+
+[`sokol_shape.h`]: https://github.com/floooh/sokol/blob/master/util/sokol_shape.h
+*/
+
+use std::{error::Error, fmt, mem::size_of};
+
+use rokol_ffi::shape as ffi;
+
+use crate::gfx::{self as rg, Bindings, IndexBuffer, LayoutDesc, Pod, VertexBuffer, VertexLayout};
+
+/// Vertex of `sokol_shape.h`: position (`FLOAT3`), normal (`BYTE4N`), texcoord (`USHORT2N`) and
+/// color (`UBYTE4N`) in attribute slots 0 to 3
+pub type Vertex = ffi::sshape_vertex_t;
+
+/// Range of indices of a shape (for [`rg::draw`])
+pub type ElementRange = ffi::sshape_element_range_t;
+
+/// Transform of a shape (column-major)
+pub type Mat4 = ffi::sshape_mat4_t;
+
+pub type PlaneDesc = ffi::sshape_plane_t;
+pub type BoxDesc = ffi::sshape_box_t;
+pub type SphereDesc = ffi::sshape_sphere_t;
+pub type CylinderDesc = ffi::sshape_cylinder_t;
+pub type TorusDesc = ffi::sshape_torus_t;
+
+// `#[repr(C)]` with 4-byte fields except two `u16`s, so there's no padding
+unsafe impl Pod for Vertex {}
+
+unsafe impl VertexLayout for Vertex {
+    fn layout_desc() -> LayoutDesc {
+        self::layout_desc()
+    }
+}
+
+/// Vertex layout of [`Vertex`] in buffer slot 0
+pub fn layout_desc() -> LayoutDesc {
+    let mut desc = LayoutDesc::default();
+    unsafe {
+        desc.buffers[0] = ffi::sshape_buffer_layout_desc();
+        desc.attrs[0] = ffi::sshape_position_attr_desc();
+        desc.attrs[1] = ffi::sshape_normal_attr_desc();
+        desc.attrs[2] = ffi::sshape_texcoord_attr_desc();
+        desc.attrs[3] = ffi::sshape_color_attr_desc();
+    }
+    desc
+}
+
+/// Packs a color for the shape parameters
+pub fn color(rgba: [u8; 4]) -> u32 {
+    unsafe { ffi::sshape_color_4b(rgba[0], rgba[1], rgba[2], rgba[3]) }
+}
+
+/// Creates [`Mat4`] from a column-major matrix
+pub fn mat4(m: &[f32; 16]) -> Mat4 {
+    unsafe { ffi::sshape_mat4(m.as_ptr()) }
+}
+
+/// Parameters of a shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Plane(PlaneDesc),
+    Box(BoxDesc),
+    Sphere(SphereDesc),
+    Cylinder(CylinderDesc),
+    Torus(TorusDesc),
+}
+
+/// Zero-initialized value replaced with the default value (like `sokol_shape.h`)
+fn def<T: Default + PartialEq>(x: T, default: T) -> T {
+    if x == T::default() {
+        default
+    } else {
+        x
+    }
+}
+
+impl Shape {
+    /// Replaces zero-initialized sizes and tessellation parameters with Sokol defaults
+    fn with_defaults(mut self) -> Self {
+        match &mut self {
+            Self::Plane(p) => {
+                p.width = def(p.width, 1.0);
+                p.depth = def(p.depth, 1.0);
+                p.tiles = def(p.tiles, 1);
+            }
+            Self::Box(p) => {
+                p.width = def(p.width, 1.0);
+                p.height = def(p.height, 1.0);
+                p.depth = def(p.depth, 1.0);
+                p.tiles = def(p.tiles, 1);
+            }
+            Self::Sphere(p) => {
+                p.radius = def(p.radius, 0.5);
+                p.slices = def(p.slices, 5);
+                p.stacks = def(p.stacks, 4);
+            }
+            Self::Cylinder(p) => {
+                p.radius = def(p.radius, 0.5);
+                p.height = def(p.height, 1.0);
+                p.slices = def(p.slices, 5);
+                p.stacks = def(p.stacks, 1);
+            }
+            Self::Torus(p) => {
+                p.radius = def(p.radius, 0.5);
+                p.ring_radius = def(p.ring_radius, 0.2);
+                p.sides = def(p.sides, 5);
+                p.rings = def(p.rings, 5);
+            }
+        }
+        self
+    }
+
+    /// Number of vertices and indices
+    pub fn sizes(&self) -> (usize, usize) {
+        let sizes = unsafe {
+            match self.with_defaults() {
+                Self::Plane(p) => ffi::sshape_plane_sizes(p.tiles as u32),
+                Self::Box(p) => ffi::sshape_box_sizes(p.tiles as u32),
+                Self::Sphere(p) => ffi::sshape_sphere_sizes(p.slices as u32, p.stacks as u32),
+                Self::Cylinder(p) => ffi::sshape_cylinder_sizes(p.slices as u32, p.stacks as u32),
+                Self::Torus(p) => ffi::sshape_torus_sizes(p.sides as u32, p.rings as u32),
+            }
+        };
+        (sizes.vertices.num as usize, sizes.indices.num as usize)
+    }
+
+    fn set_merge(&mut self, merge: bool) {
+        match self {
+            Self::Plane(p) => p.merge = merge,
+            Self::Box(p) => p.merge = merge,
+            Self::Sphere(p) => p.merge = merge,
+            Self::Cylinder(p) => p.merge = merge,
+            Self::Torus(p) => p.merge = merge,
+        }
+    }
+
+    unsafe fn build(&self, buf: &ffi::sshape_buffer_t) -> ffi::sshape_buffer_t {
+        match self {
+            Self::Plane(p) => ffi::sshape_build_plane(buf, p),
+            Self::Box(p) => ffi::sshape_build_box(buf, p),
+            Self::Sphere(p) => ffi::sshape_build_sphere(buf, p),
+            Self::Cylinder(p) => ffi::sshape_build_cylinder(buf, p),
+            Self::Torus(p) => ffi::sshape_build_torus(buf, p),
+        }
+    }
+}
+
+/// Error on building a shape into [`ShapeData`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeError {
+    /// The vertices can't be indexed with `u16`
+    TooManyVertices { n_verts: usize },
+    /// `sokol_shape.h` failed to build the shape
+    Build(Shape),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyVertices { n_verts } => write!(
+                f,
+                "{} vertices can't be indexed with `u16` (max {})",
+                n_verts,
+                u16::MAX as usize + 1
+            ),
+            Self::Build(shape) => write!(f, "failed to build shape: {:?}", shape),
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+/// Vertices and `u16` indices of shapes with their element ranges
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShapeData {
+    pub verts: Vec<Vertex>,
+    pub indices: Vec<u16>,
+    pub ranges: Vec<ElementRange>,
+    /// Byte offsets to the last shape (`sshape_buffer_item_t::shape_offset`)
+    offsets: [usize; 2],
+}
+
+impl ShapeData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.verts.clear();
+        self.indices.clear();
+        self.ranges.clear();
+        self.offsets = [0, 0];
+    }
+
+    /// Appends a shape as a new element range. The data is left unchanged on error.
+    pub fn push(&mut self, shape: Shape) -> Result<ElementRange, ShapeError> {
+        self.build(shape, false)
+    }
+
+    /// Appends a shape merged into the last element range. The data is left unchanged on error.
+    pub fn merge(&mut self, shape: Shape) -> Result<ElementRange, ShapeError> {
+        self.build(shape, !self.ranges.is_empty())
+    }
+
+    fn build(&mut self, mut shape: Shape, merge: bool) -> Result<ElementRange, ShapeError> {
+        let (n_verts, n_indices) = shape.sizes();
+        let n_verts = self.verts.len() + n_verts;
+        if n_verts > u16::MAX as usize + 1 {
+            return Err(ShapeError::TooManyVertices { n_verts });
+        }
+
+        self.verts.reserve(n_verts);
+        self.indices.reserve(n_indices);
+
+        let buf = ffi::sshape_buffer_t {
+            valid: true,
+            vertices: self::buffer_item(&mut self.verts, self.offsets[0]),
+            indices: self::buffer_item(&mut self.indices, self.offsets[1]),
+        };
+
+        shape.set_merge(merge);
+        let buf = unsafe { shape.build(&buf) };
+        if !buf.valid {
+            return Err(ShapeError::Build(shape));
+        }
+
+        unsafe {
+            self.verts
+                .set_len(buf.vertices.data_size as usize / size_of::<Vertex>());
+            self.indices
+                .set_len(buf.indices.data_size as usize / size_of::<u16>());
+        }
+        self.offsets = [
+            buf.vertices.shape_offset as usize,
+            buf.indices.shape_offset as usize,
+        ];
+
+        let range = unsafe { ffi::sshape_element_range(&buf) };
+        match self.ranges.last_mut() {
+            Some(last) if merge => *last = range,
+            _ => self.ranges.push(range),
+        }
+        Ok(range)
+    }
+
+    /// Creates immutable buffers
+    pub fn to_buffers(&self, label: &str) -> ShapeBuffers {
+        ShapeBuffers {
+            vbuf: VertexBuffer::immutable(&self.verts, &format!("{}-vertices", label)),
+            ibuf: IndexBuffer::immutable(&self.indices, &format!("{}-indices", label)),
+            ranges: self.ranges.clone(),
+        }
+    }
+}
+
+/// Whole capacity of a `Vec` as the output of `sokol_shape.h`
+fn buffer_item<T>(items: &mut Vec<T>, shape_offset: usize) -> ffi::sshape_buffer_item_t {
+    ffi::sshape_buffer_item_t {
+        buffer: ffi::sshape_range {
+            ptr: items.as_mut_ptr() as *const _,
+            size: (items.capacity() * size_of::<T>()) as _,
+        },
+        data_size: (items.len() * size_of::<T>()) as _,
+        shape_offset: shape_offset as _,
+    }
+}
+
+/// Shapes in GPU buffers (destroyed on drop)
+#[derive(Debug)]
+pub struct ShapeBuffers {
+    pub vbuf: VertexBuffer<Vertex>,
+    pub ibuf: IndexBuffer<u16>,
+    pub ranges: Vec<ElementRange>,
+}
+
+impl ShapeBuffers {
+    /// Sets the vertex buffer to a slot and the index buffer
+    pub fn set_to(&self, bind: &mut Bindings, slot: usize) {
+        self.vbuf.set_to(bind, slot);
+        self.ibuf.set_to(bind);
+    }
+
+    /// Draws the shape of an element range
+    pub fn draw(&self, range: usize, n_instances: u32) {
+        let range = &self.ranges[range];
+        rg::draw(
+            range.base_element as u32,
+            range.num_elements as u32,
+            n_instances,
+        );
+    }
+
+    /// Draws all the shapes
+    pub fn draw_all(&self, n_instances: u32) {
+        self.ibuf.draw(n_instances);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        match Shape::Sphere(Default::default()).with_defaults() {
+            Shape::Sphere(p) => assert_eq!((p.radius, p.slices, p.stacks), (0.5, 5, 4)),
+            _ => unreachable!(),
+        }
+
+        // explicit parameters are kept
+        let torus = TorusDesc {
+            sides: 3,
+            ..Default::default()
+        };
+        match Shape::Torus(torus).with_defaults() {
+            Shape::Torus(p) => assert_eq!((p.ring_radius, p.sides, p.rings), (0.2, 3, 5)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn build_default_sphere() {
+        let sphere = Shape::Sphere(Default::default());
+        let mut data = ShapeData::new();
+        let range = data.push(sphere).unwrap();
+        assert_eq!(sphere.sizes(), (data.verts.len(), data.indices.len()));
+        assert_eq!(range.num_elements as usize, data.indices.len());
+        assert!(!data.indices.is_empty());
+    }
+
+    #[test]
+    fn too_many_vertices() {
+        let sphere = Shape::Sphere(SphereDesc {
+            slices: 300,
+            stacks: 300,
+            ..Default::default()
+        });
+        let mut data = ShapeData::new();
+        assert!(matches!(
+            data.push(sphere),
+            Err(ShapeError::TooManyVertices { .. })
+        ));
+        assert_eq!(data, ShapeData::new());
+
+        // fits alone, but not merged twice
+        let sphere = Shape::Sphere(SphereDesc {
+            slices: 200,
+            stacks: 200,
+            ..Default::default()
+        });
+        data.push(sphere).unwrap();
+        let n_verts = data.verts.len();
+        assert!(matches!(
+            data.merge(sphere),
+            Err(ShapeError::TooManyVertices { .. })
+        ));
+        assert_eq!(data.verts.len(), n_verts);
+    }
+}
@@ -0,0 +1,632 @@
+/*!
+Immediate-mode 2D shapes with anti-aliased edges
+
+[`ShapeMesh`] tessellates rectangles, circles, arcs, polylines and polygons in Rust. Edges get a
+one-pixel fringe that fades out to transparent. [`Shapes`] uploads the mesh into stream buffers
+and draws it with a built-in pipeline:
+
+```no_run
+use rokol::{batch, gfx as rg, shapes::Shapes};
+
+let mut shapes = Shapes::new(1024);
+let pa = rg::PassAction::clear([0.1, 0.1, 0.1, 1.0]);
+
+rg::begin_default_pass(&pa, 1280, 720);
+
+shapes.begin(batch::ortho(1280.0, 720.0));
+shapes.fill_rect([10.0, 10.0], [200.0, 100.0], [255, 0, 0, 255]);
+shapes.stroke_circle([400.0, 300.0], 50.0, 2.0, [0, 255, 0, 255]);
+shapes.polyline(&[[0.0, 0.0], [50.0, 80.0], [100.0, 0.0]], 3.0, false, [255; 4]);
+shapes.end();
+
+rg::end_pass();
+rg::commit();
+```
+*/
+
+use std::{
+    f32::consts::PI,
+    ops::{Deref, DerefMut},
+};
+
+use crate as rokol;
+
+use crate::gfx::{
+    self as rg, BakedResource, Bindings, BlendFactor, LayoutDesc, Pipeline, Shader, ShaderStage,
+    StreamBuffer,
+};
+
+/// Vertex of the shape shader
+#[derive(LayoutDesc, Debug, Clone, Copy, Default, PartialEq)]
+#[repr(C)]
+pub struct ShapeVertex {
+    pub pos: [f32; 2],
+    pub color: [u8; 4],
+}
+
+type Vec2 = [f32; 2];
+
+fn sub(a: Vec2, b: Vec2) -> Vec2 {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn normalize(v: Vec2) -> Vec2 {
+    let len = (v[0] * v[0] + v[1] * v[1]).sqrt();
+    if len > 1e-6 {
+        [v[0] / len, v[1] / len]
+    } else {
+        [0.0, 0.0]
+    }
+}
+
+/// Twice the signed area (positive if counter-clockwise in the y-up coordinate system)
+fn signed_area2(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| cross(points[i], points[(i + 1) % points.len()]))
+        .sum()
+}
+
+fn is_convex(points: &[Vec2]) -> bool {
+    let n = points.len();
+    let mut sign = 0.0f32;
+    for i in 0..n {
+        let d0 = sub(points[(i + 1) % n], points[i]);
+        let d1 = sub(points[(i + 2) % n], points[(i + 1) % n]);
+        let c = cross(d0, d1);
+        if c.abs() < 1e-6 {
+            continue;
+        }
+        if sign == 0.0 {
+            sign = c.signum();
+        } else if c.signum() != sign {
+            return false;
+        }
+    }
+    true
+}
+
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d0 = cross(sub(b, a), sub(p, a));
+    let d1 = cross(sub(c, b), sub(p, b));
+    let d2 = cross(sub(a, c), sub(p, c));
+    let has_neg = d0 < 0.0 || d1 < 0.0 || d2 < 0.0;
+    let has_pos = d0 > 0.0 || d1 > 0.0 || d2 > 0.0;
+    !(has_neg && has_pos)
+}
+
+/// Triangulates a simple polygon by ear clipping
+fn triangulate(points: &[Vec2], out: &mut Vec<[usize; 3]>) {
+    let n = points.len();
+    if n < 3 {
+        return;
+    }
+
+    if self::is_convex(points) {
+        out.extend((1..n - 1).map(|i| [0, i, i + 1]));
+        return;
+    }
+
+    let sign = self::signed_area2(points).signum();
+    let mut rest = (0..n).collect::<Vec<_>>();
+
+    while rest.len() > 3 {
+        let m = rest.len();
+        let ear = (0..m).find(|&i| {
+            let (a, b, c) = (rest[(i + m - 1) % m], rest[i], rest[(i + 1) % m]);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+            // reflex vertex can't be an ear
+            if cross(sub(pb, pa), sub(pc, pb)) * sign <= 0.0 {
+                return false;
+            }
+            rest.iter()
+                .filter(|&&j| j != a && j != b && j != c)
+                .all(|&j| !self::in_triangle(points[j], pa, pb, pc))
+        });
+
+        match ear {
+            Some(i) => {
+                out.push([rest[(i + m - 1) % m], rest[i], rest[(i + 1) % m]]);
+                rest.remove(i);
+            }
+            None => {
+                // self-intersecting or degenerate; give up with a fan
+                out.extend((1..m - 1).map(|i| [rest[0], rest[i], rest[i + 1]]));
+                return;
+            }
+        }
+    }
+
+    out.push([rest[0], rest[1], rest[2]]);
+}
+
+/// Removes consecutive duplicate points (and the last point if it equals the first one)
+fn dedup(points: &[Vec2], closed: bool) -> Vec<Vec2> {
+    let mut out: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &p in points {
+        match out.last() {
+            Some(&last) if (p[0] - last[0]).abs() < 1e-6 && (p[1] - last[1]).abs() < 1e-6 => {}
+            _ => out.push(p),
+        }
+    }
+    if closed && out.len() > 1 {
+        let (first, last) = (out[0], out[out.len() - 1]);
+        if (first[0] - last[0]).abs() < 1e-6 && (first[1] - last[1]).abs() < 1e-6 {
+            out.pop();
+        }
+    }
+    out
+}
+
+/// Miter direction at a joint scaled so that the offset edges keep their distance
+fn miter(n0: Vec2, n1: Vec2) -> Vec2 {
+    let dm = [(n0[0] + n1[0]) * 0.5, (n0[1] + n1[1]) * 0.5];
+    let r2 = dm[0] * dm[0] + dm[1] * dm[1];
+    if r2 > 1e-6 {
+        // limit spikes on sharp corners
+        let scale = (1.0 / r2).min(16.0);
+        [dm[0] * scale, dm[1] * scale]
+    } else {
+        n0
+    }
+}
+
+/// Number of segments for a circle of radius `r` (error below a quarter pixel)
+pub fn circle_segments(r: f32) -> usize {
+    if r <= 0.5 {
+        return 8;
+    }
+    let n = (2.0 * PI / (1.0 - 0.25 / r).acos()).ceil();
+    (n as usize).clamp(8, 512)
+}
+
+/// Tessellated shapes (CPU side of [`Shapes`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeMesh {
+    pub verts: Vec<ShapeVertex>,
+    pub indices: Vec<u32>,
+    /// Width of the anti-aliasing fringe (`1.0` for pixel coordinates, `0.0` to disable)
+    pub aa: f32,
+}
+
+impl Default for ShapeMesh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShapeMesh {
+    pub fn new() -> Self {
+        Self {
+            verts: Vec::new(),
+            indices: Vec::new(),
+            aa: 1.0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.verts.clear();
+        self.indices.clear();
+    }
+
+    fn vert(&mut self, pos: Vec2, color: [u8; 4]) -> u32 {
+        let i = self.verts.len() as u32;
+        self.verts.push(ShapeVertex { pos, color });
+        i
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+
+    /// Fills a simple (convex or concave) polygon
+    pub fn fill_polygon(&mut self, points: &[Vec2], color: [u8; 4]) {
+        let points = self::dedup(points, true);
+        let n = points.len();
+        if n < 3 {
+            return;
+        }
+
+        // outward normals of the edges
+        let sign = self::signed_area2(&points).signum();
+        let normals = (0..n)
+            .map(|i| {
+                let d = self::normalize(sub(points[(i + 1) % n], points[i]));
+                [d[1] * sign, -d[0] * sign]
+            })
+            .collect::<Vec<_>>();
+
+        let transparent = [color[0], color[1], color[2], 0];
+        let half = self.aa * 0.5;
+
+        // inner ring (opaque), then outer ring (transparent)
+        let base = self.verts.len() as u32;
+        for i in 0..n {
+            let dm = self::miter(normals[(i + n - 1) % n], normals[i]);
+            self.vert(
+                [points[i][0] - dm[0] * half, points[i][1] - dm[1] * half],
+                color,
+            );
+        }
+        if self.aa > 0.0 {
+            for i in 0..n {
+                let dm = self::miter(normals[(i + n - 1) % n], normals[i]);
+                self.vert(
+                    [points[i][0] + dm[0] * half, points[i][1] + dm[1] * half],
+                    transparent,
+                );
+            }
+        }
+
+        let mut tris = Vec::with_capacity(n - 2);
+        self::triangulate(&points, &mut tris);
+        for [a, b, c] in tris {
+            self.indices
+                .extend_from_slice(&[base + a as u32, base + b as u32, base + c as u32]);
+        }
+
+        if self.aa > 0.0 {
+            let n = n as u32;
+            for i in 0..n {
+                let j = (i + 1) % n;
+                self.quad(base + i, base + n + i, base + n + j, base + j);
+            }
+        }
+    }
+
+    /// Strokes a polyline. `closed` connects the last point to the first one.
+    pub fn polyline(&mut self, points: &[Vec2], thickness: f32, closed: bool, color: [u8; 4]) {
+        let points = self::dedup(points, closed);
+        let n = points.len();
+        if n < 2 || thickness <= 0.0 {
+            return;
+        }
+
+        let n_edges = if closed { n } else { n - 1 };
+        let normals = (0..n_edges)
+            .map(|i| {
+                let d = self::normalize(sub(points[(i + 1) % n], points[i]));
+                [d[1], -d[0]]
+            })
+            .collect::<Vec<_>>();
+
+        // lines thinner than the fringe fade out instead of getting thinner
+        let mut color = color;
+        if thickness < self.aa {
+            color[3] = (color[3] as f32 * thickness / self.aa) as u8;
+        }
+        let transparent = [color[0], color[1], color[2], 0];
+
+        let core = (thickness * 0.5 - self.aa * 0.5).max(0.0);
+        let fringe = core + self.aa;
+
+        let base = self.verts.len() as u32;
+        for i in 0..n {
+            let dm = match (closed, i) {
+                (false, 0) => normals[0],
+                (false, i) if i == n - 1 => normals[n_edges - 1],
+                _ => self::miter(normals[(i + n_edges - 1) % n_edges], normals[i % n_edges]),
+            };
+            let p = points[i];
+            let at = |d: f32| [p[0] + dm[0] * d, p[1] + dm[1] * d];
+
+            self.vert(at(fringe), transparent);
+            self.vert(at(core), color);
+            self.vert(at(-core), color);
+            self.vert(at(-fringe), transparent);
+        }
+
+        for e in 0..n_edges as u32 {
+            let a = base + e * 4;
+            let b = base + ((e + 1) % n as u32) * 4;
+            // outer fringe, core, inner fringe
+            for k in 0..3 {
+                self.quad(a + k, b + k, b + k + 1, a + k + 1);
+            }
+        }
+    }
+
+    pub fn line(&mut self, a: Vec2, b: Vec2, thickness: f32, color: [u8; 4]) {
+        self.polyline(&[a, b], thickness, false, color);
+    }
+
+    /// Fills a rectangle at `pos` (top-left corner)
+    pub fn fill_rect(&mut self, pos: Vec2, size: Vec2, color: [u8; 4]) {
+        self.fill_polygon(&Self::rect_points(pos, size), color);
+    }
+
+    pub fn stroke_rect(&mut self, pos: Vec2, size: Vec2, thickness: f32, color: [u8; 4]) {
+        self.polyline(&Self::rect_points(pos, size), thickness, true, color);
+    }
+
+    fn rect_points(pos: Vec2, size: Vec2) -> [Vec2; 4] {
+        let [x, y] = pos;
+        let [w, h] = size;
+        [[x, y], [x + w, y], [x + w, y + h], [x, y + h]]
+    }
+
+    /// Points on an arc from `start` to `end` (radians)
+    pub fn arc_points(center: Vec2, r: f32, start: f32, end: f32) -> Vec<Vec2> {
+        let sweep = end - start;
+        let n =
+            ((self::circle_segments(r) as f32 * sweep.abs() / (2.0 * PI)).ceil() as usize).max(2);
+        (0..=n)
+            .map(|i| {
+                let t = start + sweep * i as f32 / n as f32;
+                [center[0] + r * t.cos(), center[1] + r * t.sin()]
+            })
+            .collect()
+    }
+
+    fn circle_points(center: Vec2, r: f32) -> Vec<Vec2> {
+        let n = self::circle_segments(r);
+        (0..n)
+            .map(|i| {
+                let t = 2.0 * PI * i as f32 / n as f32;
+                [center[0] + r * t.cos(), center[1] + r * t.sin()]
+            })
+            .collect()
+    }
+
+    pub fn fill_circle(&mut self, center: Vec2, r: f32, color: [u8; 4]) {
+        self.fill_polygon(&Self::circle_points(center, r), color);
+    }
+
+    pub fn stroke_circle(&mut self, center: Vec2, r: f32, thickness: f32, color: [u8; 4]) {
+        self.polyline(&Self::circle_points(center, r), thickness, true, color);
+    }
+
+    /// Strokes an arc from `start` to `end` (radians)
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        r: f32,
+        start: f32,
+        end: f32,
+        thickness: f32,
+        color: [u8; 4],
+    ) {
+        self.polyline(
+            &Self::arc_points(center, r, start, end),
+            thickness,
+            false,
+            color,
+        );
+    }
+
+    /// Fills a pie slice from `start` to `end` (radians)
+    pub fn fill_arc(&mut self, center: Vec2, r: f32, start: f32, end: f32, color: [u8; 4]) {
+        let mut points = Self::arc_points(center, r, start, end);
+        points.push(center);
+        self.fill_polygon(&points, color);
+    }
+}
+
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+mod shader {
+    pub const VS: &str = r#"#version 330
+
+uniform mat4 transform;
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec4 color0;
+
+out vec4 color;
+
+void main() {
+    gl_Position = transform * vec4(position, 0.0, 1.0);
+    color = color0;
+}
+"#;
+
+    pub const FS: &str = r#"#version 330
+
+in vec4 color;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = color;
+}
+"#;
+}
+
+/// Creates the built-in shape shader (`glcore33`)
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+pub fn shape_shader() -> Shader {
+    let vs = format!("{}\0", shader::VS);
+    let fs = format!("{}\0", shader::FS);
+
+    let mut desc = unsafe { rg::shader_desc(&vs, &fs) };
+
+    let ub = &mut desc.vs.uniform_blocks[0];
+    ub.size = std::mem::size_of::<[f32; 16]>() as _;
+    ub.uniforms[0].name = b"transform\0".as_ptr() as *const _;
+    ub.uniforms[0].type_ = rg::UniformType::Mat4.to_ffi();
+
+    desc.label = rg::intern_label("rokol-shape-shader");
+
+    Shader::create(&desc)
+}
+
+/// [`ShapeMesh`] drawn with stream vertex and index buffers
+///
+/// Dereferences to the [`ShapeMesh`] to push shapes.
+#[derive(Debug)]
+pub struct Shapes {
+    shd: Shader,
+    pip: Pipeline,
+    vbuf: StreamBuffer,
+    ibuf: StreamBuffer,
+    mesh: ShapeMesh,
+    transform: [f32; 16],
+}
+
+impl Drop for Shapes {
+    fn drop(&mut self) {
+        if unsafe { rokol::ffi::gfx::sg_isvalid() } {
+            Pipeline::destroy(self.pip);
+            Shader::destroy(self.shd);
+        }
+    }
+}
+
+impl Deref for Shapes {
+    type Target = ShapeMesh;
+    fn deref(&self) -> &Self::Target {
+        &self.mesh
+    }
+}
+
+impl DerefMut for Shapes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.mesh
+    }
+}
+
+impl Shapes {
+    /// Creates shapes renderer with the built-in shader. The buffers are initially sized for
+    /// `n_verts` vertices per frame and grow on demand.
+    #[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+    pub fn new(n_verts: usize) -> Self {
+        Self::with_shader(self::shape_shader(), n_verts)
+    }
+
+    /// Creates shapes renderer with a shader compatible with [`ShapeVertex`] and a `mat4`
+    /// uniform block in the vertex stage. The shader is destroyed with the renderer.
+    pub fn with_shader(shd: Shader, n_verts: usize) -> Self {
+        let mut pip_desc = rg::pipeline_desc::<ShapeVertex, u32>(shd);
+        let blend = &mut pip_desc.colors[0].blend;
+        blend.enabled = true;
+        blend.src_factor_rgb = BlendFactor::SrcAlpha.to_ffi();
+        blend.dst_factor_rgb = BlendFactor::OneMinusSrcAlpha.to_ffi();
+        pip_desc.label = rg::intern_label("rokol-shape-pipeline");
+        let pip = Pipeline::create(&pip_desc);
+
+        let n_verts = n_verts.max(1);
+        let vbuf = StreamBuffer::vertex(
+            std::mem::size_of::<ShapeVertex>() * n_verts,
+            "rokol-shape-vertices",
+        );
+        let ibuf = StreamBuffer::index(
+            std::mem::size_of::<u32>() * n_verts * 3,
+            "rokol-shape-indices",
+        );
+
+        Self {
+            shd,
+            pip,
+            vbuf,
+            ibuf,
+            mesh: ShapeMesh::new(),
+            transform: crate::batch::ortho(1.0, 1.0),
+        }
+    }
+
+    /// Starts pushing shapes with a column-major transform (e.g. [`crate::batch::ortho`])
+    pub fn begin(&mut self, transform: [f32; 16]) {
+        self.mesh.clear();
+        self.transform = transform;
+    }
+
+    /// Draws the shapes. Must be called inside a rendering pass.
+    pub fn end(&mut self) {
+        if self.mesh.indices.is_empty() {
+            return;
+        }
+
+        let (vs, is) = match (
+            self.vbuf.alloc(&self.mesh.verts),
+            self.ibuf.alloc(&self.mesh.indices),
+        ) {
+            (Some(vs), Some(is)) => (vs, is),
+            _ => {
+                log::warn!(
+                    "rokol: shapes dropped {} indices (the buffers grow on next frame)",
+                    self.mesh.indices.len()
+                );
+                self.mesh.clear();
+                return;
+            }
+        };
+
+        let mut bind = Bindings::default();
+        vs.set_vertex(&mut bind, 0);
+        is.set_index(&mut bind);
+
+        rg::apply_pipeline(self.pip);
+        rg::apply_bindings(&bind);
+        rg::apply_uniforms_as(ShaderStage::Vs, 0, &self.transform);
+        rg::draw(0, is.len as u32, 1);
+
+        self.mesh.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn area(mesh: &ShapeMesh, n_tris: usize) -> f32 {
+        mesh.indices
+            .chunks(3)
+            .take(n_tris)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.verts[i as usize].pos);
+                cross(sub(b, a), sub(c, a)).abs() * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn fill_concave() {
+        let mut mesh = ShapeMesh::new();
+        mesh.aa = 0.0;
+
+        // L-shape of area 3
+        let l = [
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [0.0, 2.0],
+        ];
+        mesh.fill_polygon(&l, [255; 4]);
+
+        assert_eq!(mesh.verts.len(), 6);
+        assert_eq!(mesh.indices.len(), 4 * 3);
+        assert!((area(&mesh, 4) - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn fringe() {
+        // anti-aliased by default
+        assert_eq!(ShapeMesh::default().aa, 1.0);
+
+        let mut mesh = ShapeMesh::new();
+        mesh.fill_rect([0.0, 0.0], [10.0, 10.0], [255; 4]);
+
+        // inner and outer rings, 2 triangles and 4 fringe quads
+        assert_eq!(mesh.verts.len(), 8);
+        assert_eq!(mesh.indices.len(), 2 * 3 + 4 * 6);
+        assert!(mesh.verts[..4].iter().all(|v| v.color[3] == 255));
+        assert!(mesh.verts[4..].iter().all(|v| v.color[3] == 0));
+
+        // the opaque area shrinks by half a pixel on each side
+        assert!((area(&mesh, 2) - 81.0).abs() < 1e-3);
+
+        mesh.clear();
+        mesh.polyline(
+            &[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]],
+            3.0,
+            false,
+            [255; 4],
+        );
+        assert_eq!(mesh.verts.len(), 3 * 4);
+        assert_eq!(mesh.indices.len(), 2 * 3 * 6);
+    }
+}
@@ -0,0 +1,205 @@
+/*!
+High-resolution timer with [`sokol_time.h`] (`impl-time` feature)
+
+Time is measured in ticks (nanoseconds on most platforms); convert them with [`duration`] or
+[`sec`]. [`FrameTimer`] measures frame durations independently of the application glue, so it
+works with both `glue::sapp` and `glue::sdl`:
+
+```no_run
+use rokol::time::FrameTimer;
+
+let mut timer = FrameTimer::new();
+loop {
+    let dt = timer.tick();
+    // update with `dt` and render
+    # break;
+}
+```
+
+[`sokol_time.h`]: https://github.com/floooh/sokol/blob/master/sokol_time.h
+*/
+
+use std::{sync::Once, time::Duration};
+
+use rokol_ffi::time as ffi;
+
+static SETUP: Once = Once::new();
+
+/// Sets up `sokol_time.h`. Called implicitly by the functions reading the clock.
+pub fn setup() {
+    SETUP.call_once(|| unsafe { ffi::stm_setup() });
+}
+
+/// Current time in ticks
+pub fn now() -> u64 {
+    self::setup();
+    unsafe { ffi::stm_now() }
+}
+
+/// `new - old` in ticks (never negative)
+pub fn diff(new: u64, old: u64) -> u64 {
+    unsafe { ffi::stm_diff(new, old) }
+}
+
+/// Ticks elapsed since `start`
+pub fn since(start: u64) -> u64 {
+    self::setup();
+    unsafe { ffi::stm_since(start) }
+}
+
+/// Ticks elapsed since `last` and updates `last` to now. Returns zero if `last` is zero.
+pub fn laptime(last: &mut u64) -> u64 {
+    self::setup();
+    unsafe { ffi::stm_laptime(last) }
+}
+
+/// Rounds a frame duration to a common refresh rate (60Hz, 120Hz, ..) if it's close to it.
+/// Reduces jitter of measured frame durations with vsync.
+pub fn round_to_common_refresh_rate(frame_ticks: u64) -> u64 {
+    unsafe { ffi::stm_round_to_common_refresh_rate(frame_ticks) }
+}
+
+pub fn sec(ticks: u64) -> f64 {
+    unsafe { ffi::stm_sec(ticks) }
+}
+
+pub fn ms(ticks: u64) -> f64 {
+    unsafe { ffi::stm_ms(ticks) }
+}
+
+pub fn us(ticks: u64) -> f64 {
+    unsafe { ffi::stm_us(ticks) }
+}
+
+pub fn ns(ticks: u64) -> f64 {
+    unsafe { ffi::stm_ns(ticks) }
+}
+
+/// Converts ticks to [`Duration`]
+pub fn duration(ticks: u64) -> Duration {
+    Duration::from_nanos(self::ns(ticks) as u64)
+}
+
+/// Measures frame durations. Call [`FrameTimer::tick`] once per frame.
+#[derive(Debug, Clone)]
+pub struct FrameTimer {
+    start: u64,
+    last: u64,
+    /// Duration of the last frame in ticks
+    dt: u64,
+    /// Smoothed frame duration in seconds
+    avg_dt: f64,
+    n_frames: u64,
+    /// Rounds frame durations to common refresh rates (on by default)
+    pub round_to_refresh_rate: bool,
+}
+
+impl Default for FrameTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameTimer {
+    pub fn new() -> Self {
+        let now = self::now();
+        Self {
+            start: now,
+            last: now,
+            dt: 0,
+            avg_dt: 0.0,
+            n_frames: 0,
+            round_to_refresh_rate: true,
+        }
+    }
+
+    /// Starts a new frame and returns the duration of the last one
+    pub fn tick(&mut self) -> Duration {
+        let mut dt = self::laptime(&mut self.last);
+        if self.round_to_refresh_rate {
+            dt = self::round_to_common_refresh_rate(dt);
+        }
+
+        self.dt = dt;
+        self.n_frames += 1;
+
+        let secs = self::sec(dt);
+        self.avg_dt = if self.avg_dt == 0.0 {
+            secs
+        } else {
+            self.avg_dt * 0.95 + secs * 0.05
+        };
+
+        self::duration(dt)
+    }
+
+    /// Duration of the last frame
+    pub fn dt(&self) -> Duration {
+        self::duration(self.dt)
+    }
+
+    /// Duration of the last frame in seconds
+    pub fn dt_secs(&self) -> f32 {
+        self::sec(self.dt) as f32
+    }
+
+    /// Time since the creation of the timer
+    pub fn elapsed(&self) -> Duration {
+        self::duration(self::since(self.start))
+    }
+
+    /// Number of [`FrameTimer::tick`] calls
+    pub fn frame_count(&self) -> u64 {
+        self.n_frames
+    }
+
+    /// Smoothed frames per second
+    pub fn fps(&self) -> f64 {
+        if self.avg_dt > 0.0 {
+            1.0 / self.avg_dt
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions_agree() {
+        for ticks in [0, 1, 999, 16_666_667, 1_000_000_000, 3_600_000_000_000] {
+            let secs = self::duration(ticks).as_secs_f64();
+            assert!((secs - self::sec(ticks)).abs() < 1e-6, "{} ticks", ticks);
+        }
+    }
+
+    #[test]
+    fn frame_timer() {
+        let mut timer = FrameTimer::new();
+        timer.round_to_refresh_rate = false;
+        assert_eq!(timer.frame_count(), 0);
+        assert_eq!(timer.fps(), 0.0);
+        assert_eq!(timer.dt(), Duration::ZERO);
+
+        let mut total = Duration::ZERO;
+        let mut elapsed = timer.elapsed();
+        for i in 1..=3 {
+            std::thread::sleep(Duration::from_millis(2));
+            let dt = timer.tick();
+            assert_eq!(timer.frame_count(), i);
+            assert_eq!(timer.dt(), dt);
+            assert!(dt >= Duration::from_millis(2));
+
+            total += dt;
+            let now = timer.elapsed();
+            assert!(now >= elapsed);
+            assert!(now >= total);
+            elapsed = now;
+
+            let fps = timer.fps();
+            assert!(fps.is_finite() && fps > 0.0, "fps: {}", fps);
+        }
+    }
+}
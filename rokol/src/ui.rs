@@ -0,0 +1,108 @@
+/*!
+Shader and pipeline shared by the immediate-mode GUI renderers ([`imgui`](crate::imgui) and
+[`egui`](crate::egui))
+
+Both draw 2D vertices (position, UV and RGBA8 color) with a `mat4` transform in the vertex stage
+and one 2D texture in the fragment stage.
+*/
+
+use crate as rokol;
+
+use crate::gfx::{
+    self as rg, BakedResource, BlendState, Index, Pipeline, Shader, ShaderStage, VertexLayout,
+};
+
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+mod shader {
+    pub const VS: &str = r#"#version 330
+
+uniform mat4 transform;
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 texcoord0;
+layout(location = 2) in vec4 color0;
+
+out vec2 uv;
+out vec4 color;
+
+void main() {
+    gl_Position = transform * vec4(position, 0.0, 1.0);
+    uv = texcoord0;
+    color = color0;
+}
+"#;
+
+    pub const FS: &str = r#"#version 330
+
+uniform sampler2D tex;
+
+in vec2 uv;
+in vec4 color;
+
+out vec4 frag_color;
+
+void main() {
+    frag_color = color * texture(tex, uv);
+}
+"#;
+}
+
+/// Creates the built-in textured 2D shader (`glcore33`)
+#[cfg(any(rokol_gfx = "glcore33", rokol_gfx = "dummy"))]
+pub(crate) fn shader(label: &str) -> Shader {
+    let vs = format!("{}\0", shader::VS);
+    let fs = format!("{}\0", shader::FS);
+
+    let mut desc = unsafe { rg::shader_desc(&vs, &fs) };
+
+    let ub = &mut desc.vs.uniform_blocks[0];
+    ub.size = std::mem::size_of::<[f32; 16]>() as _;
+    ub.uniforms[0].name = b"transform\0".as_ptr() as *const _;
+    ub.uniforms[0].type_ = rg::UniformType::Mat4.to_ffi();
+
+    desc.fs.images[0].name = b"tex\0".as_ptr() as *const _;
+    desc.fs.images[0].image_type = rg::ImageType::Dim2.to_ffi();
+    desc.fs.images[0].sampler_type = rg::SamplerType::Float.to_ffi();
+
+    desc.label = rg::intern_label(label);
+
+    Shader::create(&desc)
+}
+
+/// Owned shader and pipeline of a GUI renderer
+#[derive(Debug)]
+pub(crate) struct UiPipeline {
+    shd: Shader,
+    pip: Pipeline,
+}
+
+impl Drop for UiPipeline {
+    fn drop(&mut self) {
+        if unsafe { rokol::ffi::gfx::sg_isvalid() } {
+            Pipeline::destroy(self.pip);
+            Shader::destroy(self.shd);
+        }
+    }
+}
+
+impl UiPipeline {
+    /// Creates a pipeline of vertex `V` and index `I` with the given blend state. The shader is
+    /// destroyed with the pipeline.
+    pub fn create<V: VertexLayout, I: Index>(shd: Shader, blend: BlendState, label: &str) -> Self {
+        let mut pip_desc = rg::pipeline_desc::<V, I>(shd);
+        pip_desc.colors[0].blend = BlendState {
+            enabled: true,
+            ..blend
+        };
+        pip_desc.label = rg::intern_label(label);
+        let pip = Pipeline::create(&pip_desc);
+
+        Self { shd, pip }
+    }
+
+    /// Applies the pipeline and the transform (again after user callbacks)
+    pub fn apply(&self, transform: &[f32; 16]) {
+        rg::apply_pipeline(self.pip);
+        rg::apply_uniforms_as(ShaderStage::Vs, 0, transform);
+    }
+}
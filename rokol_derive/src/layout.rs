@@ -1,6 +1,6 @@
 use {proc_macro2::TokenStream as TokenStream2, quote::*, syn::*};
 
-// implements `fn layout_desc`
+// implements `fn layout_desc`, `Pod` and `VertexLayout`
 pub fn impl_vertex_layout(ast: DeriveInput) -> TokenStream2 {
     let ty_name = &ast.ident;
 
@@ -74,6 +74,7 @@ pub fn impl_vertex_layout(ast: DeriveInput) -> TokenStream2 {
     });
 
     let i = 0usize..fields.named.len();
+    let tys = fields.named.iter().map(|field| &field.ty);
 
     quote! {
         impl #ty_name {
@@ -85,5 +86,19 @@ pub fn impl_vertex_layout(ast: DeriveInput) -> TokenStream2 {
                 desc
             }
         }
+
+        // the fields are plain data, so the struct is `Pod` unless it has padding
+        const _: () = assert!(
+            std::mem::size_of::<#ty_name>() == 0 #(+ std::mem::size_of::<#tys>())*,
+            "`#[derive(LayoutDesc)]` doesn't support structs with padding bytes",
+        );
+
+        unsafe impl rokol::gfx::Pod for #ty_name {}
+
+        unsafe impl rokol::gfx::VertexLayout for #ty_name {
+            fn layout_desc() -> rokol::gfx::LayoutDesc {
+                <#ty_name>::layout_desc()
+            }
+        }
     }
 }
@@ -15,8 +15,16 @@ fn main() {
     println!("cargo:rerun-if-changed=sokol");
     println!("cargo:rerun-if-changed=wrappers");
 
-    if !cfg!(feature = "impl-app") && !cfg!(feature = "impl-gfx") {
-        panic!("use at least one of `impl-app` or `impl-gfx` feature flag");
+    if !cfg!(feature = "impl-app")
+        && !cfg!(feature = "impl-gfx")
+        && !cfg!(feature = "impl-time")
+        && !cfg!(feature = "impl-audio")
+        && !cfg!(feature = "impl-fetch")
+        && !cfg!(feature = "impl-args")
+    {
+        panic!(
+            "use at least one of `impl-app`, `impl-gfx`, `impl-time`, `impl-audio`, `impl-fetch` or `impl-args` feature flag"
+        );
     }
 
     let mut build = Build::new();
@@ -66,6 +74,107 @@ fn main() {
          );
     }
 
+    if cfg!(feature = "impl-time") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_time.h"),
+             root.join("src/time.rs"),
+             args,
+             "//! Rust FFI to [sokol_time.h](https://github.com/floooh/sokol/blob/master/sokol_time.h)",
+             |b| b.allowlist_function("stm_.*").allowlist_var("SOKOL_TIME_INCLUDED"),
+         );
+    }
+
+    if cfg!(feature = "impl-audio") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_audio.h"),
+             root.join("src/audio.rs"),
+             args,
+             "//! Rust FFI to [sokol_audio.h](https://github.com/floooh/sokol/blob/master/sokol_audio.h)",
+             |b| b.derive_default(true).derive_partialeq(true).derive_eq(true)
+                     .allowlist_function("saudio_.*")
+                     .allowlist_type("saudio_.*")
+                     .allowlist_var("SOKOL_AUDIO_INCLUDED"),
+         );
+    }
+
+    if cfg!(feature = "impl-fetch") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_fetch.h"),
+             root.join("src/fetch.rs"),
+             args,
+             "//! Rust FFI to [sokol_fetch.h](https://github.com/floooh/sokol/blob/master/sokol_fetch.h)",
+             |b| b.derive_default(true).derive_partialeq(true).derive_eq(true)
+                     .default_enum_style(bindgen::EnumVariation::Rust {
+                 non_exhaustive: true,
+             })
+                     .allowlist_function("sfetch_.*")
+                     .allowlist_type("sfetch_.*")
+                     .allowlist_var("SFETCH_.*")
+                     .allowlist_var("SOKOL_FETCH_INCLUDED"),
+         );
+    }
+
+    if cfg!(feature = "impl-args") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_args.h"),
+             root.join("src/args.rs"),
+             args,
+             "//! Rust FFI to [sokol_args.h](https://github.com/floooh/sokol/blob/master/sokol_args.h)",
+             |b| b.derive_default(true).derive_partialeq(true).derive_eq(true)
+                     .allowlist_function("sargs_.*")
+                     .allowlist_type("sargs_.*")
+                     .allowlist_var("SOKOL_ARGS_INCLUDED"),
+         );
+    }
+
+    if cfg!(feature = "impl-gl") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_gl.h"),
+             root.join("src/gl.rs"),
+             args,
+             "//! Rust FFI to [sokol_gl.h](https://github.com/floooh/sokol/blob/master/util/sokol_gl.h)",
+             |b| self::util_builder(b, "sgl_"),
+         );
+    }
+
+    if cfg!(feature = "impl-debugtext") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_debugtext.h"),
+             root.join("src/debugtext.rs"),
+             args,
+             "//! Rust FFI to [sokol_debugtext.h](https://github.com/floooh/sokol/blob/master/util/sokol_debugtext.h)",
+             // `va_list` is not portable
+             |b| self::util_builder(b, "sdtx_").blocklist_function("sdtx_vprintf"),
+         );
+    }
+
+    if cfg!(feature = "impl-shape") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_shape.h"),
+             root.join("src/shape.rs"),
+             args,
+             "//! Rust FFI to [sokol_shape.h](https://github.com/floooh/sokol/blob/master/util/sokol_shape.h)",
+             |b| self::util_builder(b, "sshape_"),
+         );
+    }
+
+    if cfg!(feature = "impl-gfx-imgui") {
+        self::gen_bindings(
+             root.join("wrappers/rokol_gfx_imgui.h"),
+             root.join("src/gfx_imgui.rs"),
+             args,
+             "//! Rust FFI to [sokol_gfx_imgui.h](https://github.com/floooh/sokol/blob/master/util/sokol_gfx_imgui.h)",
+             // `sg_imgui_t` is opaque
+             |b| b.derive_default(true).derive_partialeq(true).derive_eq(true)
+                     .default_enum_style(bindgen::EnumVariation::Rust {
+                 non_exhaustive: true,
+             })
+                     .allowlist_function("sg_imgui_.*")
+                     .allowlist_function("rokol_sg_imgui_.*")
+                     .allowlist_type("rokol_sg_imgui_.*"),
+         );
+    }
+
     // compile and link to them
     self::compile(&mut build, is_msvc, &renderer, is_debug);
 }
@@ -76,6 +185,8 @@ enum Renderer {
     D3D11,
     Metal,
     GlCore33,
+    /// Headless backend that does nothing (for tests and call stream replays)
+    Dummy,
 }
 
 impl Renderer {
@@ -87,6 +198,8 @@ impl Renderer {
             Self::Metal
         } else if cfg!(feature = "d3d11") {
             Self::D3D11
+        } else if cfg!(feature = "dummy") {
+            Self::Dummy
         } else {
             // select default renderer
             // - Windows: D3D11 with MSVC, GLCORE33 otherwise
@@ -108,6 +221,7 @@ impl Renderer {
             Self::D3D11 => "SOKOL_D3D11",
             Self::Metal => "SOKOL_METAL",
             Self::GlCore33 => "SOKOL_GLCORE33",
+            Self::Dummy => "SOKOL_DUMMY_BACKEND",
         }
     }
 
@@ -118,10 +232,29 @@ impl Renderer {
             Self::D3D11 => println!("cargo:gfx=\"d3d11\""),
             Self::Metal => println!("cargo:gfx=\"metal\""),
             Self::GlCore33 => println!("cargo:gfx=\"glcore33\""),
+            Self::Dummy => println!("cargo:gfx=\"dummy\""),
         }
     }
 }
 
+/// Sets up bindgen for a header in `sokol/util`, reusing types in `sokol_gfx.h` from `crate::gfx`
+fn util_builder(b: bindgen::Builder, prefix: &str) -> bindgen::Builder {
+    let pattern = format!("{}.*", prefix);
+    b.derive_default(true)
+        .derive_partialeq(true)
+        .derive_eq(true)
+        .default_enum_style(bindgen::EnumVariation::Rust {
+            non_exhaustive: true,
+        })
+        .allowlist_function(&pattern)
+        .allowlist_type(&pattern)
+        .allowlist_var(&pattern.to_uppercase())
+        .allowlist_var("SOKOL_.*_INCLUDED")
+        .blocklist_type("sg_.*")
+        .raw_line("")
+        .raw_line("use crate::gfx::*;")
+}
+
 /// Generates Rust FFI using a wrapper header file
 fn gen_bindings(
     wrapper: impl AsRef<Path>,
@@ -135,11 +268,11 @@ fn gen_bindings(
         .clang_args(args)
         .parse_callbacks(Box::new(bindgen::CargoCallbacks));
 
-    let gen = setup_builder(gen);
     let gen = gen
         .raw_line(docstring)
         .raw_line("")
         .raw_line(r"#![allow(warnings)]");
+    let gen = setup_builder(gen);
 
     let gen = gen.generate().unwrap_or_else(|err| {
         panic!(
@@ -187,6 +320,50 @@ fn compile(build: &mut Build, is_msvc: bool, renderer: &Renderer, will_set_debug
         }
     }
 
+    if cfg!(feature = "impl-time") {
+        build.file(root.join("wrappers/rokol_time_impl.c"));
+    }
+
+    if cfg!(feature = "impl-audio") {
+        build.file(root.join("wrappers/rokol_audio_impl.c"));
+    }
+
+    if cfg!(feature = "impl-fetch") {
+        build.file(root.join("wrappers/rokol_fetch_impl.c"));
+    }
+
+    if cfg!(feature = "impl-args") {
+        build.file(root.join("wrappers/rokol_args_impl.c"));
+    }
+
+    // utilities on top of `sokol_gfx.h`
+    if cfg!(feature = "impl-gl") {
+        build.file(root.join("wrappers/rokol_gl_impl.c"));
+    }
+    if cfg!(feature = "impl-debugtext") {
+        build.file(root.join("wrappers/rokol_debugtext_impl.c"));
+    }
+    if cfg!(feature = "impl-shape") {
+        build.file(root.join("wrappers/rokol_shape_impl.c"));
+    }
+    if cfg!(feature = "impl-gfx-imgui") {
+        // `cimgui.h` and the defines that `imgui-sys` was compiled with
+        let third_party = env::var("DEP_IMGUI_THIRD_PARTY")
+            .expect("`imgui-sys` doesn't provide `DEP_IMGUI_THIRD_PARTY`");
+        build.include(third_party);
+        for (key, value) in env::vars() {
+            if let Some(name) = key.strip_prefix("DEP_IMGUI_DEFINE_") {
+                let value = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.as_str())
+                };
+                build.define(name, value);
+            }
+        }
+        build.file(root.join("wrappers/rokol_gfx_imgui_impl.c"));
+    }
+
     // TODO: supply only required flags for requested header
 
     // #define SOKOL_<RENDERER>
@@ -247,6 +424,7 @@ fn compile(build: &mut Build, is_msvc: bool, renderer: &Renderer, will_set_debug
                     println!("cargo:rustc-link-lib=framework=OpenGL");
                 }
                 Renderer::D3D11 => panic!("Trying to use D3D11 on macOS"),
+                Renderer::Dummy => {}
             }
         }
 
@@ -261,6 +439,28 @@ fn compile(build: &mut Build, is_msvc: bool, renderer: &Renderer, will_set_debug
         }
     }
 
+    if cfg!(feature = "impl-audio") {
+        if cfg!(target_os = "macos") {
+            println!("cargo:rustc-link-lib=framework=AudioToolbox");
+        }
+
+        if cfg!(target_os = "linux") {
+            println!("cargo:rustc-link-lib=dylib=asound");
+            println!("cargo:rustc-link-lib=dylib=pthread");
+        }
+
+        if cfg!(target_os = "windows") {
+            println!("cargo:rustc-link-lib=dylib=ole32");
+        }
+    }
+
+    if cfg!(feature = "impl-fetch") {
+        // I/O threads
+        if cfg!(target_os = "linux") {
+            println!("cargo:rustc-link-lib=dylib=pthread");
+        }
+    }
+
     if cfg!(feature = "impl-app") {
         //
     }
@@ -0,0 +1,119 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_args.h](https://github.com/floooh/sokol/blob/master/sokol_args.h)
+
+#![allow(warnings)]
+
+pub const SOKOL_ARGS_INCLUDED: u32 = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sargs_desc {
+    pub argc: ::std::os::raw::c_int,
+    pub argv: *mut *mut ::std::os::raw::c_char,
+    pub max_args: ::std::os::raw::c_int,
+    pub buf_size: ::std::os::raw::c_int,
+}
+#[test]
+fn bindgen_test_layout_sargs_desc() {
+    assert_eq!(
+        ::std::mem::size_of::<sargs_desc>(),
+        24usize,
+        concat!("Size of: ", stringify!(sargs_desc))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sargs_desc>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sargs_desc))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sargs_desc>())).argc as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sargs_desc),
+            "::",
+            stringify!(argc)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sargs_desc>())).argv as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sargs_desc),
+            "::",
+            stringify!(argv)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sargs_desc>())).max_args as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sargs_desc),
+            "::",
+            stringify!(max_args)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sargs_desc>())).buf_size as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sargs_desc),
+            "::",
+            stringify!(buf_size)
+        )
+    );
+}
+impl Default for sargs_desc {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+extern "C" {
+    pub fn sargs_setup(desc: *const sargs_desc);
+}
+extern "C" {
+    pub fn sargs_shutdown();
+}
+extern "C" {
+    pub fn sargs_isvalid() -> bool;
+}
+extern "C" {
+    pub fn sargs_exists(key: *const ::std::os::raw::c_char) -> bool;
+}
+extern "C" {
+    pub fn sargs_value(key: *const ::std::os::raw::c_char) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn sargs_value_def(
+        key: *const ::std::os::raw::c_char,
+        def: *const ::std::os::raw::c_char,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn sargs_equals(
+        key: *const ::std::os::raw::c_char,
+        val: *const ::std::os::raw::c_char,
+    ) -> bool;
+}
+extern "C" {
+    pub fn sargs_boolean(key: *const ::std::os::raw::c_char) -> bool;
+}
+extern "C" {
+    pub fn sargs_find(key: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn sargs_num_args() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn sargs_key_at(index: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn sargs_value_at(index: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
+}
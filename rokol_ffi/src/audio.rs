@@ -0,0 +1,87 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_audio.h](https://github.com/floooh/sokol/blob/master/sokol_audio.h)
+
+#![allow(warnings)]
+
+pub const SOKOL_AUDIO_INCLUDED: u32 = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct saudio_desc {
+    pub sample_rate: ::std::os::raw::c_int,
+    pub num_channels: ::std::os::raw::c_int,
+    pub buffer_frames: ::std::os::raw::c_int,
+    pub packet_frames: ::std::os::raw::c_int,
+    pub num_packets: ::std::os::raw::c_int,
+    pub stream_cb: ::std::option::Option<
+        unsafe extern "C" fn(
+            buffer: *mut f32,
+            num_frames: ::std::os::raw::c_int,
+            num_channels: ::std::os::raw::c_int,
+        ),
+    >,
+    pub stream_userdata_cb: ::std::option::Option<
+        unsafe extern "C" fn(
+            buffer: *mut f32,
+            num_frames: ::std::os::raw::c_int,
+            num_channels: ::std::os::raw::c_int,
+            user_data: *mut ::std::os::raw::c_void,
+        ),
+    >,
+    pub user_data: *mut ::std::os::raw::c_void,
+}
+#[test]
+fn bindgen_test_layout_saudio_desc() {
+    assert_eq!(
+        ::std::mem::size_of::<saudio_desc>(),
+        48usize,
+        concat!("Size of: ", stringify!(saudio_desc))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<saudio_desc>(),
+        8usize,
+        concat!("Alignment of ", stringify!(saudio_desc))
+    );
+}
+impl Default for saudio_desc {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+extern "C" {
+    pub fn saudio_setup(desc: *const saudio_desc);
+}
+extern "C" {
+    pub fn saudio_shutdown();
+}
+extern "C" {
+    pub fn saudio_isvalid() -> bool;
+}
+extern "C" {
+    pub fn saudio_userdata() -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn saudio_query_desc() -> saudio_desc;
+}
+extern "C" {
+    pub fn saudio_sample_rate() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn saudio_buffer_frames() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn saudio_channels() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn saudio_expect() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn saudio_push(
+        frames: *const f32,
+        num_frames: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
@@ -0,0 +1,250 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_debugtext.h](https://github.com/floooh/sokol/blob/master/util/sokol_debugtext.h)
+
+#![allow(warnings)]
+
+use crate::gfx::*;
+
+pub const SOKOL_DEBUGTEXT_INCLUDED: u32 = 1;
+pub const SDTX_MAX_FONTS: u32 = 8;
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sdtx_context {
+    pub id: u32,
+}
+#[test]
+fn bindgen_test_layout_sdtx_context() {
+    assert_eq!(
+        ::std::mem::size_of::<sdtx_context>(),
+        4usize,
+        concat!("Size of: ", stringify!(sdtx_context))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sdtx_context>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sdtx_context))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sdtx_range {
+    pub ptr: *const ::std::os::raw::c_void,
+    pub size: size_t,
+}
+#[test]
+fn bindgen_test_layout_sdtx_range() {
+    assert_eq!(
+        ::std::mem::size_of::<sdtx_range>(),
+        16usize,
+        concat!("Size of: ", stringify!(sdtx_range))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sdtx_range>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sdtx_range))
+    );
+}
+impl Default for sdtx_range {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sdtx_font_desc_t {
+    pub data: sdtx_range,
+    pub first_char: u8,
+    pub last_char: u8,
+}
+#[test]
+fn bindgen_test_layout_sdtx_font_desc_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sdtx_font_desc_t>(),
+        24usize,
+        concat!("Size of: ", stringify!(sdtx_font_desc_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sdtx_font_desc_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sdtx_font_desc_t))
+    );
+}
+impl Default for sdtx_font_desc_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct sdtx_context_desc_t {
+    pub char_buf_size: ::std::os::raw::c_int,
+    pub canvas_width: f32,
+    pub canvas_height: f32,
+    pub tab_width: ::std::os::raw::c_int,
+    pub color_format: sg_pixel_format,
+    pub depth_format: sg_pixel_format,
+    pub sample_count: ::std::os::raw::c_int,
+}
+#[test]
+fn bindgen_test_layout_sdtx_context_desc_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sdtx_context_desc_t>(),
+        28usize,
+        concat!("Size of: ", stringify!(sdtx_context_desc_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sdtx_context_desc_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sdtx_context_desc_t))
+    );
+}
+impl Default for sdtx_context_desc_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct sdtx_desc_t {
+    pub context_pool_size: ::std::os::raw::c_int,
+    pub printf_buf_size: ::std::os::raw::c_int,
+    pub fonts: [sdtx_font_desc_t; 8usize],
+    pub context: sdtx_context_desc_t,
+}
+#[test]
+fn bindgen_test_layout_sdtx_desc_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sdtx_desc_t>(),
+        232usize,
+        concat!("Size of: ", stringify!(sdtx_desc_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sdtx_desc_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sdtx_desc_t))
+    );
+}
+impl Default for sdtx_desc_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+extern "C" {
+    pub fn sdtx_setup(desc: *const sdtx_desc_t);
+}
+extern "C" {
+    pub fn sdtx_shutdown();
+}
+extern "C" {
+    pub fn sdtx_font_kc853() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_font_kc854() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_font_z1013() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_font_cpc() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_font_c64() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_font_oric() -> sdtx_font_desc_t;
+}
+extern "C" {
+    pub fn sdtx_make_context(desc: *const sdtx_context_desc_t) -> sdtx_context;
+}
+extern "C" {
+    pub fn sdtx_destroy_context(ctx: sdtx_context);
+}
+extern "C" {
+    pub fn sdtx_set_context(ctx: sdtx_context);
+}
+extern "C" {
+    pub fn sdtx_get_context() -> sdtx_context;
+}
+extern "C" {
+    pub fn sdtx_default_context() -> sdtx_context;
+}
+extern "C" {
+    pub fn sdtx_draw();
+}
+extern "C" {
+    pub fn sdtx_font(font_index: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn sdtx_canvas(w: f32, h: f32);
+}
+extern "C" {
+    pub fn sdtx_origin(x: f32, y: f32);
+}
+extern "C" {
+    pub fn sdtx_home();
+}
+extern "C" {
+    pub fn sdtx_pos(x: f32, y: f32);
+}
+extern "C" {
+    pub fn sdtx_pos_x(x: f32);
+}
+extern "C" {
+    pub fn sdtx_pos_y(y: f32);
+}
+extern "C" {
+    pub fn sdtx_move(dx: f32, dy: f32);
+}
+extern "C" {
+    pub fn sdtx_move_x(dx: f32);
+}
+extern "C" {
+    pub fn sdtx_move_y(dy: f32);
+}
+extern "C" {
+    pub fn sdtx_crlf();
+}
+extern "C" {
+    pub fn sdtx_color3b(r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sdtx_color3f(r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sdtx_color4b(r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sdtx_color4f(r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sdtx_color1i(rgba: u32);
+}
+extern "C" {
+    pub fn sdtx_putc(c: ::std::os::raw::c_char);
+}
+extern "C" {
+    pub fn sdtx_puts(str_: *const ::std::os::raw::c_char);
+}
+extern "C" {
+    pub fn sdtx_putr(str_: *const ::std::os::raw::c_char, len: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn sdtx_printf(fmt: *const ::std::os::raw::c_char, ...) -> ::std::os::raw::c_int;
+}
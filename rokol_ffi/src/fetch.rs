@@ -0,0 +1,508 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_fetch.h](https://github.com/floooh/sokol/blob/master/sokol_fetch.h)
+
+#![allow(warnings)]
+
+pub const SOKOL_FETCH_INCLUDED: u32 = 1;
+pub const SFETCH_MAX_PATH: u32 = 1024;
+pub const SFETCH_MAX_USERDATA_UINT64: u32 = 16;
+pub const SFETCH_MAX_CHANNELS: u32 = 16;
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sfetch_desc_t {
+    pub _start_canary: u32,
+    pub max_requests: u32,
+    pub num_channels: u32,
+    pub num_lanes: u32,
+    pub _end_canary: u32,
+}
+#[test]
+fn bindgen_test_layout_sfetch_desc_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sfetch_desc_t>(),
+        20usize,
+        concat!("Size of: ", stringify!(sfetch_desc_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sfetch_desc_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sfetch_desc_t))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_desc_t>()))._start_canary as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_desc_t),
+            "::",
+            stringify!(_start_canary)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_desc_t>())).max_requests as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_desc_t),
+            "::",
+            stringify!(max_requests)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_desc_t>())).num_channels as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_desc_t),
+            "::",
+            stringify!(num_channels)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_desc_t>())).num_lanes as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_desc_t),
+            "::",
+            stringify!(num_lanes)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_desc_t>()))._end_canary as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_desc_t),
+            "::",
+            stringify!(_end_canary)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sfetch_handle_t {
+    pub id: u32,
+}
+#[test]
+fn bindgen_test_layout_sfetch_handle_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sfetch_handle_t>(),
+        4usize,
+        concat!("Size of: ", stringify!(sfetch_handle_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sfetch_handle_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sfetch_handle_t))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_handle_t>())).id as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_handle_t),
+            "::",
+            stringify!(id)
+        )
+    );
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum sfetch_error_t {
+    SFETCH_ERROR_NO_ERROR = 0,
+    SFETCH_ERROR_FILE_NOT_FOUND = 1,
+    SFETCH_ERROR_NO_BUFFER = 2,
+    SFETCH_ERROR_BUFFER_TOO_SMALL = 3,
+    SFETCH_ERROR_UNEXPECTED_EOF = 4,
+    SFETCH_ERROR_INVALID_HTTP_STATUS = 5,
+    SFETCH_ERROR_CANCELLED = 6,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sfetch_response_t {
+    pub handle: sfetch_handle_t,
+    pub dispatched: bool,
+    pub fetched: bool,
+    pub paused: bool,
+    pub finished: bool,
+    pub failed: bool,
+    pub cancelled: bool,
+    pub error_code: sfetch_error_t,
+    pub channel: u32,
+    pub lane: u32,
+    pub path: *const ::std::os::raw::c_char,
+    pub user_data: *mut ::std::os::raw::c_void,
+    pub fetched_offset: u32,
+    pub fetched_size: u32,
+    pub buffer_ptr: *mut ::std::os::raw::c_void,
+    pub buffer_size: u32,
+}
+#[test]
+fn bindgen_test_layout_sfetch_response_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sfetch_response_t>(),
+        64usize,
+        concat!("Size of: ", stringify!(sfetch_response_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sfetch_response_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sfetch_response_t))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).handle as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(handle)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).dispatched as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(dispatched)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).fetched as *const _ as usize },
+        5usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(fetched)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).paused as *const _ as usize },
+        6usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(paused)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).finished as *const _ as usize },
+        7usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(finished)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).failed as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(failed)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).cancelled as *const _ as usize },
+        9usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(cancelled)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).error_code as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(error_code)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).channel as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(channel)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).lane as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(lane)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).path as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(path)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).user_data as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(user_data)
+        )
+    );
+    assert_eq!(
+        unsafe {
+            &(*(::std::ptr::null::<sfetch_response_t>())).fetched_offset as *const _ as usize
+        },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(fetched_offset)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).fetched_size as *const _ as usize },
+        44usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(fetched_size)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).buffer_ptr as *const _ as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(buffer_ptr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_response_t>())).buffer_size as *const _ as usize },
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_response_t),
+            "::",
+            stringify!(buffer_size)
+        )
+    );
+}
+impl Default for sfetch_response_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sfetch_request_t {
+    pub _start_canary: u32,
+    pub channel: u32,
+    pub path: *const ::std::os::raw::c_char,
+    pub callback: ::std::option::Option<unsafe extern "C" fn(arg1: *const sfetch_response_t)>,
+    pub buffer_ptr: *mut ::std::os::raw::c_void,
+    pub buffer_size: u32,
+    pub chunk_size: u32,
+    pub user_data_ptr: *const ::std::os::raw::c_void,
+    pub user_data_size: u32,
+    pub _end_canary: u32,
+}
+#[test]
+fn bindgen_test_layout_sfetch_request_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sfetch_request_t>(),
+        56usize,
+        concat!("Size of: ", stringify!(sfetch_request_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sfetch_request_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sfetch_request_t))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>()))._start_canary as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(_start_canary)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).channel as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(channel)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).path as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(path)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).callback as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(callback)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).buffer_ptr as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(buffer_ptr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).buffer_size as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(buffer_size)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).chunk_size as *const _ as usize },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(chunk_size)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).user_data_ptr as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(user_data_ptr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>())).user_data_size as *const _ as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(user_data_size)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<sfetch_request_t>()))._end_canary as *const _ as usize },
+        52usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(sfetch_request_t),
+            "::",
+            stringify!(_end_canary)
+        )
+    );
+}
+impl Default for sfetch_request_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+extern "C" {
+    pub fn sfetch_setup(desc: *const sfetch_desc_t);
+}
+extern "C" {
+    pub fn sfetch_shutdown();
+}
+extern "C" {
+    pub fn sfetch_valid() -> bool;
+}
+extern "C" {
+    pub fn sfetch_desc() -> sfetch_desc_t;
+}
+extern "C" {
+    pub fn sfetch_max_userdata_bytes() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn sfetch_max_path() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn sfetch_send(request: *const sfetch_request_t) -> sfetch_handle_t;
+}
+extern "C" {
+    pub fn sfetch_handle_valid(h: sfetch_handle_t) -> bool;
+}
+extern "C" {
+    pub fn sfetch_dowork();
+}
+extern "C" {
+    pub fn sfetch_bind_buffer(
+        h: sfetch_handle_t,
+        buffer_ptr: *mut ::std::os::raw::c_void,
+        buffer_size: u32,
+    );
+}
+extern "C" {
+    pub fn sfetch_unbind_buffer(h: sfetch_handle_t) -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn sfetch_cancel(h: sfetch_handle_t);
+}
+extern "C" {
+    pub fn sfetch_pause(h: sfetch_handle_t);
+}
+extern "C" {
+    pub fn sfetch_continue(h: sfetch_handle_t);
+}
@@ -0,0 +1,83 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_gfx_imgui.h](https://github.com/floooh/sokol/blob/master/util/sokol_gfx_imgui.h)
+
+#![allow(warnings)]
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sg_imgui_t {
+    _unused: [u8; 0],
+}
+extern "C" {
+    pub fn sg_imgui_init(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_discard(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_buffers_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_images_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_shaders_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_pipelines_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_passes_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_capture_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_capabilities_content(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_buffers_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_images_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_shaders_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_pipelines_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_passes_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_capture_window(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn sg_imgui_draw_capabilities_window(ctx: *mut sg_imgui_t);
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum rokol_sg_imgui_window {
+    ROKOL_SG_IMGUI_WINDOW_BUFFERS = 0,
+    ROKOL_SG_IMGUI_WINDOW_IMAGES = 1,
+    ROKOL_SG_IMGUI_WINDOW_SHADERS = 2,
+    ROKOL_SG_IMGUI_WINDOW_PIPELINES = 3,
+    ROKOL_SG_IMGUI_WINDOW_PASSES = 4,
+    ROKOL_SG_IMGUI_WINDOW_CAPTURE = 5,
+    ROKOL_SG_IMGUI_WINDOW_CAPABILITIES = 6,
+}
+extern "C" {
+    pub fn rokol_sg_imgui_alloc() -> *mut sg_imgui_t;
+}
+extern "C" {
+    pub fn rokol_sg_imgui_dealloc(ctx: *mut sg_imgui_t);
+}
+extern "C" {
+    pub fn rokol_sg_imgui_open(ctx: *mut sg_imgui_t, window: rokol_sg_imgui_window) -> *mut bool;
+}
@@ -0,0 +1,315 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_gl.h](https://github.com/floooh/sokol/blob/master/util/sokol_gl.h)
+
+#![allow(warnings)]
+
+use crate::gfx::*;
+
+pub const SOKOL_GL_INCLUDED: u32 = 1;
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sgl_pipeline {
+    pub id: u32,
+}
+#[test]
+fn bindgen_test_layout_sgl_pipeline() {
+    assert_eq!(
+        ::std::mem::size_of::<sgl_pipeline>(),
+        4usize,
+        concat!("Size of: ", stringify!(sgl_pipeline))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sgl_pipeline>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sgl_pipeline))
+    );
+}
+#[repr(u32)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum sgl_error_t {
+    SGL_NO_ERROR = 0,
+    SGL_ERROR_VERTICES_FULL = 1,
+    SGL_ERROR_UNIFORMS_FULL = 2,
+    SGL_ERROR_COMMANDS_FULL = 3,
+    SGL_ERROR_STACK_OVERFLOW = 4,
+    SGL_ERROR_STACK_UNDERFLOW = 5,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sgl_desc_t {
+    pub max_vertices: ::std::os::raw::c_int,
+    pub max_commands: ::std::os::raw::c_int,
+    pub pipeline_pool_size: ::std::os::raw::c_int,
+    pub color_format: sg_pixel_format,
+    pub depth_format: sg_pixel_format,
+    pub sample_count: ::std::os::raw::c_int,
+    pub face_winding: sg_face_winding,
+}
+#[test]
+fn bindgen_test_layout_sgl_desc_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sgl_desc_t>(),
+        28usize,
+        concat!("Size of: ", stringify!(sgl_desc_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sgl_desc_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sgl_desc_t))
+    );
+}
+impl Default for sgl_desc_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+extern "C" {
+    pub fn sgl_setup(desc: *const sgl_desc_t);
+}
+extern "C" {
+    pub fn sgl_shutdown();
+}
+extern "C" {
+    pub fn sgl_error() -> sgl_error_t;
+}
+extern "C" {
+    pub fn sgl_defaults();
+}
+extern "C" {
+    pub fn sgl_rad(deg: f32) -> f32;
+}
+extern "C" {
+    pub fn sgl_deg(rad: f32) -> f32;
+}
+extern "C" {
+    pub fn sgl_make_pipeline(desc: *const sg_pipeline_desc) -> sgl_pipeline;
+}
+extern "C" {
+    pub fn sgl_destroy_pipeline(pip: sgl_pipeline);
+}
+extern "C" {
+    pub fn sgl_viewport(
+        x: ::std::os::raw::c_int,
+        y: ::std::os::raw::c_int,
+        w: ::std::os::raw::c_int,
+        h: ::std::os::raw::c_int,
+        origin_top_left: bool,
+    );
+}
+extern "C" {
+    pub fn sgl_viewportf(x: f32, y: f32, w: f32, h: f32, origin_top_left: bool);
+}
+extern "C" {
+    pub fn sgl_scissor_rect(
+        x: ::std::os::raw::c_int,
+        y: ::std::os::raw::c_int,
+        w: ::std::os::raw::c_int,
+        h: ::std::os::raw::c_int,
+        origin_top_left: bool,
+    );
+}
+extern "C" {
+    pub fn sgl_scissor_rectf(x: f32, y: f32, w: f32, h: f32, origin_top_left: bool);
+}
+extern "C" {
+    pub fn sgl_enable_texture();
+}
+extern "C" {
+    pub fn sgl_disable_texture();
+}
+extern "C" {
+    pub fn sgl_texture(img: sg_image);
+}
+extern "C" {
+    pub fn sgl_default_pipeline();
+}
+extern "C" {
+    pub fn sgl_load_pipeline(pip: sgl_pipeline);
+}
+extern "C" {
+    pub fn sgl_push_pipeline();
+}
+extern "C" {
+    pub fn sgl_pop_pipeline();
+}
+extern "C" {
+    pub fn sgl_matrix_mode_modelview();
+}
+extern "C" {
+    pub fn sgl_matrix_mode_projection();
+}
+extern "C" {
+    pub fn sgl_matrix_mode_texture();
+}
+extern "C" {
+    pub fn sgl_load_identity();
+}
+extern "C" {
+    pub fn sgl_load_matrix(m: *const f32);
+}
+extern "C" {
+    pub fn sgl_load_transpose_matrix(m: *const f32);
+}
+extern "C" {
+    pub fn sgl_mult_matrix(m: *const f32);
+}
+extern "C" {
+    pub fn sgl_mult_transpose_matrix(m: *const f32);
+}
+extern "C" {
+    pub fn sgl_rotate(angle_rad: f32, x: f32, y: f32, z: f32);
+}
+extern "C" {
+    pub fn sgl_scale(x: f32, y: f32, z: f32);
+}
+extern "C" {
+    pub fn sgl_translate(x: f32, y: f32, z: f32);
+}
+extern "C" {
+    pub fn sgl_frustum(l: f32, r: f32, b: f32, t: f32, n: f32, f: f32);
+}
+extern "C" {
+    pub fn sgl_ortho(l: f32, r: f32, b: f32, t: f32, n: f32, f: f32);
+}
+extern "C" {
+    pub fn sgl_perspective(fov_y: f32, aspect: f32, z_near: f32, z_far: f32);
+}
+extern "C" {
+    pub fn sgl_lookat(
+        eye_x: f32,
+        eye_y: f32,
+        eye_z: f32,
+        center_x: f32,
+        center_y: f32,
+        center_z: f32,
+        up_x: f32,
+        up_y: f32,
+        up_z: f32,
+    );
+}
+extern "C" {
+    pub fn sgl_push_matrix();
+}
+extern "C" {
+    pub fn sgl_pop_matrix();
+}
+extern "C" {
+    pub fn sgl_t2f(u: f32, v: f32);
+}
+extern "C" {
+    pub fn sgl_c3f(r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sgl_c4f(r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sgl_c3b(r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sgl_c4b(r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sgl_c1i(rgba: u32);
+}
+extern "C" {
+    pub fn sgl_begin_points();
+}
+extern "C" {
+    pub fn sgl_begin_lines();
+}
+extern "C" {
+    pub fn sgl_begin_line_strip();
+}
+extern "C" {
+    pub fn sgl_begin_triangles();
+}
+extern "C" {
+    pub fn sgl_begin_triangle_strip();
+}
+extern "C" {
+    pub fn sgl_begin_quads();
+}
+extern "C" {
+    pub fn sgl_v2f(x: f32, y: f32);
+}
+extern "C" {
+    pub fn sgl_v3f(x: f32, y: f32, z: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f(x: f32, y: f32, u: f32, v: f32);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f(x: f32, y: f32, z: f32, u: f32, v: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_c3f(x: f32, y: f32, r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_c3b(x: f32, y: f32, r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sgl_v2f_c4f(x: f32, y: f32, r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_c4b(x: f32, y: f32, r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sgl_v2f_c1i(x: f32, y: f32, rgba: u32);
+}
+extern "C" {
+    pub fn sgl_v3f_c3f(x: f32, y: f32, z: f32, r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sgl_v3f_c3b(x: f32, y: f32, z: f32, r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sgl_v3f_c4f(x: f32, y: f32, z: f32, r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sgl_v3f_c4b(x: f32, y: f32, z: f32, r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sgl_v3f_c1i(x: f32, y: f32, z: f32, rgba: u32);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f_c3f(x: f32, y: f32, u: f32, v: f32, r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f_c3b(x: f32, y: f32, u: f32, v: f32, r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f_c4f(x: f32, y: f32, u: f32, v: f32, r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f_c4b(x: f32, y: f32, u: f32, v: f32, r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sgl_v2f_t2f_c1i(x: f32, y: f32, u: f32, v: f32, rgba: u32);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f_c3f(x: f32, y: f32, z: f32, u: f32, v: f32, r: f32, g: f32, b: f32);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f_c3b(x: f32, y: f32, z: f32, u: f32, v: f32, r: u8, g: u8, b: u8);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f_c4f(x: f32, y: f32, z: f32, u: f32, v: f32, r: f32, g: f32, b: f32, a: f32);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f_c4b(x: f32, y: f32, z: f32, u: f32, v: f32, r: u8, g: u8, b: u8, a: u8);
+}
+extern "C" {
+    pub fn sgl_v3f_t2f_c1i(x: f32, y: f32, z: f32, u: f32, v: f32, rgba: u32);
+}
+extern "C" {
+    pub fn sgl_end();
+}
+extern "C" {
+    pub fn sgl_draw();
+}
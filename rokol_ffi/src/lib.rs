@@ -29,6 +29,30 @@ pub mod app;
 #[cfg(feature = "impl-gfx")]
 pub mod gfx;
 
+#[cfg(feature = "impl-time")]
+pub mod time;
+
+#[cfg(feature = "impl-audio")]
+pub mod audio;
+
+#[cfg(feature = "impl-fetch")]
+pub mod fetch;
+
+#[cfg(feature = "impl-args")]
+pub mod args;
+
+#[cfg(feature = "impl-gl")]
+pub mod gl;
+
+#[cfg(feature = "impl-debugtext")]
+pub mod debugtext;
+
+#[cfg(feature = "impl-shape")]
+pub mod shape;
+
+#[cfg(feature = "impl-gfx-imgui")]
+pub mod gfx_imgui;
+
 #[cfg(all(feature = "impl-app", feature = "impl-gfx"))]
 pub mod glue {
     //! FFI to [`sokol_glue.h`](https://github.com/floooh/sokol/blob/master/sokol_glue.h)
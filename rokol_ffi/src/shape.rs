@@ -0,0 +1,404 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_shape.h](https://github.com/floooh/sokol/blob/master/util/sokol_shape.h)
+
+#![allow(warnings)]
+
+use crate::gfx::*;
+
+pub const SOKOL_SHAPE_INCLUDED: u32 = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_range {
+    pub ptr: *const ::std::os::raw::c_void,
+    pub size: size_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_range() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_range>(),
+        16usize,
+        concat!("Size of: ", stringify!(sshape_range))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_range>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sshape_range))
+    );
+}
+impl Default for sshape_range {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_mat4_t {
+    pub m: [[f32; 4usize]; 4usize],
+}
+#[test]
+fn bindgen_test_layout_sshape_mat4_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_mat4_t>(),
+        64usize,
+        concat!("Size of: ", stringify!(sshape_mat4_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_mat4_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_mat4_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_vertex_t {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub normal: u32,
+    pub u: u16,
+    pub v: u16,
+    pub color: u32,
+}
+#[test]
+fn bindgen_test_layout_sshape_vertex_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_vertex_t>(),
+        24usize,
+        concat!("Size of: ", stringify!(sshape_vertex_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_vertex_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_vertex_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_element_range_t {
+    pub base_element: ::std::os::raw::c_int,
+    pub num_elements: ::std::os::raw::c_int,
+}
+#[test]
+fn bindgen_test_layout_sshape_element_range_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_element_range_t>(),
+        8usize,
+        concat!("Size of: ", stringify!(sshape_element_range_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_element_range_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_element_range_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_sizes_item_t {
+    pub num: u32,
+    pub size: u32,
+}
+#[test]
+fn bindgen_test_layout_sshape_sizes_item_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_sizes_item_t>(),
+        8usize,
+        concat!("Size of: ", stringify!(sshape_sizes_item_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_sizes_item_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_sizes_item_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_sizes_t {
+    pub vertices: sshape_sizes_item_t,
+    pub indices: sshape_sizes_item_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_sizes_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_sizes_t>(),
+        16usize,
+        concat!("Size of: ", stringify!(sshape_sizes_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_sizes_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_sizes_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_buffer_item_t {
+    pub buffer: sshape_range,
+    pub data_size: size_t,
+    pub shape_offset: size_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_buffer_item_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_buffer_item_t>(),
+        32usize,
+        concat!("Size of: ", stringify!(sshape_buffer_item_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_buffer_item_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sshape_buffer_item_t))
+    );
+}
+impl Default for sshape_buffer_item_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct sshape_buffer_t {
+    pub valid: bool,
+    pub vertices: sshape_buffer_item_t,
+    pub indices: sshape_buffer_item_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_buffer_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_buffer_t>(),
+        72usize,
+        concat!("Size of: ", stringify!(sshape_buffer_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_buffer_t>(),
+        8usize,
+        concat!("Alignment of ", stringify!(sshape_buffer_t))
+    );
+}
+impl Default for sshape_buffer_t {
+    fn default() -> Self {
+        let mut s = ::std::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::std::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_plane_t {
+    pub width: f32,
+    pub depth: f32,
+    pub tiles: u16,
+    pub color: u32,
+    pub random_colors: bool,
+    pub merge: bool,
+    pub transform: sshape_mat4_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_plane_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_plane_t>(),
+        84usize,
+        concat!("Size of: ", stringify!(sshape_plane_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_plane_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_plane_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_box_t {
+    pub width: f32,
+    pub height: f32,
+    pub depth: f32,
+    pub tiles: u16,
+    pub color: u32,
+    pub random_colors: bool,
+    pub merge: bool,
+    pub transform: sshape_mat4_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_box_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_box_t>(),
+        88usize,
+        concat!("Size of: ", stringify!(sshape_box_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_box_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_box_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_sphere_t {
+    pub radius: f32,
+    pub slices: u16,
+    pub stacks: u16,
+    pub color: u32,
+    pub random_colors: bool,
+    pub merge: bool,
+    pub transform: sshape_mat4_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_sphere_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_sphere_t>(),
+        80usize,
+        concat!("Size of: ", stringify!(sshape_sphere_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_sphere_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_sphere_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_cylinder_t {
+    pub radius: f32,
+    pub height: f32,
+    pub slices: u16,
+    pub stacks: u16,
+    pub color: u32,
+    pub random_colors: bool,
+    pub merge: bool,
+    pub transform: sshape_mat4_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_cylinder_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_cylinder_t>(),
+        84usize,
+        concat!("Size of: ", stringify!(sshape_cylinder_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_cylinder_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_cylinder_t))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct sshape_torus_t {
+    pub radius: f32,
+    pub ring_radius: f32,
+    pub sides: u16,
+    pub rings: u16,
+    pub color: u32,
+    pub random_colors: bool,
+    pub merge: bool,
+    pub transform: sshape_mat4_t,
+}
+#[test]
+fn bindgen_test_layout_sshape_torus_t() {
+    assert_eq!(
+        ::std::mem::size_of::<sshape_torus_t>(),
+        84usize,
+        concat!("Size of: ", stringify!(sshape_torus_t))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<sshape_torus_t>(),
+        4usize,
+        concat!("Alignment of ", stringify!(sshape_torus_t))
+    );
+}
+extern "C" {
+    pub fn sshape_build_plane(
+        buf: *const sshape_buffer_t,
+        params: *const sshape_plane_t,
+    ) -> sshape_buffer_t;
+}
+extern "C" {
+    pub fn sshape_build_box(
+        buf: *const sshape_buffer_t,
+        params: *const sshape_box_t,
+    ) -> sshape_buffer_t;
+}
+extern "C" {
+    pub fn sshape_build_sphere(
+        buf: *const sshape_buffer_t,
+        params: *const sshape_sphere_t,
+    ) -> sshape_buffer_t;
+}
+extern "C" {
+    pub fn sshape_build_cylinder(
+        buf: *const sshape_buffer_t,
+        params: *const sshape_cylinder_t,
+    ) -> sshape_buffer_t;
+}
+extern "C" {
+    pub fn sshape_build_torus(
+        buf: *const sshape_buffer_t,
+        params: *const sshape_torus_t,
+    ) -> sshape_buffer_t;
+}
+extern "C" {
+    pub fn sshape_plane_sizes(tiles: u32) -> sshape_sizes_t;
+}
+extern "C" {
+    pub fn sshape_box_sizes(tiles: u32) -> sshape_sizes_t;
+}
+extern "C" {
+    pub fn sshape_sphere_sizes(slices: u32, stacks: u32) -> sshape_sizes_t;
+}
+extern "C" {
+    pub fn sshape_cylinder_sizes(slices: u32, stacks: u32) -> sshape_sizes_t;
+}
+extern "C" {
+    pub fn sshape_torus_sizes(sides: u32, rings: u32) -> sshape_sizes_t;
+}
+extern "C" {
+    pub fn sshape_element_range(buf: *const sshape_buffer_t) -> sshape_element_range_t;
+}
+extern "C" {
+    pub fn sshape_vertex_buffer_desc(buf: *const sshape_buffer_t) -> sg_buffer_desc;
+}
+extern "C" {
+    pub fn sshape_index_buffer_desc(buf: *const sshape_buffer_t) -> sg_buffer_desc;
+}
+extern "C" {
+    pub fn sshape_buffer_layout_desc() -> sg_buffer_layout_desc;
+}
+extern "C" {
+    pub fn sshape_position_attr_desc() -> sg_vertex_attr_desc;
+}
+extern "C" {
+    pub fn sshape_normal_attr_desc() -> sg_vertex_attr_desc;
+}
+extern "C" {
+    pub fn sshape_texcoord_attr_desc() -> sg_vertex_attr_desc;
+}
+extern "C" {
+    pub fn sshape_color_attr_desc() -> sg_vertex_attr_desc;
+}
+extern "C" {
+    pub fn sshape_color_4f(r: f32, g: f32, b: f32, a: f32) -> u32;
+}
+extern "C" {
+    pub fn sshape_color_3f(r: f32, g: f32, b: f32) -> u32;
+}
+extern "C" {
+    pub fn sshape_color_4b(r: u8, g: u8, b: u8, a: u8) -> u32;
+}
+extern "C" {
+    pub fn sshape_color_3b(r: u8, g: u8, b: u8) -> u32;
+}
+extern "C" {
+    pub fn sshape_mat4(m: *const f32) -> sshape_mat4_t;
+}
+extern "C" {
+    pub fn sshape_mat4_transpose(m: *const f32) -> sshape_mat4_t;
+}
@@ -0,0 +1,37 @@
+/* automatically generated by rust-bindgen 0.59.2 */
+
+//! Rust FFI to [sokol_time.h](https://github.com/floooh/sokol/blob/master/sokol_time.h)
+
+#![allow(warnings)]
+
+pub const SOKOL_TIME_INCLUDED: u32 = 1;
+extern "C" {
+    pub fn stm_setup();
+}
+extern "C" {
+    pub fn stm_now() -> u64;
+}
+extern "C" {
+    pub fn stm_diff(new_ticks: u64, old_ticks: u64) -> u64;
+}
+extern "C" {
+    pub fn stm_since(start_ticks: u64) -> u64;
+}
+extern "C" {
+    pub fn stm_laptime(last_time: *mut u64) -> u64;
+}
+extern "C" {
+    pub fn stm_round_to_common_refresh_rate(frame_ticks: u64) -> u64;
+}
+extern "C" {
+    pub fn stm_sec(ticks: u64) -> f64;
+}
+extern "C" {
+    pub fn stm_ms(ticks: u64) -> f64;
+}
+extern "C" {
+    pub fn stm_us(ticks: u64) -> f64;
+}
+extern "C" {
+    pub fn stm_ns(ticks: u64) -> f64;
+}
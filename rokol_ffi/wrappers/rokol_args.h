@@ -0,0 +1,4 @@
+//! File for generating Rust FFI
+
+// search from include path (-I flag)
+#include "sokol_args.h"
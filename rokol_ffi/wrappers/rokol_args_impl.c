@@ -0,0 +1,6 @@
+//! File for compiling `sokol_args.h`
+
+#define SOKOL_ARGS_IMPL
+
+// search from include path (-I flag)
+#include "sokol_args.h"
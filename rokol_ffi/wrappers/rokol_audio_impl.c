@@ -0,0 +1,6 @@
+//! File for compiling `sokol_audio.h`
+
+#define SOKOL_AUDIO_IMPL
+
+// search from include path (-I flag)
+#include "sokol_audio.h"
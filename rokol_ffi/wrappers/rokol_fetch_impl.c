@@ -0,0 +1,6 @@
+//! File for compiling `sokol_fetch.h`
+
+#define SOKOL_FETCH_IMPL
+
+// search from include path (-I flag)
+#include "sokol_fetch.h"
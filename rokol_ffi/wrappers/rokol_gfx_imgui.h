@@ -0,0 +1,53 @@
+//! File for generating Rust FFI
+
+#define SOKOL_NO_ENTRY
+#define SOKOL_NO_DEPRECATED
+#define SOKOL_TRACE_HOOKS
+
+#include <stdbool.h>
+
+// search from include path (-I flag)
+#include "sokol_gfx.h"
+
+#if !defined(SOKOL_GFX_IMGUI_INCLUDED)
+// `sg_imgui_t` depends on the imgui headers, so it's opaque to Rust and allocated in C
+typedef struct sg_imgui_t sg_imgui_t;
+
+void sg_imgui_init(sg_imgui_t* ctx);
+void sg_imgui_discard(sg_imgui_t* ctx);
+void sg_imgui_draw(sg_imgui_t* ctx);
+
+void sg_imgui_draw_buffers_content(sg_imgui_t* ctx);
+void sg_imgui_draw_images_content(sg_imgui_t* ctx);
+void sg_imgui_draw_shaders_content(sg_imgui_t* ctx);
+void sg_imgui_draw_pipelines_content(sg_imgui_t* ctx);
+void sg_imgui_draw_passes_content(sg_imgui_t* ctx);
+void sg_imgui_draw_capture_content(sg_imgui_t* ctx);
+void sg_imgui_draw_capabilities_content(sg_imgui_t* ctx);
+
+void sg_imgui_draw_buffers_window(sg_imgui_t* ctx);
+void sg_imgui_draw_images_window(sg_imgui_t* ctx);
+void sg_imgui_draw_shaders_window(sg_imgui_t* ctx);
+void sg_imgui_draw_pipelines_window(sg_imgui_t* ctx);
+void sg_imgui_draw_passes_window(sg_imgui_t* ctx);
+void sg_imgui_draw_capture_window(sg_imgui_t* ctx);
+void sg_imgui_draw_capabilities_window(sg_imgui_t* ctx);
+#endif
+
+// helpers implemented in `rokol_gfx_imgui_impl.c`
+
+typedef enum rokol_sg_imgui_window {
+    ROKOL_SG_IMGUI_WINDOW_BUFFERS,
+    ROKOL_SG_IMGUI_WINDOW_IMAGES,
+    ROKOL_SG_IMGUI_WINDOW_SHADERS,
+    ROKOL_SG_IMGUI_WINDOW_PIPELINES,
+    ROKOL_SG_IMGUI_WINDOW_PASSES,
+    ROKOL_SG_IMGUI_WINDOW_CAPTURE,
+    ROKOL_SG_IMGUI_WINDOW_CAPABILITIES,
+} rokol_sg_imgui_window;
+
+// Allocates a zero-initialized `sg_imgui_t`
+sg_imgui_t* rokol_sg_imgui_alloc(void);
+void rokol_sg_imgui_dealloc(sg_imgui_t* ctx);
+// Pointer to the `open` flag of a window
+bool* rokol_sg_imgui_open(sg_imgui_t* ctx, rokol_sg_imgui_window window);
@@ -0,0 +1,44 @@
+//! File for compiling `sokol_gfx_imgui.h`
+
+// Sokol render flag is selected and defined by `build.rs`
+// #define SOKOL_<RENDERER>
+
+// `sokol_gfx.h` is implemented in another file
+#define SOKOL_GFX_IMGUI_IMPL
+
+#define SOKOL_NO_ENTRY
+#define SOKOL_NO_DEPRECATED
+#define SOKOL_TRACE_HOOKS
+
+#include <stdlib.h>
+
+// `cimgui.h` of `imgui-sys` (the include path is set by `build.rs`)
+#define CIMGUI_DEFINE_ENUMS_AND_STRUCTS
+#include "cimgui.h"
+
+// search from include path (-I flag)
+#include "sokol_gfx.h"
+#include "sokol_gfx_imgui.h"
+
+#include "rokol_gfx_imgui.h"
+
+sg_imgui_t* rokol_sg_imgui_alloc(void) {
+    return (sg_imgui_t*)calloc(1, sizeof(sg_imgui_t));
+}
+
+void rokol_sg_imgui_dealloc(sg_imgui_t* ctx) {
+    free(ctx);
+}
+
+bool* rokol_sg_imgui_open(sg_imgui_t* ctx, rokol_sg_imgui_window window) {
+    switch (window) {
+        case ROKOL_SG_IMGUI_WINDOW_BUFFERS: return &ctx->buffers.open;
+        case ROKOL_SG_IMGUI_WINDOW_IMAGES: return &ctx->images.open;
+        case ROKOL_SG_IMGUI_WINDOW_SHADERS: return &ctx->shaders.open;
+        case ROKOL_SG_IMGUI_WINDOW_PIPELINES: return &ctx->pipelines.open;
+        case ROKOL_SG_IMGUI_WINDOW_PASSES: return &ctx->passes.open;
+        case ROKOL_SG_IMGUI_WINDOW_CAPTURE: return &ctx->capture.open;
+        case ROKOL_SG_IMGUI_WINDOW_CAPABILITIES: return &ctx->caps.open;
+        default: return NULL;
+    }
+}
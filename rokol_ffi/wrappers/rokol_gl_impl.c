@@ -0,0 +1,15 @@
+//! File for compiling `sokol_gl.h`
+
+// Sokol render flag is selected and defined by `build.rs`
+// #define SOKOL_<RENDERER>
+
+// `sokol_gfx.h` is implemented in another file
+#define SOKOL_GL_IMPL
+
+#define SOKOL_NO_ENTRY
+#define SOKOL_NO_DEPRECATED
+#define SOKOL_TRACE_HOOKS
+
+// search from include path (-I flag)
+#include "sokol_gfx.h"
+#include "sokol_gl.h"
@@ -4,8 +4,6 @@
 // #define SOKOL_<RENDERER>
 
 #define SOKOL_IMPL
-#define SOKOL_IMGUI_IMPL
-#define SOKOL_IMGUI_GFX_IMPL
 
 #define SOKOL_NO_ENTRY
 #define SOKOL_NO_DEPRECATED
@@ -0,0 +1,9 @@
+//! File for generating Rust FFI
+
+#define SOKOL_NO_ENTRY
+#define SOKOL_NO_DEPRECATED
+#define SOKOL_TRACE_HOOKS
+
+// search from include path (-I flag)
+#include "sokol_gfx.h"
+#include "sokol_shape.h"
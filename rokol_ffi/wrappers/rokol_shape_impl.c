@@ -0,0 +1,12 @@
+//! File for compiling `sokol_shape.h`
+
+// `sokol_gfx.h` is implemented in another file
+#define SOKOL_SHAPE_IMPL
+
+#define SOKOL_NO_ENTRY
+#define SOKOL_NO_DEPRECATED
+#define SOKOL_TRACE_HOOKS
+
+// search from include path (-I flag)
+#include "sokol_gfx.h"
+#include "sokol_shape.h"
@@ -0,0 +1,6 @@
+//! File for compiling `sokol_time.h`
+
+#define SOKOL_TIME_IMPL
+
+// search from include path (-I flag)
+#include "sokol_time.h"